@@ -0,0 +1,424 @@
+# 英文文案
+# key和zh-CN.ftl一致，另外包含命令行帮助(cli-*)
+
+## 通用
+login-success = Logged in!
+login-failed = Login failed, please check your account or try again
+books-fetch-success = Books fetched
+books-cache-missing = No cached books, run ytool pull first
+books-read-failed = Failed to read book info
+book-not-found = Book not found, run ytool ls to list all books
+output-dir-missing = Output directory does not exist, run ytool pull first
+push-map-parse-failed = Failed to parse the push records
+output-dir-create-failed = Failed to create the output directory
+file-write-failed = Failed to write file: { $file }
+file-create-failed = Failed to create file
+star-link = Give it a ⭐️
+
+## 日志
+log-file-create-failed = Failed to create the log file
+log-init-failed = Failed to initialize logging
+log-dump-failed = Failed to write intermediate data: { $file }
+
+## 网络请求和缓存
+request-cookies-expired = Cookies have expired, please clear the cache and run again
+request-failed = Request failed: { $url }
+request-upload-failed = Failed to upload file: { $file }
+cache-dir-create-failed = Failed to create the cache directory
+cache-write-failed = Failed to write the cache
+
+## 交互
+inquiry-select-books = Select books
+inquiry-select-books-help = Space to select/deselect, ⬆ ⬇ to move
+inquiry-no-book-selected = No book selected, exiting
+inquiry-skip = Skip files that already exist locally?
+inquiry-select-failed = Selection failed, exiting
+inquiry-line-break = Keep Yuque line break tags?
+inquiry-line-break-help = </br> is handled differently across platforms, keep it if you need it
+inquiry-layout = How should docs with children be written?
+inquiry-layout-sibling = Sibling file (a.md + a/)
+inquiry-layout-index = Directory index (a/index.md)
+inquiry-layout-readme = Directory readme (a/README.md)
+inquiry-layout-help = Static site generators usually expect index.md or README.md
+inquiry-hidden = Export hidden/draft docs?
+inquiry-hidden-help = Docs invisible in the table of contents; skipped ones are listed in the export report
+inquiry-books-read-failed = Failed to read the books file, exiting
+inquiry-username = yuque username:
+inquiry-username-failed = Failed to read username, exiting
+inquiry-password = yuque password:
+inquiry-password-failed = Failed to read password, exiting
+
+## 下载进度
+progress-done = [{ $done }/{ $total }] done { $item } ({ $size })
+progress-failed = [{ $done }/{ $total }] failed { $item }
+progress-docs = Docs
+progress-resources = Resources
+progress-eta = left
+progress-group-done = done
+progress-total-status = downloaded { $bytes }, failed { $failed }
+progress-summary = { $label } download summary:
+progress-col-group = Group
+progress-col-success = Success
+progress-col-failed = Failed
+progress-col-skipped = Skipped
+progress-col-size = Size
+progress-row-total = Total
+progress-elapsed = Elapsed { $time }
+
+## 浏览和搜索
+browse-dir-not-found = Directory not found, please check the path
+browse-search-no-match = No docs match "{ $query }"
+browse-indexing = Building the full-text index...
+browse-index-done = Index updated: { $added } added, { $updated } updated, { $removed } removed
+browse-index-failed = Failed to build the full-text index
+browse-index-missing = No full-text index yet, run ytool index first
+browse-grep-no-match = No docs contain "{ $query }"
+filter-invalid = Invalid filter
+
+## 合并导出
+bundle-book-not-found = Book "{ $book }" not found, run ytool ls to list books
+bundle-book-empty = Book "{ $book }" has no docs to export
+bundle-start = Bundling book "{ $book }", { $count } docs
+bundle-epub-failed = Failed to build EPUB for book "{ $book }"
+bundle-exported = Exported { $file }
+bundle-body-failed = Failed to fetch doc content: { $title }
+bundle-image-failed = Failed to download image, keeping the original URL: { $url }
+
+## 导出过滤
+filter-glob-invalid = Invalid path pattern: { $glob }
+filter-date-invalid = Invalid date: { $date }, expected YYYY-MM-DD
+filter-title-invalid = Invalid title regex: { $title }
+filter-summary = Filters:
+filter-summary-toc-range = TOC range: { $value }
+filter-summary-include = Include paths: { $value }
+filter-summary-exclude = Exclude paths: { $value }
+filter-summary-updated-since = Updated since: { $value }
+filter-summary-author = Author: { $value }
+filter-summary-title = Title matches: { $value }
+filter-summary-max-depth = Max depth: { $value }
+
+## git仓库
+git-not-found = git not found, please install git first
+git-init = Initialized { $dir } as a git repository
+git-exec-error = Failed to run git
+git-exec-failed = git command failed
+git-commit-doc-added = Add { $path }
+git-commit-doc-updated = Update { $path }
+git-summary = ytool pull: { $added } added, { $modified } modified, { $deleted } deleted
+git-summary-added = Added:
+git-summary-modified = Modified:
+git-summary-deleted = Deleted:
+git-summary-assets = { $count } attachments changed
+
+## 复制知识库
+mirror-namespace-invalid = Books must be given as user/slug
+mirror-same-book = Source and target books must differ
+mirror-start = Mirroring book { $from } -> { $to }
+mirror-plan = Planned: { $created } to create, { $updated } to update, { $exists } already exist
+mirror-failed-count = { $count } nodes failed to copy, run again to retry
+mirror-done = Mirror finished: { $created } created, { $updated } updated, { $exists } already existed
+mirror-toc-failed = Failed to fetch the source book's table of contents, check the book path and your access
+mirror-node-success = Copied { $path }
+mirror-node-failed = Failed to copy { $path }
+
+## 归档
+archive-format-unsupported = Only .tar.zst, .tzst and .zip archives are supported
+archive-create-failed = Failed to create the archive file
+archive-write-failed = Failed to write the archive file
+
+## 加密
+encrypt-recipient-invalid = Invalid public key, expected an X25519 key starting with age1
+encrypt-passphrase-empty = No passphrase entered
+encrypt-passphrase-prompt = Enter the encryption passphrase
+encrypt-passphrase-confirm = Enter the passphrase again
+encrypt-failed = Encryption failed
+encrypt-git-conflict = Encrypted exports cannot be committed to a git repository
+encrypt-passphrase-slow = Passphrase encryption takes about 1s per file, use public keys or --archive for large books
+decrypt-not-encrypted = Not an age encrypted file
+decrypt-identity-missing = The file is encrypted to a public key, specify the private key with --identity or the profile's identity_file
+decrypt-identity-invalid = Failed to read the identity file, expected a private key starting with AGE-SECRET-KEY-
+decrypt-failed = Decryption failed, wrong key or passphrase
+decrypt-no-files = No .age files found to decrypt
+decrypt-path-missing = File or directory does not exist
+decrypt-write-failed = Failed to write the decrypted file
+decrypt-file-failed = Failed to decrypt { $file }: { $reason }
+decrypt-done = Decryption finished, { $count } succeeded, { $failed } failed
+
+## 路径模板
+template-invalid = Invalid path template: { $template }, { $reason }
+template-outside = it must be a relative path without ..
+template-unclosed = unclosed brace
+template-unknown-variable = unknown variable { $name }, available variables: { $variables }
+template-not-unique = it must contain one of { $variables }, otherwise file names are bound to clash
+template-conflict = { $source } maps to the same path as another item: { $target }
+template-conflict-abort = The path template causes { $count } path conflicts, nothing was exported, please adjust the template
+pull-aborted = Export aborted
+template-logseq-ignored = Logseq pages and assets have fixed locations, doc and asset path templates are ignored
+template-asset-obsidian-only = The asset path template is only used by Obsidian, plain Markdown exports don't download attachments; remove asset or use --target obsidian
+
+## 导出计划
+plan-summary = { $total } items: { $download } to download, { $exists } existing skipped, { $hidden } hidden skipped, { $conflict } path conflicts, about { $secs } seconds
+
+## 推送
+push-dir-not-found = The directory to push does not exist
+push-start = Pushing { $dir } to book { $book }
+push-failed-count = { $count } docs failed to push, run push again to retry
+push-done = Push finished: { $created } created, { $updated } updated, { $skipped } unchanged
+push-dir-read-failed = Failed to read the directory to push
+push-node-success = Pushed { $path }
+push-node-failed = Failed to push { $path }
+push-map-save-failed = Failed to save the push records
+push-token-missing = Pushing needs a Yuque open API token, set token in the config file or the profile
+
+## 预览服务
+serve-dir-not-found = Docs directory does not exist, run ytool pull first
+serve-index-failed = Failed to index the docs, search is unavailable
+serve-port-in-use = Port is in use, choose another with --port
+serve-started = Preview server running at http://127.0.0.1:{ $port }, press Ctrl+C to stop
+serve-exited = Preview server exited unexpectedly
+
+## 更新
+upgrade-exe-not-found = Unable to locate the running executable
+upgrade-checking = Checking for a new version...
+upgrade-feed-failed = Failed to fetch release info
+upgrade-feed-parse-failed = Failed to parse release info
+upgrade-version-invalid = Unrecognized version number
+upgrade-up-to-date = Already up to date ({ $version })
+upgrade-found = New version { $latest } available, current version { $current }
+upgrade-no-asset = No package available for this platform
+upgrade-asset = Package: { $url }, run ytool upgrade to update
+upgrade-no-checksum = The package has no checksum file, update cancelled
+upgrade-checksum-download-failed = Failed to download the checksum file
+upgrade-checksum-missing = The checksum file has no entry for this package
+upgrade-downloading = Downloading { $name }
+upgrade-download-failed = Failed to download the package
+upgrade-checksum-mismatch = Package checksum mismatch, update cancelled
+upgrade-extract-failed = Failed to extract the package
+upgrade-replace-failed = Failed to replace the executable, check write permissions
+upgrade-done = Updated to { $version }
+
+## 定时同步
+watch-status-write-failed = Failed to write the status file
+watch-config-missing = Run ytool init first and fill in the books to sync (toc_range)
+watch-stopping = Received a stop signal, exiting after the current round
+watch-start = Syncing every { $secs } seconds, status in { $file }, press Ctrl+C to stop
+watch-stopped = Sync stopped
+watch-fetch-failed = Failed to fetch book info, will retry on the next check
+watch-changes = [{ $time }] { $added } added, { $updated } updated, { $deleted } deleted
+watch-doc-deleted = Doc deleted on Yuque, kept locally: { $title }
+watch-interval-invalid = Invalid interval, e.g. 30s, 10m, 1h
+watch-interval-too-large = Interval is too large
+watch-interval-zero = Interval must not be 0
+
+## webhook
+webhook-config-missing = Run ytool init first and fill in the books to export (toc_range)
+webhook-listen-failed = Invalid listen address or the port is in use
+webhook-started = Webhook server running at http://{ $listen }/webhook, press Ctrl+C to stop
+webhook-exited = Webhook server exited unexpectedly
+webhook-received = Received doc change: { $title } ({ $action })
+webhook-refresh-failed = Failed to refresh book info, these changes were not exported
+webhook-out-of-range = "{ $title }" is outside the export range, ignored
+webhook-book-missing = The book of "{ $title }" does not exist, ignored
+webhook-exported = Re-exported { $count } docs
+
+## 语雀接口
+yuque-books-fetching = Fetching books
+yuque-books-loading = Fetching book data, please wait...
+yuque-books-fetch-failed = Failed to fetch books
+yuque-books-fetch-failed-reason = Failed to fetch books: { $reason }
+
+## 笔记库
+vault-attachment-save-failed = Failed to save attachment: { $file }
+vault-attachment-download-failed = Failed to download attachment, keeping the original URL: { $url }
+
+## 命令补全
+completion-script-failed = Failed to write the completion script
+completion-man-failed = Failed to write the man page
+completion-man-dir-failed = Failed to create the man page directory
+completion-man-generate-failed = Failed to generate man pages
+completion-man-done = Man pages generated in { $dir }
+
+## 配置档案
+profile-not-found = Profile not found, add it with ytool profile add first
+profile-using = Using profile: { $name }
+profile-updated = Profile "{ $name }" updated
+profile-added = Profile "{ $name }" added
+profile-write-failed = Failed to write profiles
+profile-empty = No profiles yet
+profile-removed = Profile "{ $name }" removed, delete its cache directory manually
+profile-missing = Profile "{ $name }" does not exist
+profile-keygen-done = Private key of profile "{ $name }" saved to { $file }, keep a backup. Public key:
+profile-keygen-exists = Identity file { $file } already exists and will not be overwritten
+profile-keygen-failed = Failed to write the identity file
+profile-personal = personal
+profile-no-account = no account
+profile-list-item = { $name }  [{ $space }]  { $account }  cache: { $dir }
+
+## 初始化和缓存
+init-done = Config file initialized, see 👉{ $file }
+clear-failed = Failed to clear the cache
+clear-done = Cache cleared~
+clear-empty = No cache
+clear-kept-keys = Kept { $count } private key file(s) (identity.txt) in the cache directory
+
+## 导出
+report-file = export-report.md
+report-title = Export report
+pull-config = Export with the following settings:
+pull-config-books = Books: { $value }
+pull-config-skip = Skip existing: { $value }
+pull-config-line-break = Keep line breaks: { $value }
+pull-config-hidden = Export hidden docs: { $value }
+pull-dry-run = Export plan (--dry-run, no local files are changed):
+pull-dry-run-git = A real export will commit the changes to the git repository in { $dir }
+pull-dry-run-archive = The real export will be written to the archive { $file }, the output directory is left untouched
+pull-dry-run-no-cache = --dry-run plans from the cached book info only and never logs in, run ytool pull once first
+pull-missing-meta = { $count } docs lack update time/author info, run ytool clear and fetch again
+pull-filtered = { $count } docs do not match the filter and will not be exported
+pull-clean-dir-failed = Failed to clean up empty book directories
+pull-hidden-skipped = { $count } hidden/draft docs will be skipped, see the export report
+pull-conflict-skipped = { $count } docs share an output path with other docs and were skipped, see the export report
+pull-start = Exporting { $count } docs ({ $skipped } existing skipped), about { $secs } seconds
+pull-index-failed = Failed to update the full-text index, run ytool index --rebuild to rebuild it
+pull-git-failed = Failed to commit to the git repository: { $reason }
+pull-done = Export finished, { $count } docs exported. { $link }
+pull-archive-done = Archive written: { $files }
+pull-stale-docs = { $count } docs have been deleted on Yuque
+pull-missing-editor = { $count } docs lack last editor info and were added to the summary commit, run ytool clear and fetch again
+pull-git-doc-commits = Committed per doc to the git repository, { $count } commits
+pull-git-no-changes = No doc changes, nothing to commit
+pull-git-committed = Committed to the git repository: { $summary }
+pull-book-dir-failed = Failed to create the book directory
+pull-doc-failed = Failed to export doc: { $path }
+
+## 团队资源
+grd-start = Starting team resource download
+grd-fetching = Fetching team resources, make sure resource folders and file names contain no special characters~
+grd-base-info-success = Fetched team resource base info
+grd-dry-run = Download plan (--dry-run, no local files are changed):
+grd-host-missing = Please configure the team space host
+grd-dir-failed = Failed to create the resource directory
+grd-conflict = { $file } has the same name as another resource, skipped
+grd-sub-dir-failed = Failed to create resource directory { $dir }
+grd-download-failed = Failed to download { $file }, check the resource name for special characters
+grd-done = Team resource download finished, { $count } files downloaded~ { $link }
+grd-detail-fetching = Fetching resource info for { $title }
+grd-detail-success = Fetched resource info for { $title }
+grd-detail-failed = Failed to fetch resource info for { $title }
+grd-list-fetching = Fetching resource list for { $name }
+grd-list-success = Fetched resource list for { $name }
+grd-list-failed = Failed to fetch resource list for { $name }
+grd-info-success = Fetched team resource info
+
+## 桌面端
+gui-not-logged-in = Not logged in
+gui-login-failed = Login failed, please check your username and password
+gui-get-user-info-failed = Failed to get user info
+gui-get-books-failed = Failed to get books
+gui-get-docs-failed = Failed to get docs
+gui-export-failed = Export failed
+gui-cache-operation-failed = Cache operation failed
+gui-login-success = Logged in
+gui-personal-books = Personal books
+gui-team-books = Team books
+gui-all-books = All books
+gui-skip-hidden = hidden doc
+gui-skip-filtered = doc not matching the filter
+gui-export-skipped = Skipped { $reason }: { $title }
+
+## 命令行帮助
+# cli-子命令-about 为命令说明，cli-子命令-参数名 为参数帮助
+cli-about = Batch export tool for Yuque knowledge bases
+cli-profile = Use the given profile (account/space), see the profile command
+cli-verbose = Print debug info (request URLs, failed responses, etc.), -vv for more detail
+cli-log-file = Also write logs to this file as JSON, passwords and cookies are hidden
+cli-dump-dir = Write intermediate data such as doc trees to this directory for troubleshooting
+cli-lang = Language of messages, chosen from the LANG environment variable by default
+cli-pull-about = Fetch books (see the docs for the interactive flow)
+cli-pull-dry-run = Only print the export plan (download/skip/conflict and estimated time), no local files are changed
+cli-pull-include = Only export matching paths, glob format such as "book/folder/**", can be repeated
+cli-pull-exclude = Exclude matching paths, glob format, can be repeated
+cli-pull-updated-since = Only export docs updated after this date, e.g. 2023-09-01
+cli-pull-author = Only export docs by this author (Yuque login)
+cli-pull-title = Title regex
+cli-pull-max-depth = Maximum directory depth, the first level under a book is 1
+cli-pull-target = Export target, overrides target in the config file
+cli-pull-git = Commit the changes to a git repository in the output directory, initialized automatically if needed
+cli-pull-git-author = Commit each doc separately, authored by its last editor
+cli-pull-path-template = Doc path template such as "{book_slug}/{path}/{title}.{ext}", overrides path_template.doc in the config file
+cli-pull-archive = Export to an archive file (.tar.zst/.zip) with a manifest.json instead of the output directory
+cli-pull-split-by-book = Write one archive per book, named archive-book.ext
+cli-pull-encrypt = Encrypt the export with the profile's keys, or a passphrase when no public key is set
+cli-pull-recipients = Recipient public key (age1...), can be repeated, implies encryption
+cli-pull-passphrase = Encrypt with a passphrase read from YTOOL_PASSPHRASE, asked interactively if unset
+cli-down-about = Download any book
+cli-grd-about = Download team resources (group-resource-download)
+cli-grd-dry-run = Only print the download plan, no local files are changed
+cli-init-about = Initialize the tool config
+cli-clear-about = Clear the local cache
+cli-profile-about = Manage profiles (multiple accounts/spaces)
+cli-profile-add-about = Add a profile, one with the same name is overwritten
+cli-profile-add-name = Profile name
+cli-profile-add-host = Space host (e.g. https://xxx.yuque.com), leave empty for personal books
+cli-profile-add-username = Yuque account
+cli-profile-add-password = Yuque password, asked during login if empty
+cli-profile-add-token = Open API token, used by push and mirror
+cli-profile-add-output = Output directory, defaults to ./docs/{name}
+cli-profile-add-meta-dir = Cache directory, defaults to .meta/profiles/{name}
+cli-profile-add-recipients = Recipient public key (age1...) for encrypted exports, can be repeated
+cli-profile-add-identity-file = Private key file used for decryption
+cli-profile-add-encrypt = Always encrypt exports
+cli-profile-keygen-about = Generate a key pair for encrypted exports, the public key is added to the profile
+cli-profile-keygen-name = Profile name
+cli-profile-keygen-output = Private key file, defaults to yuque.keys/{name}.txt next to the profiles file
+cli-profile-list-about = List all profiles
+cli-profile-remove-about = Remove a profile
+cli-profile-remove-name = Profile name
+cli-ls-about = Browse cached books and their table of contents (no login needed)
+cli-ls-target = Book name or slug, optionally with a folder, e.g. book/folder
+cli-ls-json = Output as JSON
+cli-search-about = Fuzzy search cached doc titles (no login needed)
+cli-search-query = Search text
+cli-search-limit = Maximum number of results
+cli-search-json = Output as JSON
+cli-index-about = Build a full-text index of exported docs, updated incrementally on later exports
+cli-index-rebuild = Discard the old index and rebuild it
+cli-grep-about = Full-text search in exported docs
+cli-grep-query = Search text
+cli-grep-limit = Maximum number of results
+cli-grep-json = Output as JSON
+cli-stats-about = Show doc count, depth, words, images, stale docs and broken links of books (no login needed)
+cli-stats-books = Book names or slugs, all cached books if empty
+cli-stats-format = Output format
+cli-stats-stale-days = Docs not updated for this many days are considered stale
+cli-stats-top = Number of docs with the most words to list
+cli-push-about = Push a local Markdown directory to a book, pushed docs are updated in place
+cli-push-dir = Local docs directory
+cli-push-book = Target book slug, or user/slug
+cli-mirror-about = Copy a whole book to a book in another space
+cli-mirror-from = Source book, e.g. user/book
+cli-mirror-to = Target book, e.g. group/book
+cli-mirror-dry-run = Only list the planned operations, change nothing
+cli-bundle-about = Export books as single files with a table of contents, in-book links become in-file anchors
+cli-bundle-books = Book names or slugs, can be repeated
+cli-bundle-format = Output format
+cli-decrypt-about = Decrypt an encrypted export file, archive or directory
+cli-decrypt-path = Encrypted file (.age) or directory, all .age files in a directory are decrypted
+cli-decrypt-identity = Private key file, defaults to the profile's identity_file, not needed for passphrase encryption
+cli-decrypt-output = Output directory, defaults to next to the encrypted files
+cli-decrypt-remove = Remove the encrypted files after successful decryption
+cli-watch-about = Keep running, check books for changes periodically and sync them locally
+cli-watch-interval = Check interval, e.g. 30s, 10m, 1h
+cli-webhook-about = Receive Yuque webhooks and re-export docs after they are published/updated
+cli-webhook-listen = Listen address
+cli-webhook-token = Token for verification, the webhook URL must then include ?token=xxx
+cli-webhook-debounce = Only export a doc after no new edits for this long, e.g. 5s, 1m
+cli-serve-about = Start a local server to preview exported docs
+cli-serve-dir = Docs directory, defaults to the output directory
+cli-serve-port = Port
+cli-completions-about = Print a shell completion script, e.g. source <(ytool completions bash)
+cli-completions-shell = Shell type
+cli-man-about = Print the man page
+cli-man-dir = Output directory, one page per subcommand; prints the main page to the terminal if empty
+cli-upgrade-about = Update ytool to the latest version
+cli-upgrade-check = Only check for a new version, do not update
@@ -0,0 +1,328 @@
+# 简体中文文案
+# 写法为 key = value，变量为 { $name }
+# 命令行帮助直接使用命令定义中的说明，不在此列出
+
+## 通用
+login-success = 登录成功!
+login-failed = 登录失败，请检查账号信息是否正确或重试
+books-fetch-success = 获取知识库成功
+books-cache-missing = 暂无知识库缓存，请先执行 ytool pull 获取知识库
+books-read-failed = 知识库信息读取失败
+book-not-found = 知识库不存在，可通过 ytool ls 查看所有知识库
+output-dir-missing = 文档输出目录不存在，请先执行 ytool pull 导出文档
+push-map-parse-failed = 推送记录解析失败
+output-dir-create-failed = 文档输出目录创建失败
+file-write-failed = 文件写入失败：{ $file }
+file-create-failed = 文件创建失败
+star-link = 去点个⭐️吧
+
+## 日志
+log-file-create-failed = 日志文件创建失败
+log-init-failed = 日志初始化失败
+log-dump-failed = 中间数据写入失败：{ $file }
+
+## 网络请求和缓存
+request-cookies-expired = cookies已过期，请清除缓存后重新执行程序
+request-failed = 接口请求失败：{ $url }
+request-upload-failed = 文件上传失败：{ $file }
+cache-dir-create-failed = 缓存目录创建失败
+cache-write-failed = 缓存暂存失败
+
+## 交互
+inquiry-select-books = 请选择知识库
+inquiry-select-books-help = 空格选中/取消选中，⬆ ⬇ 键移动选择
+inquiry-no-book-selected = 未选择知识库，程序退出
+inquiry-skip = 是否跳过本地文件?
+inquiry-select-failed = 选择出错，程序退出
+inquiry-line-break = 是否保留语雀换行标识?
+inquiry-line-break-help = </br>在不同平台处理逻辑存在差异，可按需选择是否保留
+inquiry-layout = 含子文档的文档如何输出?
+inquiry-layout-sibling = 同级文件 (a.md + a/)
+inquiry-layout-index = 目录索引 (a/index.md)
+inquiry-layout-readme = 目录说明 (a/README.md)
+inquiry-layout-help = 静态站点生成器通常需要index.md或README.md
+inquiry-hidden = 是否导出隐藏/草稿文档?
+inquiry-hidden-help = 目录中不可见的文档，不导出时会在导出报告中列出
+inquiry-books-read-failed = 知识库文件读取失败，程序退出
+inquiry-username = yuque username:
+inquiry-username-failed = username 填写出错，程序退出
+inquiry-password = yuque password:
+inquiry-password-failed = password 填写出错，程序退出
+
+## 下载进度
+progress-done = [{ $done }/{ $total }] 完成 { $item } ({ $size })
+progress-failed = [{ $done }/{ $total }] 失败 { $item }
+progress-docs = 文档
+progress-resources = 资源
+progress-eta = 剩余
+progress-group-done = 完成
+progress-total-status = 已下载 { $bytes } 失败 { $failed }
+progress-summary = { $label }下载汇总：
+progress-col-group = 分组
+progress-col-success = 成功
+progress-col-failed = 失败
+progress-col-skipped = 跳过
+progress-col-size = 大小
+progress-row-total = 合计
+progress-elapsed = 耗时 { $time }
+
+## 浏览和搜索
+browse-dir-not-found = 目录不存在，请检查路径是否正确
+browse-search-no-match = 没有找到与【{ $query }】匹配的文档
+browse-indexing = 正在建立全文索引...
+browse-index-done = 索引更新完毕，新增 { $added } 篇，更新 { $updated } 篇，移除 { $removed } 篇
+browse-index-failed = 全文索引建立失败
+browse-index-missing = 还没有建立全文索引，请先执行 ytool index
+browse-grep-no-match = 没有找到包含【{ $query }】的文档
+filter-invalid = 过滤条件有误
+
+## 合并导出
+bundle-book-not-found = 知识库【{ $book }】不存在，可通过 ytool ls 查看
+bundle-book-empty = 知识库【{ $book }】没有可导出的文档
+bundle-start = 开始合并知识库【{ $book }】，共 { $count } 篇
+bundle-epub-failed = 知识库【{ $book }】EPUB生成失败
+bundle-exported = 已导出 { $file }
+bundle-body-failed = 文档内容获取失败：{ $title }
+bundle-image-failed = 图片下载失败，保留原地址：{ $url }
+
+## 导出过滤
+filter-glob-invalid = 路径格式有误：{ $glob }
+filter-date-invalid = 日期格式有误：{ $date }，应为 YYYY-MM-DD
+filter-title-invalid = 标题正则有误：{ $title }
+filter-summary = 过滤条件：
+filter-summary-toc-range = 目录范围：{ $value }
+filter-summary-include = 包含路径：{ $value }
+filter-summary-exclude = 排除路径：{ $value }
+filter-summary-updated-since = 更新时间不早于：{ $value }
+filter-summary-author = 作者：{ $value }
+filter-summary-title = 标题匹配：{ $value }
+filter-summary-max-depth = 最大目录深度：{ $value }
+
+## git仓库
+git-not-found = 未找到git，请先安装git
+git-init = 已将 { $dir } 初始化为git仓库
+git-exec-error = git执行异常
+git-exec-failed = git执行失败
+git-commit-doc-added = 新增 { $path }
+git-commit-doc-updated = 更新 { $path }
+git-summary = ytool pull: 新增 { $added } 篇，修改 { $modified } 篇，删除 { $deleted } 篇
+git-summary-added = 新增：
+git-summary-modified = 修改：
+git-summary-deleted = 删除：
+git-summary-assets = 附件变更 { $count } 个
+
+## 复制知识库
+mirror-namespace-invalid = 知识库格式应为 用户/slug
+mirror-same-book = 源知识库和目标知识库不能相同
+mirror-start = 开始复制知识库 { $from } -> { $to }
+mirror-plan = 预计新建 { $created } 个，更新 { $updated } 个，已存在 { $exists } 个
+mirror-failed-count = 有 { $count } 个节点复制失败，可重新执行
+mirror-done = 复制完毕，新建 { $created } 个，更新 { $updated } 个，已存在 { $exists } 个
+mirror-toc-failed = 源知识库目录获取失败，请检查知识库地址和访问权限
+mirror-node-success = 复制成功 { $path }
+mirror-node-failed = 复制失败 { $path }
+
+## 归档
+archive-format-unsupported = 归档文件只支持 .tar.zst、.tzst 和 .zip
+archive-create-failed = 归档文件创建失败
+archive-write-failed = 归档文件写入失败
+
+## 加密
+encrypt-recipient-invalid = 公钥格式不正确，应为 age1 开头的X25519公钥
+encrypt-passphrase-empty = 没有输入口令
+encrypt-passphrase-prompt = 请输入加密口令
+encrypt-passphrase-confirm = 请再次输入口令
+encrypt-failed = 加密失败
+encrypt-git-conflict = 加密导出不能同时提交到git仓库
+encrypt-passphrase-slow = 口令加密每个文件需要约1秒，文档较多时建议使用公钥或配合 --archive 整体加密
+decrypt-not-encrypted = 不是age加密的文件
+decrypt-identity-missing = 文件使用公钥加密，请通过 --identity 或配置档案的identity_file指定私钥
+decrypt-identity-invalid = 私钥文件读取失败，应包含 AGE-SECRET-KEY- 开头的私钥
+decrypt-failed = 解密失败，密钥或口令不正确
+decrypt-no-files = 没有找到需要解密的 .age 文件
+decrypt-path-missing = 文件或目录不存在
+decrypt-write-failed = 解密后的文件写入失败
+decrypt-file-failed = 解密失败 { $file }：{ $reason }
+decrypt-done = 解密完成，成功 { $count } 个，失败 { $failed } 个
+
+## 路径模板
+template-invalid = 路径模板有误：{ $template }，{ $reason }
+template-outside = 只能是相对路径，且不能包含 ..
+template-unclosed = 花括号没有闭合
+template-unknown-variable = 未知变量 { $name }，可用的变量：{ $variables }
+template-not-unique = 至少包含 { $variables } 之一，否则文件必然重名
+template-conflict = { $source } 和其他文档输出到同一路径 { $target }
+template-conflict-abort = 路径模板导致 { $count } 处路径冲突，未进行导出，请调整模板
+pull-aborted = 导出已中止
+template-logseq-ignored = Logseq的页面和附件位置是固定的，文档和附件路径模板不生效
+template-asset-obsidian-only = 附件路径模板只用于Obsidian，导出为普通Markdown时不下载附件，请去掉asset或使用--target obsidian
+
+## 导出计划
+plan-summary = 共 { $total } 项：下载 { $download }，已存在跳过 { $exists }，隐藏跳过 { $hidden }，路径冲突 { $conflict }，预计需要 { $secs } 秒
+
+## 推送
+push-dir-not-found = 推送目录不存在
+push-start = 开始推送 { $dir } 到知识库 { $book }
+push-failed-count = 有 { $count } 个文档推送失败，可重新执行推送
+push-done = 推送完毕，新建 { $created } 个，更新 { $updated } 个，未变化 { $skipped } 个
+push-dir-read-failed = 推送目录读取失败
+push-node-success = 推送成功 { $path }
+push-node-failed = 推送失败 { $path }
+push-map-save-failed = 推送记录保存失败
+push-token-missing = 推送需要语雀开放接口的Token，请在配置文件或配置档案中填写 token
+
+## 预览服务
+serve-dir-not-found = 文档目录不存在，请先执行 ytool pull 导出文档
+serve-index-failed = 文档索引建立失败，搜索不可用
+serve-port-in-use = 端口被占用，请通过 --port 指定其他端口
+serve-started = 预览服务已启动：http://127.0.0.1:{ $port }，按 Ctrl+C 退出
+serve-exited = 预览服务异常退出
+
+## 更新
+upgrade-exe-not-found = 无法获取当前程序的位置
+upgrade-checking = 正在检查新版本...
+upgrade-feed-failed = 版本信息获取失败
+upgrade-feed-parse-failed = 版本信息解析失败
+upgrade-version-invalid = 版本号无法识别
+upgrade-up-to-date = 当前已是最新版本 { $version }
+upgrade-found = 发现新版本 { $latest }，当前版本 { $current }
+upgrade-no-asset = 没有适用于当前平台的安装包
+upgrade-asset = 安装包：{ $url }，执行 ytool upgrade 进行更新
+upgrade-no-checksum = 缺少安装包的校验文件，已取消更新
+upgrade-checksum-download-failed = 校验文件下载失败
+upgrade-checksum-missing = 校验文件中没有该安装包的记录
+upgrade-downloading = 正在下载 { $name }
+upgrade-download-failed = 安装包下载失败
+upgrade-checksum-mismatch = 安装包校验失败，已取消更新
+upgrade-extract-failed = 安装包解压失败
+upgrade-replace-failed = 程序替换失败，请检查是否有写入权限
+upgrade-done = 已更新到 { $version }
+
+## 定时同步
+watch-status-write-failed = 状态文件写入失败
+watch-config-missing = 请先通过 ytool init 生成配置文件并填写要同步的知识库(toc_range)
+watch-stopping = 收到退出信号，本轮同步结束后退出
+watch-start = 开始同步，每 { $secs } 秒检查一次，状态见 { $file }，按 Ctrl+C 退出
+watch-stopped = 同步已停止
+watch-fetch-failed = 知识库信息获取失败，下次检查时重试
+watch-changes = [{ $time }] 新增 { $added } 篇，更新 { $updated } 篇，删除 { $deleted } 篇
+watch-doc-deleted = 文档已在语雀上删除，本地保留：{ $title }
+watch-interval-invalid = 检查间隔格式有误，如 30s、10m、1h
+watch-interval-too-large = 检查间隔过大
+watch-interval-zero = 检查间隔不能为0
+
+## webhook
+webhook-config-missing = 请先通过 ytool init 生成配置文件并填写要导出的知识库(toc_range)
+webhook-listen-failed = 监听地址有误或端口被占用
+webhook-started = webhook服务已启动：http://{ $listen }/webhook，按 Ctrl+C 退出
+webhook-exited = webhook服务异常退出
+webhook-received = 收到文档变更：{ $title }({ $action })
+webhook-refresh-failed = 知识库信息刷新失败，本次变更未导出
+webhook-out-of-range = 《{ $title }》不在导出范围内，已忽略
+webhook-book-missing = 《{ $title }》所在的知识库不存在，已忽略
+webhook-exported = 已重新导出 { $count } 篇文档
+
+## 语雀接口
+yuque-books-fetching = 开始获取知识库
+yuque-books-loading = 正在获取知识库数据，请稍后...
+yuque-books-fetch-failed = 获取知识库失败
+yuque-books-fetch-failed-reason = 获取知识库失败: { $reason }
+
+## 笔记库
+vault-attachment-save-failed = 附件保存失败：{ $file }
+vault-attachment-download-failed = 附件下载失败，保留原地址：{ $url }
+
+## 命令补全
+completion-script-failed = 补全脚本输出失败
+completion-man-failed = man手册输出失败
+completion-man-dir-failed = man手册目录创建失败
+completion-man-generate-failed = man手册生成失败
+completion-man-done = man手册已生成到 { $dir }
+
+## 配置档案
+profile-not-found = 配置档案不存在，请先通过 ytool profile add 添加
+profile-using = 使用配置档案：{ $name }
+profile-updated = 配置档案【{ $name }】已更新
+profile-added = 配置档案【{ $name }】已添加
+profile-write-failed = 配置档案写入失败
+profile-empty = 暂无配置档案
+profile-removed = 配置档案【{ $name }】已删除，缓存目录需手动清理
+profile-missing = 配置档案【{ $name }】不存在
+profile-keygen-done = 配置档案【{ $name }】的私钥已保存到 { $file }，请妥善备份，公钥如下
+profile-keygen-exists = 私钥文件 { $file } 已存在，不会覆盖
+profile-keygen-failed = 私钥文件写入失败
+profile-personal = 个人
+profile-no-account = 未配置账号
+profile-list-item = { $name }  [{ $space }]  { $account }  缓存：{ $dir }
+
+## 初始化和缓存
+init-done = 配置文件已初始化，见👉{ $file }
+clear-failed = 缓存清除失败
+clear-done = 缓存已清除~
+clear-empty = 暂无缓存
+clear-kept-keys = 缓存目录中有 { $count } 个私钥文件(identity.txt)，已保留
+
+## 导出
+report-file = 导出报告.md
+report-title = 导出报告
+pull-config = 将按以下配置进行导出：
+pull-config-books = 知识库：{ $value }
+pull-config-skip = 跳过本地：{ $value }
+pull-config-line-break = 保留换行：{ $value }
+pull-config-hidden = 导出隐藏文档：{ $value }
+pull-dry-run = 导出计划(--dry-run，不会修改任何本地文件)：
+pull-dry-run-git = 正式导出后会把变更提交到 { $dir } 的git仓库
+pull-dry-run-archive = 正式导出时会写入归档 { $file }，不修改输出目录
+pull-dry-run-no-cache = --dry-run 只根据已缓存的知识库信息生成导出计划，不会登录，请先执行一次 ytool pull
+pull-missing-meta = 有 { $count } 篇文档缺少更新时间/作者信息，请执行 ytool clear 清除缓存后重新获取
+pull-filtered = 有 { $count } 篇文档不符合过滤条件，不会导出
+pull-clean-dir-failed = 知识库空目录清理失败
+pull-hidden-skipped = 有 { $count } 篇隐藏/草稿文档将被跳过，详见导出报告
+pull-conflict-skipped = 有 { $count } 篇文档的输出路径和其他文档重复，已跳过，详见导出报告
+pull-start = 开始执行导出任务，共 { $count } 篇文档(本地已存在跳过 { $skipped } 篇)，预计需要 { $secs } 秒
+pull-index-failed = 全文索引更新失败，可执行 ytool index --rebuild 重建
+pull-git-failed = 提交到git仓库失败：{ $reason }
+pull-done = 导出任务执行完毕，共导出{ $count }个文档，{ $link }
+pull-archive-done = 归档已生成：{ $files }
+pull-stale-docs = 有 { $count } 篇文档已在语雀上删除
+pull-missing-editor = 有 { $count } 篇文档缺少最后编辑者信息，合并到汇总提交中，可执行 ytool clear 清除缓存后重新获取
+pull-git-doc-commits = 已按文档提交到git仓库，共 { $count } 次提交
+pull-git-no-changes = 文档没有变化，无需提交
+pull-git-committed = 已提交到git仓库：{ $summary }
+pull-book-dir-failed = 知识库目录创建失败
+pull-doc-failed = 文档导出失败：{ $path }
+
+## 团队资源
+grd-start = 团队资源下载程序开始
+grd-fetching = 开始获取团队资源信息，请确保资源目录和文件名称不包含特殊字符哦～
+grd-base-info-success = 获取团队资源基础信息成功
+grd-dry-run = 下载计划(--dry-run，不会修改任何本地文件)：
+grd-host-missing = 请配置团队空间域名
+grd-dir-failed = 资源目录创建失败
+grd-conflict = { $file } 和其他资源重名，已跳过
+grd-sub-dir-failed = { $dir } 资源目录创建失败
+grd-download-failed = { $file } 下载失败，请确认资源名称是否有特殊字符
+grd-done = 团队资源下载任务完成，共下载{ $count }个文件~，{ $link }
+grd-detail-fetching = 【{ $title }】资源信息开始获取
+grd-detail-success = 【{ $title }】资源信息获取成功
+grd-detail-failed = 【{ $title }】资源信息获取失败
+grd-list-fetching = 【{ $name }】资源列表开始获取
+grd-list-success = 【{ $name }】资源列表获取成功
+grd-list-failed = 【{ $name }】资源列表获取失败
+grd-info-success = 获取团队资源信息成功
+
+## 桌面端
+gui-not-logged-in = 未登录
+gui-login-failed = 登录失败，请检查用户名和密码
+gui-get-user-info-failed = 获取用户信息失败
+gui-get-books-failed = 获取知识库失败
+gui-get-docs-failed = 获取文档失败
+gui-export-failed = 导出失败
+gui-cache-operation-failed = 缓存操作失败
+gui-login-success = 登录成功
+gui-personal-books = 个人知识库
+gui-team-books = 团队知识库
+gui-all-books = 所有知识库
+gui-skip-hidden = 隐藏文档
+gui-skip-filtered = 不符合过滤条件的文档
+gui-export-skipped = 跳过{ $reason }: { $title }
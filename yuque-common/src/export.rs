@@ -0,0 +1,73 @@
+/*
+ * Description: 哪些目录节点需要输出文件，命令行工具和桌面端按同一规则导出
+ * Created: 2026-10-19 04:15:32
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+/// 文档节点
+pub const DOC: &str = "DOC";
+/// 分组节点，没有正文
+pub const TITLE: &str = "TITLE";
+
+/// 导出规则
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportPolicy {
+    /// 是否导出visible为0的隐藏/草稿文档
+    pub include_hidden: bool,
+    /// 有可导出下级文档的分组是否生成索引文件
+    /// 命令行工具的index/readme布局为true，sibling布局和桌面端为false
+    pub group_index: bool,
+}
+
+impl ExportPolicy {
+    /// 节点是否可见
+    pub fn is_visible(&self, visible: u8) -> bool {
+        visible == 1 || self.include_hidden
+    }
+
+    /// 节点是否需要输出文件，分组只在生成索引且有可导出的下级文档时输出
+    /// # Arguments
+    /// * node_type - DOC 或 TITLE
+    /// * visible - 语雀目录中的visible
+    /// * has_exportable_children - 是否有可导出的下级文档，只在分组需要判断时调用
+    pub fn is_exportable(
+        &self,
+        node_type: &str,
+        visible: u8,
+        has_exportable_children: impl FnOnce() -> bool,
+    ) -> bool {
+        if !self.is_visible(visible) {
+            return false;
+        }
+        match node_type {
+            DOC => true,
+            TITLE => self.group_index && has_exportable_children(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_exportable() {
+        let sibling = ExportPolicy::default();
+        assert!(sibling.is_exportable(DOC, 1, || false));
+        assert!(!sibling.is_exportable(DOC, 0, || false));
+        assert!(!sibling.is_exportable(TITLE, 1, || true));
+
+        let index = ExportPolicy {
+            include_hidden: true,
+            group_index: true,
+        };
+        assert!(index.is_exportable(DOC, 0, || false));
+        assert!(index.is_exportable(TITLE, 1, || true));
+        assert!(!index.is_exportable(TITLE, 1, || false));
+        assert!(!index.is_exportable("LINK", 1, || true));
+    }
+}
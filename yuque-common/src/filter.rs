@@ -0,0 +1,258 @@
+/*
+ * Description: 导出过滤条件，命令行工具和桌面端按同一规则筛选文档
+ * Created: 2026-10-19 05:24:20
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// 导出过滤条件，全部为空时不过滤
+pub struct DocFilter {
+    #[serde(default)]
+    /// 包含的路径，glob格式，如 `book/目录/**`，为空时包含全部
+    pub include: Vec<String>,
+    #[serde(default)]
+    /// 排除的路径，glob格式，目录被排除时其下的文档一并排除
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    /// 只导出该日期(YYYY-MM-DD)及之后更新过的文档
+    pub updated_since: String,
+    #[serde(default)]
+    /// 只导出该作者(语雀login)的文档
+    pub author: String,
+    #[serde(default)]
+    /// 标题正则
+    pub title: String,
+    #[serde(default)]
+    /// 最大目录深度，知识库下第一级为1，0表示不限制
+    pub max_depth: usize,
+}
+
+/// 过滤条件有误
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterError {
+    Glob(String),
+    Date(String),
+    Title(String),
+}
+
+impl FilterError {
+    /// 对应的文案key、变量名和变量值
+    pub fn message(&self) -> (&'static str, &'static str, &str) {
+        match self {
+            FilterError::Glob(glob) => ("filter-glob-invalid", "glob", glob),
+            FilterError::Date(date) => ("filter-date-invalid", "date", date),
+            FilterError::Title(title) => ("filter-title-invalid", "title", title),
+        }
+    }
+}
+
+/// 编译后的过滤条件
+/// - 路径(include/exclude)匹配文档本身或任意一级上级目录即算命中
+/// - 更新时间、作者、标题只对文档本身生效，缺少元数据的文档视为不匹配
+///
+/// 命令行工具裁剪文档树时，不符合条件但有符合条件的下级文档的文档转为分组，
+/// 分组按ExportPolicy决定是否输出，因此两端导出的文档都是 [`DocMatcher::match_doc`] 命中的文档
+#[derive(Debug)]
+pub struct DocMatcher {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    updated_since: String,
+    author: String,
+    title: Option<Regex>,
+    max_depth: usize,
+}
+
+impl DocMatcher {
+    /// 校验并编译过滤条件
+    pub fn compile(filter: &DocFilter) -> Result<Self, FilterError> {
+        let compile_globs = |globs: &Vec<String>| -> Result<Vec<Regex>, FilterError> {
+            globs
+                .iter()
+                .map(|glob| glob_to_regex(glob).map_err(|_| FilterError::Glob(glob.to_string())))
+                .collect()
+        };
+
+        if !filter.updated_since.is_empty()
+            && NaiveDate::parse_from_str(&filter.updated_since, "%Y-%m-%d").is_err()
+        {
+            return Err(FilterError::Date(filter.updated_since.to_string()));
+        }
+
+        let title = if filter.title.is_empty() {
+            None
+        } else {
+            Some(
+                Regex::new(&filter.title)
+                    .map_err(|_| FilterError::Title(filter.title.to_string()))?,
+            )
+        };
+
+        Ok(DocMatcher {
+            include: compile_globs(&filter.include)?,
+            exclude: compile_globs(&filter.exclude)?,
+            updated_since: filter.updated_since.to_string(),
+            author: filter.author.to_string(),
+            title,
+            max_depth: filter.max_depth,
+        })
+    }
+
+    /// 是否没有任何过滤条件
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty()
+            && self.exclude.is_empty()
+            && self.updated_since.is_empty()
+            && self.author.is_empty()
+            && self.title.is_none()
+            && self.max_depth == 0
+    }
+
+    /// 是否需要文档的更新时间/作者信息
+    pub fn need_meta(&self) -> bool {
+        !self.updated_since.is_empty() || !self.author.is_empty()
+    }
+
+    /// 路径是否符合条件
+    /// # Arguments
+    /// * full_path - 知识库名称开头的完整路径，如 `book/目录/文档`
+    pub fn match_path(&self, full_path: &str) -> bool {
+        let segments: Vec<&str> = full_path.split('/').collect();
+        // 第一段是知识库名称
+        if self.max_depth > 0 && segments.len() - 1 > self.max_depth {
+            return false;
+        }
+
+        // 文档本身及所有上级目录
+        let paths: Vec<String> = (1..=segments.len())
+            .map(|end| segments[..end].join("/"))
+            .collect();
+        let hit = |globs: &Vec<Regex>| {
+            paths
+                .iter()
+                .any(|path| globs.iter().any(|reg| reg.is_match(path)))
+        };
+
+        if hit(&self.exclude) {
+            return false;
+        }
+        self.include.is_empty() || hit(&self.include)
+    }
+
+    /// 文档元数据是否符合条件
+    pub fn match_meta(&self, title: &str, updated_at: &str, author: &str) -> bool {
+        if let Some(reg) = &self.title {
+            if !reg.is_match(title) {
+                return false;
+            }
+        }
+        // ISO 8601格式的日期可以直接按字符串比较
+        if !self.updated_since.is_empty()
+            && updated_at
+                .get(..10)
+                .is_none_or(|date| date < self.updated_since.as_str())
+        {
+            return false;
+        }
+        if !self.author.is_empty() && author != self.author {
+            return false;
+        }
+        true
+    }
+
+    /// 文档是否符合条件
+    pub fn match_doc(&self, full_path: &str, title: &str, updated_at: &str, author: &str) -> bool {
+        self.match_path(full_path) && self.match_meta(title, updated_at, author)
+    }
+}
+
+/// glob转正则，`**` 匹配任意多级，`*` 和 `?` 不跨越 `/`，其余字符按字面匹配
+pub fn glob_to_regex(glob: &str) -> Result<Regex, regex::Error> {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_to_regex() {
+        let reg = glob_to_regex("book(1)/*.md").unwrap();
+        assert!(reg.is_match("book(1)/a.md"));
+        assert!(!reg.is_match("book(1)/a/b.md"));
+        assert!(!reg.is_match("book1/a.md"));
+
+        let reg = glob_to_regex("book/**").unwrap();
+        assert!(reg.is_match("book/a/b"));
+        assert!(!reg.is_match("other/a"));
+    }
+
+    #[test]
+    fn test_match_path() {
+        let filter = DocFilter {
+            include: vec!["book/目录".to_string()],
+            exclude: vec!["book/目录/私密*".to_string()],
+            max_depth: 3,
+            ..Default::default()
+        };
+        let matcher = DocMatcher::compile(&filter).unwrap();
+
+        assert!(matcher.match_path("book/目录"));
+        assert!(matcher.match_path("book/目录/文档"));
+        // 排除目录下的文档一并排除
+        assert!(!matcher.match_path("book/目录/私密笔记/文档"));
+        assert!(!matcher.match_path("book/其他"));
+        assert!(!matcher.match_path("book/目录/a/b/c"));
+    }
+
+    #[test]
+    fn test_match_meta() {
+        let filter = DocFilter {
+            updated_since: "2023-09-01".to_string(),
+            author: "vannvan".to_string(),
+            title: "^Rust".to_string(),
+            ..Default::default()
+        };
+        let matcher = DocMatcher::compile(&filter).unwrap();
+
+        assert!(matcher.match_meta("Rust入门", "2023-09-01T08:00:00.000Z", "vannvan"));
+        assert!(!matcher.match_meta("Rust入门", "2023-08-31T23:00:00.000Z", "vannvan"));
+        assert!(!matcher.match_meta("Rust入门", "2023-09-02T08:00:00.000Z", "other"));
+        assert!(!matcher.match_meta("学习Rust", "2023-09-02T08:00:00.000Z", "vannvan"));
+        // 缺少元数据视为不匹配
+        assert!(!matcher.match_meta("Rust入门", "", ""));
+        assert!(!matcher.match_doc("book/Rust入门", "Rust入门", "", ""));
+
+        let invalid_date = DocFilter {
+            updated_since: "2023/09/01".to_string(),
+            ..Default::default()
+        };
+        let err = DocMatcher::compile(&invalid_date).unwrap_err();
+        assert_eq!(err.message(), ("filter-date-invalid", "date", "2023/09/01"));
+        assert!(DocMatcher::compile(&DocFilter::default())
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+/*
+ * Description: 多语言文案文件及解析，命令行工具和桌面端的提示信息都在其中
+ * Created: 2026-10-19 04:11:33
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{collections::HashMap, env, fmt::Display};
+
+/// 文案文件，采用fluent的 `key = value` 写法，变量为 `{ $name }`
+pub const ZH_CN: &str = include_str!("../locales/zh-CN.ftl");
+pub const EN_US: &str = include_str!("../locales/en-US.ftl");
+
+/// 逐行解析文案文件，忽略空行和 # 开头的注释
+pub fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+/// 替换文案中的变量 `{ $name }`
+/// # Arguments
+/// * message - 文案
+/// * args - 变量名和值
+pub fn format(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut message = message.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{ ${} }}", name), &value.to_string());
+    }
+    message
+}
+
+/// 按 LC_ALL、LC_MESSAGES、LANG 的顺序取第一个非空的语言设置
+pub fn env_lang() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// 语言设置是否为英文，如 en_US.UTF-8，其余都使用中文
+pub fn is_english(lang: &str) -> bool {
+    lang.to_lowercase().starts_with("en")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let messages = parse("# 注释\n\nlogin-success = 登录成功\nkey=a = b\n");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages["login-success"], "登录成功");
+        assert_eq!(messages["key"], "a = b");
+
+        assert!(is_english("en_US.UTF-8"));
+        assert!(is_english("EN"));
+        assert!(!is_english("zh_CN.UTF-8"));
+        assert!(!is_english("C"));
+
+        assert_eq!(
+            format("路径格式有误：{ $glob }", &[("glob", &"a/**")]),
+            "路径格式有误：a/**"
+        );
+    }
+}
@@ -0,0 +1,12 @@
+/*
+ * Description: 命令行工具(ytool)和桌面端共用的部分
+ * Created: 2026-10-19 04:11:33
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+pub mod export;
+pub mod filter;
+pub mod i18n;
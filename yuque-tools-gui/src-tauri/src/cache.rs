@@ -83,6 +83,10 @@ pub struct CachedDocItem {
     pub sibling_uuid: Option<String>,
     pub level: Option<u8>,
     pub doc_full_path: Option<String>, // 新增：文档的完整路径，用于构建导出文件的目录结构
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
 }
 
 #[derive(Clone)]
@@ -64,6 +64,11 @@ impl Documents {
         )
     }
 
+    /// 知识库下的文档列表，带更新时间和作者
+    pub fn book_docs_url(book_id: u64) -> String {
+        format!("{}/api/docs?book_id={}", BASE_URL, book_id)
+    }
+
     /// 根据用户、知识库和文档slug构建文档URL
     pub fn doc_url(user_login: &str, book_slug: &str, doc_slug: &str) -> String {
         format!("{}/{}/{}/{}", BASE_URL, user_login, book_slug, doc_slug)
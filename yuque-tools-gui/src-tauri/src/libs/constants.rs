@@ -54,46 +54,3 @@ impl Cache {
     /// 文档缓存键前缀
     pub const DOCS_CACHE_PREFIX: &'static str = "docs_";
 }
-
-/// 错误消息常量
-pub struct ErrorMessages;
-
-impl ErrorMessages {
-    /// 未登录错误
-    pub const NOT_LOGGED_IN: &'static str = "未登录";
-
-    /// 登录失败错误
-    pub const LOGIN_FAILED: &'static str = "登录失败，请检查用户名和密码";
-
-    /// 获取用户信息失败
-    pub const GET_USER_INFO_FAILED: &'static str = "获取用户信息失败";
-
-    /// 获取知识库失败
-    pub const GET_BOOKS_FAILED: &'static str = "获取知识库失败";
-
-    /// 获取文档失败
-    pub const GET_DOCS_FAILED: &'static str = "获取文档失败";
-
-    /// 导出失败
-    pub const EXPORT_FAILED: &'static str = "导出失败";
-
-    /// 缓存操作失败
-    pub const CACHE_OPERATION_FAILED: &'static str = "缓存操作失败";
-}
-
-/// 成功消息常量
-pub struct SuccessMessages;
-
-impl SuccessMessages {
-    /// 登录成功
-    pub const LOGIN_SUCCESS: &'static str = "登录成功";
-
-    /// 个人知识库
-    pub const PERSONAL_BOOKS: &'static str = "个人知识库";
-
-    /// 团队知识库
-    pub const TEAM_BOOKS: &'static str = "团队知识库";
-
-    /// 所有知识库
-    pub const ALL_BOOKS: &'static str = "所有知识库";
-}
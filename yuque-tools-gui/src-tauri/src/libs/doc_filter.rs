@@ -0,0 +1,151 @@
+use crate::libs::{doc_parser::DocParser, i18n::t, models::DocItem};
+use yuque_common::i18n::format;
+
+/// 导出过滤条件及匹配规则与命令行版(ytool)共用，见 yuque_common::filter
+pub use yuque_common::filter::{DocFilter, DocMatcher};
+
+/// 校验并编译过滤条件，错误信息按当前语言输出
+pub fn compile(filter: &DocFilter) -> Result<DocMatcher, String> {
+    DocMatcher::compile(filter).map_err(|err| {
+        let (key, name, value) = err.message();
+        format(t(key), &[(name, &value)])
+    })
+}
+
+/// 文档列表的过滤
+pub trait DocItemFilter {
+    /// 文档是否符合条件，缺少更新时间/作者的文档在设置了对应条件时视为不匹配
+    fn matches(&self, doc: &DocItem) -> bool;
+}
+
+impl DocItemFilter for DocMatcher {
+    fn matches(&self, doc: &DocItem) -> bool {
+        self.match_doc(
+            doc.doc_full_path.as_deref().unwrap_or(&doc.title),
+            &doc.title,
+            doc.updated_at.as_deref().unwrap_or(""),
+            doc.author.as_deref().unwrap_or(""),
+        )
+    }
+}
+
+/// 导出时的文档选择规则，隐藏/草稿文档按include_hidden决定是否跳过，不符合过滤条件的文档跳过
+/// 选择文档加入导出队列和实际导出时使用同一规则
+pub struct DocSelector {
+    include_hidden: bool,
+    matcher: DocMatcher,
+}
+
+impl DocSelector {
+    pub fn new(include_hidden: bool, filter: &DocFilter) -> Result<Self, String> {
+        Ok(DocSelector {
+            include_hidden,
+            matcher: compile(filter)?,
+        })
+    }
+
+    /// 跳过的原因，需要导出时为None
+    pub fn skip_reason(&self, doc: &DocItem) -> Option<&'static str> {
+        if !DocParser::is_exportable(doc, self.include_hidden) {
+            Some(t("gui-skip-hidden"))
+        } else if !self.matcher.matches(doc) {
+            Some(t("gui-skip-filtered"))
+        } else {
+            None
+        }
+    }
+
+    /// 跳过时输出的提示，需要导出时为None
+    pub fn skip_message(&self, doc: &DocItem) -> Option<String> {
+        self.skip_reason(doc).map(|reason| {
+            format(
+                t("gui-export-skipped"),
+                &[("reason", &reason), ("title", &doc.title)],
+            )
+        })
+    }
+
+    /// 会被导出的文档uuid
+    pub fn exportable_uuids(&self, docs: &[DocItem]) -> Vec<String> {
+        docs.iter()
+            .filter(|doc| self.skip_reason(doc).is_none())
+            .map(|doc| doc.uuid.to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(full_path: &str, updated_at: Option<&str>, author: Option<&str>) -> DocItem {
+        let title = full_path.rsplit('/').next().unwrap().to_string();
+        DocItem {
+            title: title.clone(),
+            node_type: "DOC".to_string(),
+            uuid: title.clone(),
+            child_uuid: "".to_string(),
+            parent_uuid: "".to_string(),
+            visible: 1,
+            url: title,
+            slug: None,
+            doc_id: None,
+            id: None,
+            open_window: None,
+            prev_uuid: None,
+            sibling_uuid: None,
+            level: None,
+            doc_full_path: Some(full_path.to_string()),
+            updated_at: updated_at.map(|s| s.to_string()),
+            author: author.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let filter = DocFilter {
+            include: vec!["book(1)/目录/**".to_string()],
+            exclude: vec!["book(1)/目录/私密*".to_string()],
+            updated_since: "2023-09-01".to_string(),
+            author: "vannvan".to_string(),
+            title: "^Rust".to_string(),
+            max_depth: 3,
+        };
+        let matcher = compile(&filter).unwrap();
+        let time = Some("2023-09-01T08:00:00.000Z");
+        let author = Some("vannvan");
+
+        assert!(matcher.matches(&doc("book(1)/目录/Rust入门", time, author)));
+        assert!(!matcher.matches(&doc("book(1)/目录/私密/Rust入门", time, author)));
+        assert!(!matcher.matches(&doc("book(1)/其他/Rust入门", time, author)));
+        assert!(!matcher.matches(&doc("book(1)/目录/a/b/Rust入门", time, author)));
+        assert!(!matcher.matches(&doc("book(1)/目录/学习Rust", time, author)));
+        assert!(!matcher.matches(&doc(
+            "book(1)/目录/Rust入门",
+            Some("2023-08-31T23:00:00.000Z"),
+            author
+        )));
+        assert!(!matcher.matches(&doc("book(1)/目录/Rust入门", time, Some("other"))));
+        // 缺少元数据视为不匹配
+        assert!(!matcher.matches(&doc("book(1)/目录/Rust入门", None, None)));
+
+        assert!(compile(&DocFilter {
+            updated_since: "2023/09/01".to_string(),
+            ..Default::default()
+        })
+        .is_err());
+
+        let mut hidden = doc("book(1)/目录/Rust草稿", time, author);
+        hidden.visible = 0;
+        let docs = vec![
+            doc("book(1)/目录/Rust入门", time, author),
+            doc("book(1)/其他/Rust入门", time, author),
+            hidden,
+        ];
+        let selector = DocSelector::new(false, &filter).unwrap();
+        assert_eq!(selector.exportable_uuids(&docs), vec!["Rust入门"]);
+        assert_eq!(selector.skip_reason(&docs[2]), Some(t("gui-skip-hidden")));
+        let selector = DocSelector::new(true, &filter).unwrap();
+        assert_eq!(selector.exportable_uuids(&docs).len(), 2);
+    }
+}
@@ -1,11 +1,26 @@
 use crate::libs::models::DocItem;
 use regex::Regex;
 use serde_json::Value;
+use yuque_common::export::ExportPolicy;
 
 /// 文档解析工具模块
 pub struct DocParser;
 
 impl DocParser {
+    /// 文档是否需要导出，visible为0的隐藏/草稿文档只在include_hidden时导出
+    /// 桌面端不生成分组索引，TITLE分组不输出文件，导出的文档集合与命令行版(ytool)的sibling布局相同
+    pub fn is_exportable(doc: &DocItem, include_hidden: bool) -> bool {
+        Self::export_policy(include_hidden).is_exportable(&doc.node_type, doc.visible, || false)
+    }
+
+    /// 与命令行版共用的导出规则
+    fn export_policy(include_hidden: bool) -> ExportPolicy {
+        ExportPolicy {
+            include_hidden,
+            group_index: false,
+        }
+    }
+
     /// 解析目录数据为文档列表
     pub fn parse_toc_to_docs(
         toc_data: &Value,
@@ -117,6 +132,8 @@ impl DocParser {
                 sibling_uuid,
                 level: original_level, // 使用原始的 level 字段
                 doc_full_path: None,   // 在解析时暂时不设置完整路径
+                updated_at: None,      // 目录中没有，由文档列表接口补充
+                author: None,
             };
 
             docs.push(doc_item);
@@ -130,6 +147,37 @@ impl DocParser {
         }
     }
 
+    /// 从HTML内容中提取知识库id，用于获取文档列表
+    pub fn extract_book_id_from_html(html_content: &str) -> Option<u64> {
+        let re = Regex::new(r#"decodeURIComponent\("([^"]+)"\)"#).unwrap();
+        let encoded_data = re.captures(html_content)?.get(1)?;
+        let decoded_data = urlencoding::decode(encoded_data.as_str()).ok()?;
+        let json_data: Value = serde_json::from_str(&decoded_data).ok()?;
+        json_data.get("book")?.get("id")?.as_u64()
+    }
+
+    /// 按文档slug把更新时间和作者合并到文档列表中，与命令行版(ytool)取值规则一致
+    pub fn merge_docs_meta(docs: &mut [DocItem], docs_meta: &Value) {
+        let Some(meta_list) = docs_meta.as_array() else {
+            return;
+        };
+        for doc in docs.iter_mut() {
+            if let Some(meta) = meta_list
+                .iter()
+                .find(|meta| meta["slug"].as_str() == Some(doc.url.as_str()))
+            {
+                doc.updated_at = meta["content_updated_at"]
+                    .as_str()
+                    .or(meta["updated_at"].as_str())
+                    .map(|s| s.to_string());
+                doc.author = meta["user"]["login"]
+                    .as_str()
+                    .or(meta["last_editor"]["login"].as_str())
+                    .map(|s| s.to_string());
+            }
+        }
+    }
+
     /// 从HTML内容中提取文档数据
     pub fn extract_docs_from_html(
         html_content: &str,
@@ -158,3 +206,39 @@ impl DocParser {
         Ok(vec![])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_exportable() {
+        let toc = serde_json::json!([
+            { "type": "TITLE", "uuid": "a", "title": "分组", "visible": 1 },
+            { "type": "DOC", "uuid": "b", "title": "文档", "visible": 1, "parent_uuid": "a" },
+            { "type": "DOC", "uuid": "c", "title": "草稿", "visible": 0, "parent_uuid": "a" }
+        ]);
+        let docs = DocParser::parse_toc_to_docs(&toc).unwrap();
+
+        let exported: Vec<&str> = docs
+            .iter()
+            .filter(|doc| DocParser::is_exportable(doc, false))
+            .map(|doc| doc.title.as_str())
+            .collect();
+        assert_eq!(exported, vec!["文档"]);
+
+        let exported_with_hidden = docs
+            .iter()
+            .filter(|doc| DocParser::is_exportable(doc, true))
+            .count();
+        assert_eq!(exported_with_hidden, 2);
+
+        // 与命令行版sibling布局的规则相同，有下级文档的分组也不输出
+        let sibling = ExportPolicy {
+            include_hidden: true,
+            group_index: false,
+        };
+        assert_eq!(DocParser::export_policy(true), sibling);
+        assert!(!DocParser::is_exportable(&docs[0], true));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::libs::constants::Export;
+use crate::libs::doc_filter::DocSelector;
 use crate::libs::models::DocItem;
 use reqwest::Client;
 
@@ -100,7 +101,7 @@ impl ExportUtils {
         Ok(full_output_path)
     }
 
-    /// 批量导出文档
+    /// 批量导出文档，按selector跳过隐藏/草稿和不符合过滤条件的文档
     pub async fn export_documents(
         client: &Client,
         docs: &[DocItem],
@@ -108,10 +109,16 @@ impl ExportUtils {
         output_dir: &str,
         cookies: &[String],
         user_login: &str,
+        selector: &DocSelector,
     ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
         let mut exported_files = Vec::new();
 
         for doc in docs {
+            if let Some(message) = selector.skip_message(doc) {
+                println!("{}", message);
+                continue;
+            }
+
             match Self::export_document(client, doc, book_slug, output_dir, cookies, user_login)
                 .await
             {
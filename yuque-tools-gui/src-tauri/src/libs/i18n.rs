@@ -0,0 +1,50 @@
+/*
+ * Description: 多语言文案，文案文件和解析在 yuque-common 中，和命令行工具共用
+ * Created: 2026-10-19 02:02:00
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use yuque_common::i18n::{env_lang, is_english, parse, EN_US, ZH_CN};
+
+static MESSAGES: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+static FALLBACK: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+/// 当前语言的文案，语言按 LC_ALL、LC_MESSAGES、LANG 识别，缺失时使用zh-CN的文案
+pub fn t(key: &'static str) -> &'static str {
+    let messages = MESSAGES.get_or_init(|| parse(source(&env_lang().unwrap_or_default())));
+    let fallback = FALLBACK.get_or_init(|| parse(ZH_CN));
+    messages
+        .get(key)
+        .or_else(|| fallback.get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// en开头的使用英文，其余为中文
+fn source(lang: &str) -> &'static str {
+    if is_english(lang) {
+        EN_US
+    } else {
+        ZH_CN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let zh = parse(source("zh_CN.UTF-8"));
+        let en = parse(source("en_US.UTF-8"));
+        assert_eq!(zh["gui-login-success"], "登录成功");
+        assert_eq!(en["gui-login-success"], "Logged in");
+        assert!(!t("gui-not-logged-in").is_empty());
+        assert_eq!(t("no-such-key"), "no-such-key");
+    }
+}
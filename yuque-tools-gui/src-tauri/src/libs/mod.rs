@@ -2,9 +2,11 @@
 pub mod api_config;
 pub mod constants;
 pub mod crypto;
+pub mod doc_filter;
 pub mod doc_parser;
 pub mod export_utils;
 pub mod http_utils;
+pub mod i18n;
 pub mod models;
 
 // 重新导出常用的类型和函数，方便外部使用
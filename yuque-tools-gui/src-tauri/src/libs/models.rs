@@ -49,6 +49,10 @@ pub struct DocItem {
     pub sibling_uuid: Option<String>,
     pub level: Option<u8>,
     pub doc_full_path: Option<String>, // 文档的完整路径，用于构建导出文件的目录结构
+    #[serde(default)]
+    pub updated_at: Option<String>, // 文档最后更新时间，用于导出过滤
+    #[serde(default)]
+    pub author: Option<String>, // 文档作者login，用于导出过滤
 }
 
 /// 知识库项目结构
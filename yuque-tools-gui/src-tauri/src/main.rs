@@ -9,6 +9,7 @@ mod cache;
 mod libs;
 mod yuque_service;
 
+use libs::doc_filter::{DocFilter, DocSelector};
 use libs::models::*;
 use std::sync::{Arc, Mutex};
 use tauri::State;
@@ -292,6 +293,8 @@ async fn export_document(
     doc: DocItem,
     book_slug: String, // 添加知识库slug参数
     output_dir: String,
+    include_hidden: Option<bool>, // 是否导出隐藏/草稿文档，默认不导出
+    filter: Option<DocFilter>,    // 导出过滤条件，与ytool配置中的filter一致
 ) -> Result<String, String> {
     let service_clone = {
         let service = state.0.lock().map_err(|_| "Failed to lock service")?;
@@ -307,7 +310,13 @@ async fn export_document(
     println!("导出文档完整路径: doc_full_path = {:?}", doc.doc_full_path);
 
     match service_clone
-        .export_document(&doc, &book_slug, &output_dir)
+        .export_document(
+            &doc,
+            &book_slug,
+            &output_dir,
+            include_hidden.unwrap_or(false),
+            &filter.unwrap_or_default(),
+        )
         .await
     {
         Ok(file_path) => Ok(file_path),
@@ -321,6 +330,8 @@ async fn export_documents(
     docs: Vec<DocItem>,
     book_slug: String, // 添加知识库slug参数
     output_dir: String,
+    include_hidden: Option<bool>, // 是否导出隐藏/草稿文档，默认不导出
+    filter: Option<DocFilter>,    // 导出过滤条件，与ytool配置中的filter一致
 ) -> Result<Vec<String>, String> {
     let service_clone = {
         let service = state.0.lock().map_err(|_| "Failed to lock service")?;
@@ -339,7 +350,13 @@ async fn export_documents(
     }
 
     match service_clone
-        .export_documents(&docs, &book_slug, &output_dir)
+        .export_documents(
+            &docs,
+            &book_slug,
+            &output_dir,
+            include_hidden.unwrap_or(false),
+            &filter.unwrap_or_default(),
+        )
         .await
     {
         Ok(file_paths) => Ok(file_paths),
@@ -347,6 +364,17 @@ async fn export_documents(
     }
 }
 
+/// 选择的文档中会被导出的文档uuid，规则与导出时相同
+#[tauri::command]
+fn filter_exportable_docs(
+    docs: Vec<DocItem>,
+    include_hidden: Option<bool>, // 是否导出隐藏/草稿文档，默认不导出
+    filter: Option<DocFilter>,    // 导出过滤条件，与ytool配置中的filter一致
+) -> Result<Vec<String>, String> {
+    let selector = DocSelector::new(include_hidden.unwrap_or(false), &filter.unwrap_or_default())?;
+    Ok(selector.exportable_uuids(&docs))
+}
+
 #[tauri::command]
 async fn get_downloads_path() -> Result<String, String> {
     // 获取用户下载目录
@@ -395,6 +423,7 @@ fn main() {
             shrink_window,
             export_document,
             export_documents,
+            filter_exportable_docs,
             get_downloads_path
         ])
         .run(tauri::generate_context!())
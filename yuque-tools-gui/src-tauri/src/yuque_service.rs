@@ -3,12 +3,13 @@ use std::collections::HashMap;
 
 use crate::cache::{CacheManager, CachedBookItem, CachedDocItem, CachedUser};
 use crate::libs::{
-    api_config::{Auth, Books, User},
-    constants::{ErrorMessages, SuccessMessages},
+    api_config::{Auth, Books, Documents, User},
     crypto::CryptoUtils,
+    doc_filter::{DocFilter, DocSelector},
     doc_parser::DocParser,
     export_utils::ExportUtils,
     http_utils::HttpUtils,
+    i18n::t,
     models::*,
 };
 
@@ -110,6 +111,8 @@ impl YuqueService {
                         sibling_uuid: doc.sibling_uuid.clone(),
                         level: doc.level,
                         doc_full_path: doc.doc_full_path.clone(),
+                        updated_at: doc.updated_at.clone(),
+                        author: doc.author.clone(),
                     })
                     .collect(),
             })
@@ -206,7 +209,7 @@ impl YuqueService {
 
                 return Ok(LoginResponse {
                     success: true,
-                    message: SuccessMessages::LOGIN_SUCCESS.to_string(),
+                    message: t("gui-login-success").to_string(),
                     user_info: Some(user_info),
                     cookies: Some(self.cookies.clone()),
                 });
@@ -218,7 +221,7 @@ impl YuqueService {
             message: data
                 .get("message")
                 .and_then(|v| v.as_str())
-                .unwrap_or(ErrorMessages::LOGIN_FAILED)
+                .unwrap_or(t("gui-login-failed"))
                 .to_string(),
             user_info: None,
             cookies: None,
@@ -233,7 +236,7 @@ impl YuqueService {
             return Ok(ApiResponse {
                 success: false,
                 data: None,
-                message: Some(ErrorMessages::NOT_LOGGED_IN.to_string()),
+                message: Some(t("gui-not-logged-in").to_string()),
             });
         }
 
@@ -286,7 +289,7 @@ impl YuqueService {
             Ok(ApiResponse {
                 success: false,
                 data: None,
-                message: Some(ErrorMessages::GET_USER_INFO_FAILED.to_string()),
+                message: Some(t("gui-get-user-info-failed").to_string()),
             })
         }
     }
@@ -337,7 +340,7 @@ impl YuqueService {
             return Ok(BooksResponse {
                 success: false,
                 data: None,
-                message: Some(ErrorMessages::NOT_LOGGED_IN.to_string()),
+                message: Some(t("gui-not-logged-in").to_string()),
                 total_count: None,
             });
         }
@@ -410,7 +413,7 @@ impl YuqueService {
         Ok(BooksResponse {
             success: true,
             data: Some(personal_books.clone()),
-            message: Some(SuccessMessages::PERSONAL_BOOKS.to_string()),
+            message: Some(t("gui-personal-books").to_string()),
             total_count: Some(personal_books.len()),
         })
     }
@@ -423,7 +426,7 @@ impl YuqueService {
             return Ok(BooksResponse {
                 success: false,
                 data: None,
-                message: Some(ErrorMessages::NOT_LOGGED_IN.to_string()),
+                message: Some(t("gui-not-logged-in").to_string()),
                 total_count: None,
             });
         }
@@ -496,7 +499,7 @@ impl YuqueService {
         Ok(BooksResponse {
             success: true,
             data: Some(team_books.clone()),
-            message: Some(SuccessMessages::TEAM_BOOKS.to_string()),
+            message: Some(t("gui-team-books").to_string()),
             total_count: Some(team_books.len()),
         })
     }
@@ -509,7 +512,7 @@ impl YuqueService {
             return Ok(BooksResponse {
                 success: false,
                 data: None,
-                message: Some(ErrorMessages::NOT_LOGGED_IN.to_string()),
+                message: Some(t("gui-not-logged-in").to_string()),
                 total_count: None,
             });
         }
@@ -548,7 +551,7 @@ impl YuqueService {
         Ok(BooksResponse {
             success: true,
             data: Some(all_books.clone()),
-            message: Some(SuccessMessages::ALL_BOOKS.to_string()),
+            message: Some(t("gui-all-books").to_string()),
             total_count: Some(all_books.len()),
         })
     }
@@ -580,6 +583,8 @@ impl YuqueService {
                     sibling_uuid: cached_doc.sibling_uuid,
                     level: cached_doc.level,
                     doc_full_path: cached_doc.doc_full_path,
+                    updated_at: cached_doc.updated_at,
+                    author: cached_doc.author,
                 })
                 .collect();
 
@@ -604,7 +609,28 @@ impl YuqueService {
         let html_content = response.text().await?;
 
         // 使用文档解析器提取文档数据
-        let docs = DocParser::extract_docs_from_html(&html_content)?;
+        let mut docs = DocParser::extract_docs_from_html(&html_content)?;
+
+        // 目录中没有更新时间和作者，从文档列表接口补充，用于导出过滤
+        if let Some(book_id) = DocParser::extract_book_id_from_html(&html_content) {
+            match self
+                .client
+                .get(&Documents::book_docs_url(book_id))
+                .headers(HttpUtils::build_headers(&self.cookies))
+                .send()
+                .await
+            {
+                Ok(meta_response) if meta_response.status().is_success() => {
+                    if let Ok(meta_json) = meta_response.json::<serde_json::Value>().await {
+                        DocParser::merge_docs_meta(&mut docs, &meta_json["data"]);
+                    }
+                }
+                _ => println!(
+                    "Debug: 知识库 '{}/{}' 文档更新时间获取失败",
+                    user_login, book_slug
+                ),
+            }
+        }
 
         if !docs.is_empty() {
             // 保存到缓存
@@ -628,6 +654,8 @@ impl YuqueService {
                         sibling_uuid: doc.sibling_uuid.clone(),
                         level: doc.level,
                         doc_full_path: doc.doc_full_path.clone(),
+                        updated_at: doc.updated_at.clone(),
+                        author: doc.author.clone(),
                     })
                     .collect::<Vec<_>>(),
             ) {
@@ -677,8 +705,19 @@ impl YuqueService {
         doc: &DocItem,
         book_slug: &str,
         output_dir: &str,
+        include_hidden: bool,
+        filter: &DocFilter,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let user_login = self.user_info.as_ref().ok_or("用户未登录")?.login.clone();
+        let user_login = self
+            .user_info
+            .as_ref()
+            .ok_or(t("gui-not-logged-in"))?
+            .login
+            .clone();
+
+        if let Some(message) = DocSelector::new(include_hidden, filter)?.skip_message(doc) {
+            return Err(message.into());
+        }
 
         ExportUtils::export_document(
             &self.client,
@@ -697,8 +736,16 @@ impl YuqueService {
         docs: &[DocItem],
         book_slug: &str,
         output_dir: &str,
+        include_hidden: bool,
+        filter: &DocFilter,
     ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-        let user_login = self.user_info.as_ref().ok_or("用户未登录")?.login.clone();
+        let user_login = self
+            .user_info
+            .as_ref()
+            .ok_or(t("gui-not-logged-in"))?
+            .login
+            .clone();
+        let selector = DocSelector::new(include_hidden, filter)?;
 
         ExportUtils::export_documents(
             &self.client,
@@ -707,6 +754,7 @@ impl YuqueService {
             output_dir,
             &self.cookies,
             &user_login,
+            &selector,
         )
         .await
     }
@@ -2,6 +2,7 @@ import React, { useMemo, useState, useCallback, useEffect } from 'react'
 import { useExportStore } from '../stores/exportStore'
 import { useMessage } from '../hooks/useMessage'
 import type { ExportTask } from '../components/ExportQueuePanel'
+import { tauriApi } from '../services/tauriApi'
 
 // 使用全局的 DocItem 类型，删除本地接口定义
 
@@ -292,22 +293,32 @@ const BookManageDrawer: React.FC<BookManageDrawerProps> = ({
         slug: doc.slug || doc.uuid, // 如果没有slug，使用uuid作为fallback
         url: doc.url,
         docFullPath: doc.docFullPath || doc.title, // 直接获取完整路径，如果没有则使用标题作为fallback
+        visible: doc.visible,
+        updated_at: doc.updated_at, // 更新时间和作者用于导出过滤
+        author: doc.author,
       },
     }
   }
 
-  // 验证文档是否可导出的通用方法
-  const validateDocumentForExport = (doc: TreeNode): boolean => {
-    if (doc.type !== 'DOC') {
-      console.log('文档类型不是DOC，跳过导出:', doc.type)
-      showWarning(`跳过导出: ${doc.title} (不是文档类型)`)
-      return false
+  // 选出可导出的文档，分组、隐藏/草稿文档和过滤条件由后端按导出时的规则判断，与 ytool 一致
+  const selectDocumentsForExport = async (docs: TreeNode[]): Promise<TreeNode[]> => {
+    const result = await tauriApi.filterExportableDocs(docs)
+    if (!result.success) {
+      showWarning(`导出过滤条件有误: ${result.error}`)
+      return []
     }
-    return true
+    const exportable = new Set(result.uuids)
+    return docs.filter((doc) => {
+      if (exportable.has(doc.uuid)) {
+        return true
+      }
+      console.log('跳过导出:', doc.title)
+      return false
+    })
   }
 
   // 处理单个文档导出
-  const handleExportDocument = (doc: TreeNode) => {
+  const handleExportDocument = async (doc: TreeNode) => {
     console.log('=== handleExportDocument 被调用 ===')
     console.log('文档信息:', {
       title: doc.title,
@@ -318,18 +329,21 @@ const BookManageDrawer: React.FC<BookManageDrawerProps> = ({
       docFullPath: doc.docFullPath, // 添加 docFullPath 的日志
     })
 
-    if (validateDocumentForExport(doc)) {
-      console.log('文档类型是DOC，开始添加导出任务')
-      console.log('文档原始信息', doc)
-      console.log('文档完整路径:', doc.docFullPath) // 添加详细日志
-      const task = createExportTask(doc, false)
-      addTask(task)
-      showSuccess(`已添加导出任务: ${doc.title}`)
+    const [validDocument] = await selectDocumentsForExport([doc])
+    if (!validDocument) {
+      showWarning(`跳过导出: ${doc.title} (不是文档、隐藏文档或不符合过滤条件)`)
+      return
     }
+    console.log('文档类型是DOC，开始添加导出任务')
+    console.log('文档原始信息', doc)
+    console.log('文档完整路径:', doc.docFullPath) // 添加详细日志
+    const task = createExportTask(doc, false)
+    addTask(task)
+    showSuccess(`已添加导出任务: ${doc.title}`)
   }
 
   // 处理批量导出 - 使用树形结构获取所有文档
-  const handleBatchExport = () => {
+  const handleBatchExport = async () => {
     const documentsToExport = getAllDocumentNodes(treeData)
 
     if (documentsToExport.length === 0) {
@@ -338,12 +352,16 @@ const BookManageDrawer: React.FC<BookManageDrawerProps> = ({
     }
 
     // 过滤出可导出的文档
-    const validDocuments = documentsToExport.filter(validateDocumentForExport)
+    const validDocuments = await selectDocumentsForExport(documentsToExport)
 
     if (validDocuments.length === 0) {
       showWarning('没有有效的文档可导出')
       return
     }
+    const skippedCount = documentsToExport.length - validDocuments.length
+    if (skippedCount > 0) {
+      showWarning(`已跳过 ${skippedCount} 个隐藏或不符合过滤条件的文档`)
+    }
 
     // 批量创建导出任务
     validDocuments.forEach((doc) => {
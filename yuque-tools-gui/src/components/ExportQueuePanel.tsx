@@ -38,6 +38,8 @@ export interface ExportTask {
     open_window?: number
     prev_uuid?: string
     sibling_uuid?: string
+    updated_at?: string // 用于导出过滤
+    author?: string
   }
 }
 
@@ -311,14 +311,21 @@ const BooksPage: React.FC<BooksPageProps> = ({
     return documents
   }
 
-  // 验证文档是否可导出的通用方法
-  const validateDocumentForExport = (doc: TreeNode): boolean => {
-    if (doc.type !== 'DOC') {
-      console.log('文档类型不是DOC，跳过导出:', doc.type)
-      showWarning(`跳过导出: ${doc.title} (不是文档类型)`)
-      return false
+  // 选出可导出的文档，分组、隐藏/草稿文档和过滤条件由后端按导出时的规则判断，与 ytool 一致
+  const selectDocumentsForExport = async (docs: TreeNode[]): Promise<TreeNode[]> => {
+    const result = await tauriApi.filterExportableDocs(docs)
+    if (!result.success) {
+      showWarning(`导出过滤条件有误: ${result.error}`)
+      return []
     }
-    return true
+    const exportable = new Set(result.uuids)
+    return docs.filter((doc) => {
+      if (exportable.has(doc.uuid)) {
+        return true
+      }
+      console.log('跳过导出:', doc.title)
+      return false
+    })
   }
 
   // 创建导出任务的通用方法
@@ -345,12 +352,15 @@ const BooksPage: React.FC<BooksPageProps> = ({
         slug: doc.slug || doc.uuid, // 如果没有slug，使用uuid作为fallback
         url: doc.url,
         docFullPath: doc.docFullPath || doc.title, // 直接获取完整路径，如果没有则使用标题作为fallback
+        visible: doc.visible,
+        updated_at: doc.updated_at, // 更新时间和作者用于导出过滤
+        author: doc.author,
       },
     }
   }
 
   // 处理知识库导出
-  const handleExportBook = (book: BookItem) => {
+  const handleExportBook = async (book: BookItem) => {
     console.log('=== handleExportBook 被调用 ===')
     console.log('知识库信息:', {
       name: book.name,
@@ -372,12 +382,16 @@ const BooksPage: React.FC<BooksPageProps> = ({
     }
 
     // 过滤出可导出的文档
-    const validDocuments = documentsToExport.filter(validateDocumentForExport)
+    const validDocuments = await selectDocumentsForExport(documentsToExport)
 
     if (validDocuments.length === 0) {
       showWarning('没有有效的文档可导出')
       return
     }
+    const skippedCount = documentsToExport.length - validDocuments.length
+    if (skippedCount > 0) {
+      showWarning(`已跳过 ${skippedCount} 个隐藏或不符合过滤条件的文档`)
+    }
 
     // 批量创建导出任务
     validDocuments.forEach((doc) => {
@@ -1,6 +1,13 @@
 import { invoke } from '@tauri-apps/api/tauri'
 import { isTimeoutError } from '../utils/timeout'
 import { TimeoutClass } from '../utils/decorators'
+import { storageService } from './storage'
+
+// 是否导出隐藏/草稿文档，读取用户配置，默认不导出
+const shouldIncludeHidden = (): boolean => storageService.getUserConfig()?.include_hidden ?? false
+
+// 导出过滤条件，读取用户配置，与 ytool 配置中的 filter 一致
+const getDocFilter = (): DocFilter | undefined => storageService.getUserConfig()?.filter
 
 // Tauri API 服务
 @TimeoutClass(10000) // 为整个类添加10秒超时
@@ -272,6 +279,8 @@ class TauriApiService {
         },
         bookSlug: bookSlug,
         outputDir: `${outputDir}/yuque-exports`,
+        includeHidden: shouldIncludeHidden(),
+        filter: getDocFilter(),
       })) as string
 
       return { success: true, filePath }
@@ -311,6 +320,8 @@ class TauriApiService {
         })),
         bookSlug: bookSlug,
         outputDir: `${outputDir}/yuque-exports`,
+        includeHidden: shouldIncludeHidden(),
+        filter: getDocFilter(),
       })) as string[]
 
       return { success: true, filePaths }
@@ -326,6 +337,28 @@ class TauriApiService {
     }
   }
 
+  // 选出会被导出的文档，隐藏/草稿和过滤条件与导出时使用同一规则
+  async filterExportableDocs(
+    docs: DocItem[]
+  ): Promise<{ success: boolean; uuids?: string[]; error?: string }> {
+    try {
+      const uuids = (await invoke('filter_exportable_docs', {
+        docs: docs.map((doc) => ({
+          ...doc,
+          children: undefined, // 只需要文档本身，不传下级节点
+          doc_full_path: doc.docFullPath, // 确保字段名完全匹配
+        })),
+        includeHidden: shouldIncludeHidden(),
+        filter: getDocFilter(),
+      })) as string[]
+
+      return { success: true, uuids }
+    } catch (error) {
+      console.error('Failed to filter documents:', error)
+      return { success: false, error: String(error) }
+    }
+  }
+
   // 获取下载目录路径
   async getDownloadsPath(): Promise<string> {
     try {
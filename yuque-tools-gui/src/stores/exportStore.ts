@@ -110,11 +110,13 @@ export const useExportStore = create<ExportStore>((set, get) => ({
           uuid: pendingTask.docInfo.uuid,
           child_uuid: '',
           parent_uuid: '',
-          visible: 1,
+          visible: pendingTask.docInfo.visible ?? 1,
           url: pendingTask.docInfo.url,
           slug: pendingTask.docInfo.slug,
           level: 0,
           docFullPath: pendingTask.docInfo.docFullPath, // 添加 docFullPath 字段
+          updated_at: pendingTask.docInfo.updated_at, // 更新时间和作者用于导出过滤
+          author: pendingTask.docInfo.author,
         }
 
         const bookSlug = pendingTask.docInfo.bookSlug
@@ -11,6 +11,20 @@ declare global {
     skip: boolean
     line_break: boolean
     output: string
+    // 是否导出隐藏/草稿文档(visible 为 0)，与 ytool 的 include_hidden 一致
+    include_hidden?: boolean
+    // 导出过滤条件，与 ytool 配置中的 filter 一致
+    filter?: DocFilter
+  }
+
+  // 导出过滤条件，全部为空时不过滤
+  interface DocFilter {
+    include?: string[] // 包含的路径，glob 格式，如 book/目录/**
+    exclude?: string[] // 排除的路径，glob 格式
+    updated_since?: string // 只导出该日期(YYYY-MM-DD)及之后更新过的文档
+    author?: string // 只导出该作者(语雀login)的文档
+    title?: string // 标题正则
+    max_depth?: number // 最大目录深度，知识库下第一级为1，0表示不限制
   }
 
   // 语雀账户信息
@@ -65,6 +79,8 @@ declare global {
     level?: number
     bookSlug?: string // 知识库的slug，用于导出时构建正确的URL
     docFullPath?: string // 文档的完整路径，用于导出时的文件保存路径
+    updated_at?: string // 文档最后更新时间，用于导出过滤
+    author?: string // 文档作者login，用于导出过滤
   }
 
   // 知识库项目结构
@@ -7,6 +7,20 @@ export interface YuqueConfig {
   skip: boolean
   line_break: boolean
   output: string
+  // 是否导出隐藏/草稿文档(visible 为 0)，与 ytool 的 include_hidden 一致
+  include_hidden?: boolean
+  // 导出过滤条件，与 ytool 配置中的 filter 一致
+  filter?: DocFilter
+}
+
+// 导出过滤条件，全部为空时不过滤
+export interface DocFilter {
+  include?: string[] // 包含的路径，glob 格式，如 book/目录/**
+  exclude?: string[] // 排除的路径，glob 格式
+  updated_since?: string // 只导出该日期(YYYY-MM-DD)及之后更新过的文档
+  author?: string // 只导出该作者(语雀login)的文档
+  title?: string // 标题正则
+  max_depth?: number // 最大目录深度，知识库下第一级为1，0表示不限制
 }
 
 // 语雀账户信息
@@ -56,6 +70,8 @@ export interface DocItem {
   open_window?: number
   prev_uuid?: string
   sibling_uuid?: string
+  updated_at?: string
+  author?: string
 }
 
 // 树形节点接口
@@ -0,0 +1,472 @@
+/*
+ * Description: 导出为归档文件(tar.zst/zip)，文档和附件直接写入归档，附带manifest.json，可整体加密
+ * Created: 2026-10-19 02:11:33
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Cursor, Write},
+    path::Path,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    libs::{
+        cipher::{Cipher, ENCRYPTED_EXT},
+        tools::{sanitize_file_name, sha256_hex},
+    },
+    t,
+};
+
+/// 归档中的清单文件
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// 归档格式，由文件扩展名决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarZst,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// 从归档文件名识别格式，支持 .tar.zst/.tzst/.zip
+    pub fn from_path(path: &str) -> Result<Self, &'static str> {
+        let lower = path.to_lowercase();
+        if lower.ends_with(".tar.zst") || lower.ends_with(".tzst") {
+            Ok(ArchiveFormat::TarZst)
+        } else if lower.ends_with(".zip") {
+            Ok(ArchiveFormat::Zip)
+        } else {
+            Err(t!("archive-format-unsupported"))
+        }
+    }
+
+    fn extension(&self, path: &str) -> &'static str {
+        match self {
+            ArchiveFormat::TarZst if path.to_lowercase().ends_with(".tzst") => ".tzst",
+            ArchiveFormat::TarZst => ".tar.zst",
+            ArchiveFormat::Zip => ".zip",
+        }
+    }
+}
+
+/// 清单中的一项
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    /// 文档uuid，附件为空
+    pub uuid: String,
+    /// 归档中的路径
+    pub path: String,
+    /// sha256
+    pub hash: String,
+    pub size: u64,
+    /// 语雀上的更新时间，附件为空
+    pub updated_at: String,
+    /// 写入归档的时间
+    pub archived_at: String,
+}
+
+/// 归档清单
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Manifest {
+    /// ytool版本
+    pub version: String,
+    pub created_at: String,
+    /// 按知识库拆分时为该归档的知识库，否则为空
+    pub book: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// 归档的输出文件，加密时写入的内容先经过age加密
+enum ArchiveOutput {
+    Plain(fs::File),
+    Encrypted(age::stream::StreamWriter<fs::File>),
+}
+
+impl Write for ArchiveOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            ArchiveOutput::Plain(file) => file.write(buf),
+            ArchiveOutput::Encrypted(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            ArchiveOutput::Plain(file) => file.flush(),
+            ArchiveOutput::Encrypted(writer) => writer.flush(),
+        }
+    }
+}
+
+impl ArchiveOutput {
+    fn finish(self) -> io::Result<()> {
+        match self {
+            ArchiveOutput::Plain(mut file) => file.flush(),
+            ArchiveOutput::Encrypted(writer) => writer.finish().map(|_| ()),
+        }
+    }
+}
+
+enum ArchiveWriter {
+    TarZst(tar::Builder<zstd::Encoder<'static, ArchiveOutput>>),
+    Zip(ZipWriter<fs::File>),
+    /// zip需要回写文件头，加密时先在内存中生成，结束时整体加密写入
+    BufferedZip(ZipWriter<Cursor<Vec<u8>>>, ArchiveOutput),
+}
+
+impl ArchiveWriter {
+    fn create(
+        format: ArchiveFormat,
+        file_path: &str,
+        cipher: Option<&Cipher>,
+    ) -> Result<Self, &'static str> {
+        if let Some(dir) = Path::new(file_path).parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir).map_err(|_| t!("archive-create-failed"))?;
+            }
+        }
+        let file = fs::File::create(file_path).map_err(|_| t!("archive-create-failed"))?;
+        let output = match cipher {
+            Some(cipher) => ArchiveOutput::Encrypted(cipher.wrap_output(file)?),
+            None => ArchiveOutput::Plain(file),
+        };
+        match (format, output) {
+            (ArchiveFormat::TarZst, output) => {
+                let encoder =
+                    zstd::Encoder::new(output, 0).map_err(|_| t!("archive-create-failed"))?;
+                Ok(ArchiveWriter::TarZst(tar::Builder::new(encoder)))
+            }
+            (ArchiveFormat::Zip, ArchiveOutput::Plain(file)) => {
+                Ok(ArchiveWriter::Zip(ZipWriter::new(file)))
+            }
+            (ArchiveFormat::Zip, output) => Ok(ArchiveWriter::BufferedZip(
+                ZipWriter::new(Cursor::new(vec![])),
+                output,
+            )),
+        }
+    }
+
+    fn append(&mut self, path: &str, bytes: &[u8]) -> Result<(), &'static str> {
+        match self {
+            ArchiveWriter::TarZst(builder) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(bytes.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(Local::now().timestamp() as u64);
+                builder
+                    .append_data(&mut header, path, bytes)
+                    .map_err(|_| t!("archive-write-failed"))
+            }
+            ArchiveWriter::Zip(zip) => Self::append_zip(zip, path, bytes),
+            ArchiveWriter::BufferedZip(zip, _) => Self::append_zip(zip, path, bytes),
+        }
+    }
+
+    fn append_zip<W: Write + io::Seek>(
+        zip: &mut ZipWriter<W>,
+        path: &str,
+        bytes: &[u8],
+    ) -> Result<(), &'static str> {
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file(path, options)
+            .map_err(|_| t!("archive-write-failed"))?;
+        zip.write_all(bytes).map_err(|_| t!("archive-write-failed"))
+    }
+
+    fn finish(self) -> Result<(), &'static str> {
+        match self {
+            ArchiveWriter::TarZst(builder) => {
+                let encoder = builder
+                    .into_inner()
+                    .map_err(|_| t!("archive-write-failed"))?;
+                let output = encoder.finish().map_err(|_| t!("archive-write-failed"))?;
+                output.finish().map_err(|_| t!("archive-write-failed"))?;
+            }
+            ArchiveWriter::Zip(mut zip) => {
+                zip.finish().map_err(|_| t!("archive-write-failed"))?;
+            }
+            ArchiveWriter::BufferedZip(mut zip, mut output) => {
+                let buffer = zip.finish().map_err(|_| t!("archive-write-failed"))?;
+                output
+                    .write_all(buffer.get_ref())
+                    .map_err(|_| t!("archive-write-failed"))?;
+                output.finish().map_err(|_| t!("archive-write-failed"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 单个归档文件，写入的同时记录清单
+struct ArchiveFile {
+    file_path: String,
+    book: String,
+    writer: ArchiveWriter,
+    entries: Vec<ManifestEntry>,
+    paths: HashSet<String>,
+}
+
+/// 导出归档，按知识库拆分时每个知识库一个归档文件，在第一次写入时创建
+pub struct ExportArchive {
+    format: ArchiveFormat,
+    path: String,
+    split: bool,
+    /// 加密时归档文件名加上 .age
+    cipher: Option<Cipher>,
+    files: HashMap<String, ArchiveFile>,
+}
+
+impl std::fmt::Debug for ExportArchive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportArchive")
+            .field("format", &self.format)
+            .field("path", &self.path)
+            .field("split", &self.split)
+            .field("cipher", &self.cipher)
+            .finish()
+    }
+}
+
+impl ExportArchive {
+    /// # Arguments
+    /// * path - 归档文件路径，按知识库拆分时为 `名称-知识库.扩展名`
+    /// * split - 是否按知识库拆分
+    /// * cipher - 加密方式，为None时不加密
+    pub fn new(path: &str, split: bool, cipher: Option<Cipher>) -> Result<Self, &'static str> {
+        Ok(ExportArchive {
+            format: ArchiveFormat::from_path(path)?,
+            path: path.to_string(),
+            split,
+            cipher,
+            files: HashMap::new(),
+        })
+    }
+
+    /// 归档文件所在的目录
+    pub fn dir(&self) -> String {
+        match Path::new(&self.path).parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy().to_string(),
+            _ => ".".to_string(),
+        }
+    }
+
+    /// 按知识库拆分时的归档文件路径
+    pub fn book_file_path(&self, book: &str) -> String {
+        let extension = self.format.extension(&self.path);
+        let stem = &self.path[..self.path.len() - extension.len()];
+        let book = sanitize_file_name(book, "_").replace(char::is_whitespace, "_");
+        format!("{}-{}{}", stem, book, extension)
+    }
+
+    /// 写入一个文件，同一归档中已存在的路径会被忽略
+    /// # Arguments
+    /// * book - 所属知识库
+    /// * path - 归档中的路径
+    /// * bytes - 内容
+    /// * uuid - 文档uuid，附件为空
+    /// * updated_at - 语雀上的更新时间，附件为空
+    pub fn add(
+        &mut self,
+        book: &str,
+        path: &str,
+        bytes: &[u8],
+        uuid: &str,
+        updated_at: &str,
+    ) -> Result<(), &'static str> {
+        let key = if self.split { book } else { "" };
+        if !self.files.contains_key(key) {
+            let mut file_path = if self.split {
+                self.book_file_path(book)
+            } else {
+                self.path.to_string()
+            };
+            if self.cipher.is_some() {
+                file_path.push_str(ENCRYPTED_EXT);
+            }
+            let file = ArchiveFile {
+                writer: ArchiveWriter::create(self.format, &file_path, self.cipher.as_ref())?,
+                file_path,
+                book: key.to_string(),
+                entries: vec![],
+                paths: HashSet::new(),
+            };
+            self.files.insert(key.to_string(), file);
+        }
+        let file = self.files.get_mut(key).unwrap();
+        if !file.paths.insert(path.to_string()) {
+            return Ok(());
+        }
+
+        file.writer.append(path, bytes)?;
+        file.entries.push(ManifestEntry {
+            uuid: uuid.to_string(),
+            path: path.to_string(),
+            hash: sha256_hex(bytes),
+            size: bytes.len() as u64,
+            updated_at: updated_at.to_string(),
+            archived_at: Local::now().to_rfc3339(),
+        });
+        Ok(())
+    }
+
+    /// 是否已经写入过文件
+    pub fn contains(&self, book: &str, path: &str) -> bool {
+        let key = if self.split { book } else { "" };
+        self.files
+            .get(key)
+            .is_some_and(|file| file.paths.contains(path))
+    }
+
+    /// 写入清单并结束所有归档，返回生成的归档文件
+    pub fn finish(&mut self) -> Result<Vec<String>, &'static str> {
+        let mut files: Vec<ArchiveFile> = self.files.drain().map(|(_, file)| file).collect();
+        files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+        let mut file_paths = vec![];
+        for mut file in files {
+            let manifest = Manifest {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                created_at: Local::now().to_rfc3339(),
+                book: file.book.to_string(),
+                entries: file.entries,
+            };
+            let json = serde_json::to_string_pretty(&manifest).unwrap();
+            file.writer.append(MANIFEST_FILE, json.as_bytes())?;
+            file.writer.finish()?;
+            file_paths.push(file.file_path);
+        }
+        Ok(file_paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use super::*;
+
+    /// 读出归档中的所有文件
+    fn read_archive(file_path: &str) -> HashMap<String, String> {
+        let bytes = fs::read(file_path).unwrap();
+        let mut files = HashMap::new();
+        if file_path.ends_with(".zip") {
+            let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+            for i in 0..zip.len() {
+                let mut entry = zip.by_index(i).unwrap();
+                let mut content = String::new();
+                entry.read_to_string(&mut content).unwrap();
+                files.insert(entry.name().to_string(), content);
+            }
+        } else {
+            let decoder = zstd::Decoder::new(Cursor::new(bytes)).unwrap();
+            let mut tar = tar::Archive::new(decoder);
+            for entry in tar.entries().unwrap() {
+                let mut entry = entry.unwrap();
+                let path = entry.path().unwrap().to_string_lossy().to_string();
+                let mut content = String::new();
+                entry.read_to_string(&mut content).unwrap();
+                files.insert(path, content);
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn test_archive_format() {
+        assert_eq!(
+            ArchiveFormat::from_path("out.TAR.ZST"),
+            Ok(ArchiveFormat::TarZst)
+        );
+        assert_eq!(ArchiveFormat::from_path("out.zip"), Ok(ArchiveFormat::Zip));
+        assert!(ArchiveFormat::from_path("out.tar.gz").is_err());
+
+        let archive = ExportArchive::new("backup/out.tar.zst", true, None).unwrap();
+        assert_eq!(
+            archive.book_file_path("Rust 笔记"),
+            "backup/out-Rust_笔记.tar.zst"
+        );
+    }
+
+    #[test]
+    fn test_export_archive() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for name in ["out.tar.zst", "out.zip"] {
+            let path = dir.path().join(name).to_string_lossy().to_string();
+            let mut archive = ExportArchive::new(&path, false, None).unwrap();
+            archive
+                .add(
+                    "book",
+                    "book/长路径/文档.md",
+                    b"# doc",
+                    "uuid-a",
+                    "2023-09-01",
+                )
+                .unwrap();
+            archive
+                .add("book", "attachments/a.png", b"png", "", "")
+                .unwrap();
+            // 同一附件只写入一次
+            archive
+                .add("book", "attachments/a.png", b"png", "", "")
+                .unwrap();
+            assert!(archive.contains("book", "attachments/a.png"));
+            assert_eq!(archive.finish().unwrap(), vec![path.to_string()]);
+
+            let files = read_archive(&path);
+            assert_eq!(files["book/长路径/文档.md"], "# doc");
+            let manifest: Manifest = serde_json::from_str(&files[MANIFEST_FILE]).unwrap();
+            assert_eq!(manifest.entries.len(), 2);
+            assert_eq!(manifest.entries[0].uuid, "uuid-a");
+            assert_eq!(manifest.entries[0].hash, sha256_hex(b"# doc"));
+            assert_eq!(manifest.entries[1].size, 3);
+        }
+
+        let path = dir.path().join("split.zip").to_string_lossy().to_string();
+        let mut archive = ExportArchive::new(&path, true, None).unwrap();
+        archive.add("a", "a/1.md", b"1", "1", "").unwrap();
+        archive.add("b", "b/2.md", b"2", "2", "").unwrap();
+        let file_paths = archive.finish().unwrap();
+        assert_eq!(file_paths.len(), 2);
+        let files = read_archive(&file_paths[1]);
+        assert!(files.contains_key("b/2.md"));
+        assert!(!files.contains_key("a/1.md"));
+        let manifest: Manifest = serde_json::from_str(&files[MANIFEST_FILE]).unwrap();
+        assert_eq!(manifest.book, "b");
+    }
+
+    #[test]
+    fn test_encrypted_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = age::x25519::Identity::generate();
+        let cipher = Cipher::from_recipients(&[identity.to_public().to_string()]).unwrap();
+
+        for name in ["out.tar.zst", "out.zip"] {
+            let path = dir.path().join(name).to_string_lossy().to_string();
+            let mut archive = ExportArchive::new(&path, false, Some(cipher.clone())).unwrap();
+            archive.add("book", "book/a.md", b"# a", "a", "").unwrap();
+            let encrypted_path = format!("{}{}", path, ENCRYPTED_EXT);
+            assert_eq!(archive.finish().unwrap(), vec![encrypted_path.to_string()]);
+            assert!(!Path::new(&path).exists());
+
+            // 解密后是普通归档
+            let mut keyring = crate::libs::cipher::Keyring::new(vec![identity.clone()], None);
+            let mut plain = fs::File::create(&path).unwrap();
+            keyring
+                .decrypt(fs::File::open(&encrypted_path).unwrap(), &mut plain)
+                .unwrap();
+            let files = read_archive(&path);
+            assert_eq!(files["book/a.md"], "# a");
+            assert!(files.contains_key(MANIFEST_FILE));
+        }
+    }
+}
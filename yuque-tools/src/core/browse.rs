@@ -0,0 +1,429 @@
+/*
+ * Description: 离线浏览与搜索本地缓存的知识库目录及导出的文档
+ * Created: 2026-10-19 00:18:58
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use serde::Serialize;
+use serde_json::json;
+use tracing::debug;
+
+use super::scheduler::Scheduler;
+use crate::{
+    libs::{
+        constants::{
+            schema::{cache_book::BookItem, TreeNone},
+            GLOBAL_CONFIG,
+        },
+        log::Log,
+        search_index::SearchIndex,
+        tools,
+    },
+    t,
+};
+
+/// 搜索结果
+#[derive(Serialize, Debug)]
+pub struct SearchHit {
+    pub book: String,
+    pub book_slug: String,
+    /// 可直接用于 toc_range 的路径
+    pub path: String,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub uuid: String,
+    pub url: String,
+    pub visible: u8,
+    pub score: u32,
+}
+
+pub struct Browse;
+
+impl Browse {
+    /// 查看知识库列表或某个知识库/目录下的文档树
+    /// # Arguments
+    /// * target - 知识库名称或slug，可带目录，如 book/目录
+    /// * as_json - 是否以JSON输出
+    pub fn list(target: Option<String>, as_json: bool) -> Result<(), &'static str> {
+        let books_info = Self::get_books_info()?;
+
+        let target = match target {
+            Some(target) if !target.is_empty() => target,
+            _ => {
+                Self::print_books(&books_info, as_json);
+                return Ok(());
+            }
+        };
+
+        let (book_key, sub_path) = match target.split_once("/") {
+            Some((book_key, sub_path)) => (book_key, sub_path.trim_end_matches("/")),
+            None => (target.as_str(), ""),
+        };
+
+        let book = books_info
+            .iter()
+            .find(|item| item.name == book_key || item.slug == book_key)
+            .ok_or(t!("book-not-found"))?;
+
+        let tree = Scheduler::build_book_tree(book);
+
+        let nodes = if sub_path.is_empty() {
+            tree
+        } else {
+            let target_path = format!("{}/{}", book.name, sub_path);
+            match Self::find_node(&tree, &target_path) {
+                Some(node) => vec![node.clone()],
+                None => return Err(t!("browse-dir-not-found")),
+            }
+        };
+
+        if as_json {
+            println!("{}", serde_json::to_string_pretty(&nodes).unwrap());
+        } else {
+            println!(
+                "{} {}  {}/{}  {} 篇文档",
+                Self::book_marker(book),
+                book.name,
+                book.user_login,
+                book.slug,
+                Self::count_docs(&nodes)
+            );
+            Self::print_tree(&nodes, 1);
+        }
+
+        Ok(())
+    }
+
+    /// 在所有缓存的知识库中模糊搜索文档标题
+    /// # Arguments
+    /// * query - 搜索内容
+    /// * limit - 最多返回的数量
+    /// * as_json - 是否以JSON输出
+    pub fn search(query: &str, limit: usize, as_json: bool) -> Result<(), &'static str> {
+        let books_info = Self::get_books_info()?;
+        let hits = Self::search_books(&books_info, query, limit);
+
+        if as_json {
+            println!("{}", serde_json::to_string_pretty(&hits).unwrap());
+        } else if hits.is_empty() {
+            Log::warn(&t!("browse-search-no-match", query = query));
+        } else {
+            for hit in &hits {
+                let marker = if hit.node_type == "TITLE" {
+                    "📁"
+                } else {
+                    "📄"
+                };
+                let hidden = if hit.visible == 1 { "" } else { " [隐藏]" };
+                println!("{} {}{}", marker, hit.path, hidden);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 为导出的文档建立/增量更新全文索引
+    /// # Arguments
+    /// * rebuild - 是否丢弃旧索引重新建立
+    pub fn index(rebuild: bool) -> Result<(), &'static str> {
+        let root = &GLOBAL_CONFIG.target_output_dir;
+        if !std::path::Path::new(root).exists() {
+            return Err(t!("output-dir-missing"));
+        }
+
+        Log::info(t!("browse-indexing"));
+        match SearchIndex::refresh(&GLOBAL_CONFIG.search_index_file, root, rebuild) {
+            Ok(summary) => {
+                Log::success(&t!(
+                    "browse-index-done",
+                    added = summary.added,
+                    updated = summary.updated,
+                    removed = summary.removed
+                ));
+                Ok(())
+            }
+            Err(err) => {
+                debug!("{}", err);
+                Err(t!("browse-index-failed"))
+            }
+        }
+    }
+
+    /// 在导出的文档中全文搜索
+    /// # Arguments
+    /// * query - 搜索内容
+    /// * limit - 最多返回的数量
+    /// * as_json - 是否以JSON输出
+    pub fn grep(query: &str, limit: usize, as_json: bool) -> Result<(), &'static str> {
+        if !std::path::Path::new(&GLOBAL_CONFIG.search_index_file).exists() {
+            return Err(t!("browse-index-missing"));
+        }
+
+        let index = SearchIndex::load(&GLOBAL_CONFIG.search_index_file);
+        let hits = index.search(query, limit);
+
+        if as_json {
+            println!("{}", serde_json::to_string_pretty(&hits).unwrap());
+        } else if hits.is_empty() {
+            Log::warn(&t!("browse-grep-no-match", query = query));
+        } else {
+            for hit in &hits {
+                println!("📄 {}  {}/{}", hit.title, index.root, hit.path);
+                if !hit.snippet.is_empty() {
+                    println!("   {}", hit.snippet);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 在知识库列表中搜索，按匹配度排序
+    pub fn search_books(books_info: &Vec<BookItem>, query: &str, limit: usize) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = vec![];
+
+        for book in books_info {
+            let mut nodes: Vec<TreeNone> = vec![];
+            Self::flatten_nodes(&Scheduler::build_book_tree(book), &mut nodes);
+
+            for node in nodes {
+                if let Some(score) = Self::fuzzy_score(query, &node.title) {
+                    hits.push(SearchHit {
+                        book: book.name.to_string(),
+                        book_slug: book.slug.to_string(),
+                        path: node.full_path,
+                        title: node.title,
+                        node_type: node.node_type,
+                        uuid: node.uuid,
+                        url: node.url,
+                        visible: node.visible,
+                        score,
+                    });
+                }
+            }
+        }
+
+        hits.sort_by(|a, b| b.score.cmp(&a.score).then(a.path.cmp(&b.path)));
+        hits.truncate(limit);
+        hits
+    }
+
+    /// 模糊匹配得分，不匹配时返回None
+    /// 完全相同 > 前缀 > 包含 > 按顺序包含所有字符(连续的字符得分更高)
+    pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+        let query = query.trim().to_lowercase();
+        let text = text.to_lowercase();
+
+        if query.is_empty() {
+            return None;
+        }
+        if text == query {
+            return Some(1000);
+        }
+        if text.starts_with(&query) {
+            return Some(800);
+        }
+        if text.contains(&query) {
+            return Some(600);
+        }
+
+        let mut query_chars = query.chars().peekable();
+        let mut score: u32 = 100;
+        let mut prev_matched = false;
+
+        for c in text.chars() {
+            match query_chars.peek() {
+                Some(q) if *q == c => {
+                    query_chars.next();
+                    if prev_matched {
+                        score += 10;
+                    }
+                    prev_matched = true;
+                }
+                Some(_) => {
+                    prev_matched = false;
+                    score = score.saturating_sub(1);
+                }
+                None => break,
+            }
+        }
+
+        if query_chars.peek().is_none() {
+            Some(score.max(1))
+        } else {
+            None
+        }
+    }
+
+    fn get_books_info() -> Result<Vec<BookItem>, &'static str> {
+        tools::get_cache_books_info().map_err(|_| t!("books-cache-missing"))
+    }
+
+    fn book_marker(book: &BookItem) -> &'static str {
+        if book.book_type == "owner" {
+            "👤"
+        } else {
+            "👥"
+        }
+    }
+
+    /// 打印知识库列表
+    fn print_books(books_info: &Vec<BookItem>, as_json: bool) {
+        if as_json {
+            let books: Vec<_> = books_info
+                .iter()
+                .map(|book| {
+                    json!({
+                        "name": book.name,
+                        "slug": book.slug,
+                        "user_login": book.user_login,
+                        "book_type": book.book_type,
+                        "doc_count": book.docs.iter().filter(|doc| doc.node_type == "DOC").count(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&books).unwrap());
+        } else {
+            for book in books_info {
+                println!(
+                    "{} {}  {}/{}  [{}]  {} 篇文档",
+                    Self::book_marker(book),
+                    book.name,
+                    book.user_login,
+                    book.slug,
+                    book.book_type,
+                    book.docs
+                        .iter()
+                        .filter(|doc| doc.node_type == "DOC")
+                        .count()
+                );
+            }
+        }
+    }
+
+    /// 按层级缩进打印文档树，目录后面是其下的文档数量
+    fn print_tree(nodes: &Vec<TreeNone>, depth: usize) {
+        for node in nodes {
+            let indent = "  ".repeat(depth);
+            let hidden = if node.visible == 1 { "" } else { " [隐藏]" };
+            if node.node_type == "TITLE" {
+                println!(
+                    "{}📁 {} ({}){}",
+                    indent,
+                    node.title,
+                    Self::count_docs(&node.children),
+                    hidden
+                );
+            } else if node.children.is_empty() {
+                println!("{}📄 {}{}", indent, node.title, hidden);
+            } else {
+                println!(
+                    "{}📄 {} ({}){}",
+                    indent,
+                    node.title,
+                    Self::count_docs(&node.children),
+                    hidden
+                );
+            }
+            Self::print_tree(&node.children, depth + 1);
+        }
+    }
+
+    /// 统计树中的文档数量
+    pub fn count_docs(nodes: &[TreeNone]) -> usize {
+        nodes
+            .iter()
+            .map(|node| {
+                let current = if node.node_type == "DOC" { 1 } else { 0 };
+                current + Self::count_docs(&node.children)
+            })
+            .sum()
+    }
+
+    /// 按完整路径查找节点
+    fn find_node<'a>(nodes: &'a Vec<TreeNone>, full_path: &str) -> Option<&'a TreeNone> {
+        for node in nodes {
+            if node.full_path == full_path {
+                return Some(node);
+            }
+            if let Some(found) = Self::find_node(&node.children, full_path) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// 树形结构转扁平列表
+    pub fn flatten_nodes(nodes: &Vec<TreeNone>, list: &mut Vec<TreeNone>) {
+        for node in nodes {
+            list.push(node.clone());
+            Self::flatten_nodes(&node.children, list);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::{
+        constants::schema::cache_book::DocItem,
+        fixtures::{book_item, doc_item},
+    };
+
+    fn doc(title: &str, node_type: &str, uuid: &str, parent_uuid: &str) -> DocItem {
+        DocItem {
+            node_type: node_type.to_string(),
+            ..doc_item(title, uuid, parent_uuid)
+        }
+    }
+
+    fn book() -> BookItem {
+        book_item(
+            "test-book",
+            "dd67e4",
+            vec![
+                doc("测试目录", "TITLE", "a", ""),
+                doc("Rust入门", "DOC", "b", "a"),
+                doc("Rust进阶", "DOC", "c", "a"),
+                doc("随笔", "DOC", "d", ""),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(Browse::fuzzy_score("rust入门", "Rust入门"), Some(1000));
+        assert_eq!(Browse::fuzzy_score("rust", "Rust入门"), Some(800));
+        assert_eq!(Browse::fuzzy_score("入门", "Rust入门"), Some(600));
+        assert!(Browse::fuzzy_score("rs门", "Rust入门").is_some());
+        assert!(Browse::fuzzy_score("门rs", "Rust入门").is_none());
+        assert!(Browse::fuzzy_score("", "Rust入门").is_none());
+    }
+
+    #[test]
+    fn test_search_books() {
+        let hits = Browse::search_books(&vec![book()], "rust", 10);
+        let paths: Vec<&str> = hits.iter().map(|hit| hit.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["test-book/测试目录/Rust入门", "test-book/测试目录/Rust进阶"]
+        );
+
+        let hits = Browse::search_books(&vec![book()], "rust", 1);
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[test]
+    fn test_count_and_find() {
+        let tree = Scheduler::build_book_tree(&book());
+        assert_eq!(Browse::count_docs(&tree), 3);
+
+        let dir = Browse::find_node(&tree, "test-book/测试目录").unwrap();
+        assert_eq!(dir.children.len(), 2);
+        assert!(Browse::find_node(&tree, "test-book/不存在").is_none());
+    }
+}
@@ -0,0 +1,636 @@
+/*
+ * Description: 把整个知识库合并导出为单个文件(Markdown/HTML/EPUB)，便于离线阅读和分享
+ * Created: 2026-10-19 00:35:48
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{collections::HashMap, fs, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::ValueEnum;
+use epub_builder::{EpubBuilder, EpubContent, ZipLibrary};
+use pulldown_cmark::{html, Options, Parser};
+use regex::{Captures, Regex};
+use tracing::debug;
+
+use super::{
+    filter::{self, TreeFilter},
+    mirror::MirrorSource,
+    push::Push,
+    scheduler::Scheduler,
+    yuque::YuqueApi,
+};
+use crate::{
+    libs::{
+        constants::{schema::TreeNone, GLOBAL_CONFIG},
+        file::File,
+        log::Log,
+        progress::Progress,
+        request::Request,
+        tools,
+    },
+    t,
+};
+
+/// 合并文件的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BundleFormat {
+    /// 单个Markdown文件
+    Md,
+    /// 单页HTML，图片内嵌
+    Html,
+    /// EPUB电子书，图片内嵌
+    Epub,
+}
+
+impl BundleFormat {
+    pub fn ext(&self) -> &'static str {
+        match self {
+            BundleFormat::Md => "md",
+            BundleFormat::Html => "html",
+            BundleFormat::Epub => "epub",
+        }
+    }
+}
+
+/// 合并文件中的一个章节，按目录顺序排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleDoc {
+    pub title: String,
+    /// 目录深度，知识库下第一级为1
+    pub depth: usize,
+    /// 章节锚点
+    pub anchor: String,
+    /// 文档slug，分组为空
+    pub url: String,
+    /// 文档内容，分组为空
+    pub body: String,
+}
+
+/// 获取图片用到的接口，测试时替换为本地模拟
+pub trait ImageSource {
+    async fn get_image(&self, url: &str) -> Result<Vec<u8>, bool>;
+}
+
+impl ImageSource for YuqueApi {
+    async fn get_image(&self, url: &str) -> Result<Vec<u8>, bool> {
+        Request::fetch_bytes(url).await.map_err(|_| false)
+    }
+}
+
+pub struct Bundle;
+
+impl Bundle {
+    /// 合并导出启动程序
+    /// # Arguments
+    /// * books - 知识库名称或slug
+    /// * format - 输出格式
+    pub async fn start(books: Vec<String>, format: BundleFormat) -> Result<(), &'static str> {
+        Push::ensure_login().await?;
+
+        if tools::get_cache_books_info().is_err() {
+            let _ = YuqueApi::get_user_bookstacks().await;
+        }
+        let books_info = tools::get_cache_books_info().map_err(|_| t!("books-read-failed"))?;
+
+        // 隐藏文档和过滤条件沿用导出配置
+        let (include_hidden, doc_filter) = match tools::get_user_config() {
+            Ok(user_config) => (user_config.include_hidden, user_config.filter),
+            Err(_) => (false, Default::default()),
+        };
+        let matcher = filter::compile(&doc_filter).map_err(|err| {
+            Log::error(&err);
+            t!("filter-invalid")
+        })?;
+
+        let f = File::new();
+        if f.mkdir(&GLOBAL_CONFIG.target_output_dir).is_err() {
+            return Err(t!("output-dir-create-failed"));
+        }
+
+        for book in &books {
+            let item = match books_info
+                .iter()
+                .find(|item| &item.name == book || &item.slug == book)
+            {
+                Some(item) => item,
+                None => {
+                    Log::warn(&t!("bundle-book-not-found", book = book));
+                    continue;
+                }
+            };
+
+            let mut tree = Scheduler::build_book_tree(item);
+            if !matcher.is_empty() {
+                tree = matcher.prune(tree).0;
+            }
+            let mut docs = Self::collect_docs(&tree, 1, include_hidden);
+            if docs.is_empty() {
+                Log::warn(&t!("bundle-book-empty", book = item.name));
+                continue;
+            }
+
+            let namespace = format!("{}/{}", item.user_login, item.slug);
+            Log::info(&t!("bundle-start", book = item.name, count = docs.len()));
+            Self::fill_bodies(&YuqueApi {}, &namespace, &mut docs).await;
+
+            let content = match format {
+                BundleFormat::Md => {
+                    Self::render_markdown(&item.name, &namespace, &docs).into_bytes()
+                }
+                BundleFormat::Html => {
+                    Self::render_html(&YuqueApi {}, &item.name, &namespace, &docs)
+                        .await
+                        .into_bytes()
+                }
+                BundleFormat::Epub => {
+                    match Self::render_epub(&YuqueApi {}, &item.name, &namespace, &docs).await {
+                        Ok(content) => content,
+                        Err(err) => {
+                            Log::error(&t!("bundle-epub-failed", book = item.name));
+                            debug!("{}", err);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let target_file = format!(
+                "{}/{}.{}",
+                GLOBAL_CONFIG.target_output_dir,
+                tools::sanitize_file_name(&item.name, ""),
+                format.ext()
+            );
+            match fs::write(&target_file, content) {
+                Ok(_) => Log::success(&t!("bundle-exported", file = target_file)),
+                Err(_) => Log::error(&t!("file-write-failed", file = target_file)),
+            }
+        }
+        Ok(())
+    }
+
+    /// 按目录顺序收集章节，隐藏文档不导出但保留其下的可见文档
+    /// # Arguments
+    /// * nodes - 文档树
+    /// * depth - 当前深度
+    /// * include_hidden - 是否导出隐藏文档
+    pub fn collect_docs(
+        nodes: &Vec<TreeNone>,
+        depth: usize,
+        include_hidden: bool,
+    ) -> Vec<BundleDoc> {
+        let mut docs = vec![];
+        for node in nodes {
+            let is_visible = node.visible == 1 || include_hidden;
+            let children = Self::collect_docs(
+                &node.children,
+                if is_visible { depth + 1 } else { depth },
+                include_hidden,
+            );
+
+            // 没有内容的分组不输出
+            let is_doc = node.node_type == "DOC";
+            if is_visible && (is_doc || !children.is_empty()) {
+                docs.push(BundleDoc {
+                    title: node.title.to_string(),
+                    depth,
+                    anchor: if is_doc {
+                        format!("doc-{}", node.url)
+                    } else {
+                        format!("title-{}", node.uuid)
+                    },
+                    url: if is_doc {
+                        node.url.to_string()
+                    } else {
+                        "".to_string()
+                    },
+                    body: "".to_string(),
+                });
+            }
+            docs.extend(children);
+        }
+        docs
+    }
+
+    /// 依次获取文档内容
+    async fn fill_bodies(api: &impl MirrorSource, namespace: &str, docs: &mut [BundleDoc]) {
+        let total = docs.iter().filter(|doc| !doc.url.is_empty()).count();
+        let progress = Progress::new(
+            t!("progress-docs"),
+            vec![(namespace.to_string(), total as u64)],
+        );
+
+        for doc in docs.iter_mut() {
+            if doc.url.is_empty() {
+                continue;
+            }
+            progress.start(namespace, &doc.title);
+            match api.get_body(namespace, &doc.url).await {
+                Ok(body) => {
+                    progress.finish(namespace, &doc.title, Some(body.len() as u64));
+                    doc.body = body;
+                }
+                Err(_) => {
+                    progress.finish(namespace, &doc.title, None);
+                    Log::warn(&t!("bundle-body-failed", title = doc.title));
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(GLOBAL_CONFIG.duration as u64)).await;
+        }
+        progress.finish_all();
+    }
+
+    /// 标题整体下移，使文档内的标题低于章节标题，代码块中的内容不处理
+    /// # Arguments
+    /// * body - 文档内容
+    /// * offset - 下移的级数，最多到六级标题
+    pub fn shift_headings(body: &str, offset: usize) -> String {
+        let heading = Regex::new(r"^(#{1,6})(\s.*)?$").unwrap();
+        let mut in_code = false;
+
+        body.lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                    in_code = !in_code;
+                }
+                if in_code {
+                    return line.to_string();
+                }
+                match heading.captures(line) {
+                    Some(caps) => format!(
+                        "{}{}",
+                        "#".repeat((caps[1].len() + offset).min(6)),
+                        caps.get(2).map_or("", |rest| rest.as_str())
+                    ),
+                    None => line.to_string(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// 把指向本知识库文档的链接改为章节锚点
+    /// # Arguments
+    /// * body - 文档内容
+    /// * namespace - 知识库 用户/slug
+    /// * docs - 所有章节
+    /// * target - 锚点转链接地址
+    fn fix_links(
+        body: &str,
+        namespace: &str,
+        docs: &[BundleDoc],
+        target: &dyn Fn(&str) -> String,
+    ) -> String {
+        let anchors: HashMap<&str, &str> = docs
+            .iter()
+            .filter(|doc| !doc.url.is_empty())
+            .map(|doc| (doc.url.as_str(), doc.anchor.as_str()))
+            .collect();
+        let link = Regex::new(&format!(
+            r"\]\((?:https?://[^/\s)]+)?/{}/([\w-]+)(?:[?#][^)\s]*)?\)",
+            regex::escape(namespace)
+        ))
+        .unwrap();
+
+        link.replace_all(body, |caps: &Captures| match anchors.get(&caps[1]) {
+            Some(anchor) => format!("]({})", target(anchor)),
+            None => caps[0].to_string(),
+        })
+        .to_string()
+    }
+
+    /// 生成目录
+    fn build_toc(docs: &[BundleDoc]) -> String {
+        docs.iter()
+            .map(|doc| {
+                format!(
+                    "{}- [{}](#{})\n",
+                    "  ".repeat(doc.depth - 1),
+                    doc.title,
+                    doc.anchor
+                )
+            })
+            .collect()
+    }
+
+    /// 章节标题和内容，标题级别按深度递增
+    fn render_chapter(doc: &BundleDoc, base_level: usize) -> String {
+        let level = (doc.depth + base_level).min(6);
+        let mut chapter = format!("{} {}\n\n", "#".repeat(level), doc.title);
+        if !doc.body.is_empty() {
+            chapter.push_str(&Self::shift_headings(doc.body.trim(), level));
+            chapter.push_str("\n\n");
+        }
+        chapter
+    }
+
+    /// 合并为单个Markdown，知识库名称为一级标题
+    pub fn render_markdown(book_name: &str, namespace: &str, docs: &Vec<BundleDoc>) -> String {
+        let mut content = format!("# {}\n\n## 目录\n\n{}\n", book_name, Self::build_toc(docs));
+        for doc in docs {
+            content.push_str(&format!("<a id=\"{}\"></a>\n\n", doc.anchor));
+            let chapter = Self::render_chapter(doc, 1);
+            content.push_str(&Self::fix_links(&chapter, namespace, docs, &|anchor| {
+                format!("#{}", anchor)
+            }));
+        }
+        content
+    }
+
+    /// 合并为单页HTML，图片以data URI内嵌
+    async fn render_html(
+        api: &impl ImageSource,
+        book_name: &str,
+        namespace: &str,
+        docs: &Vec<BundleDoc>,
+    ) -> String {
+        let markdown = Self::render_markdown(book_name, namespace, docs);
+        let images = Self::fetch_images(api, &markdown).await;
+        let data_uris: HashMap<String, String> = images
+            .iter()
+            .map(|(url, bytes)| {
+                (
+                    url.to_string(),
+                    format!(
+                        "data:{};base64,{}",
+                        Self::image_mime(url),
+                        STANDARD.encode(bytes)
+                    ),
+                )
+            })
+            .collect();
+        let markdown = Self::replace_images(&markdown, &data_uris);
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            Self::escape_html(book_name),
+            "body{max-width:860px;margin:0 auto;padding:24px;line-height:1.7}img{max-width:100%}pre{overflow:auto;background:#f6f8fa;padding:12px}table{border-collapse:collapse}td,th{border:1px solid #ddd;padding:4px 8px}",
+            Self::markdown_to_html(&markdown)
+        )
+    }
+
+    /// 生成EPUB，每篇文档一个章节，图片作为资源内嵌
+    async fn render_epub(
+        api: &impl ImageSource,
+        book_name: &str,
+        namespace: &str,
+        docs: &Vec<BundleDoc>,
+    ) -> Result<Vec<u8>, String> {
+        let mut builder = EpubBuilder::new(ZipLibrary::new().map_err(|err| err.to_string())?)
+            .map_err(|err| err.to_string())?;
+        builder
+            .metadata("title", book_name)
+            .map_err(|err| err.to_string())?
+            .metadata("lang", "zh-CN")
+            .map_err(|err| err.to_string())?
+            .metadata("generator", "ytool")
+            .map_err(|err| err.to_string())?;
+        builder.inline_toc();
+
+        let mut resources: HashMap<String, String> = HashMap::new();
+        for doc in docs {
+            let chapter = Self::render_chapter(doc, 0);
+            let chapter = Self::fix_links(&chapter, namespace, docs, &|anchor| {
+                format!("{}.xhtml", anchor)
+            });
+
+            for (url, bytes) in Self::fetch_images(api, &chapter).await {
+                if resources.contains_key(&url) {
+                    continue;
+                }
+                let path = format!(
+                    "images/{}.{}",
+                    resources.len() + 1,
+                    Self::image_mime(&url)
+                        .trim_start_matches("image/")
+                        .replace("svg+xml", "svg")
+                );
+                builder
+                    .add_resource(&path, bytes.as_slice(), Self::image_mime(&url))
+                    .map_err(|err| err.to_string())?;
+                resources.insert(url, path);
+            }
+
+            let xhtml = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<html xmlns=\"http://www.w3.org/1999/xhtml\">\n<head><title>{}</title></head>\n<body>\n{}</body>\n</html>\n",
+                Self::escape_html(&doc.title),
+                Self::markdown_to_html(&Self::replace_images(&chapter, &resources))
+            );
+            builder
+                .add_content(
+                    EpubContent::new(format!("{}.xhtml", doc.anchor), xhtml.as_bytes())
+                        .title(doc.title.to_string())
+                        .level(doc.depth as i32),
+                )
+                .map_err(|err| err.to_string())?;
+        }
+
+        let mut content: Vec<u8> = vec![];
+        builder
+            .generate(&mut content)
+            .map_err(|err| err.to_string())?;
+        Ok(content)
+    }
+
+    /// 文档中的图片地址
+    fn find_images(markdown: &str) -> Vec<String> {
+        let image = Regex::new(r"!\[[^\]]*\]\(<?([^)\s>]+)>?[^)]*\)").unwrap();
+        let mut urls: Vec<String> = vec![];
+        for caps in image.captures_iter(markdown) {
+            let url = caps[1].to_string();
+            if !url.starts_with("data:") && !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// 下载文档中的图片，失败的保留原地址
+    async fn fetch_images(api: &impl ImageSource, markdown: &str) -> Vec<(String, Vec<u8>)> {
+        let mut images = vec![];
+        for url in Self::find_images(markdown) {
+            // 语雀图片地址后面常带有#averageHue等参数
+            let download_url = url.split('#').next().unwrap_or(&url);
+            match api.get_image(download_url).await {
+                Ok(bytes) => images.push((url, bytes)),
+                Err(_) => Log::warn(&t!("bundle-image-failed", url = url)),
+            }
+        }
+        images
+    }
+
+    /// 替换图片地址
+    fn replace_images(markdown: &str, replacements: &HashMap<String, String>) -> String {
+        let image = Regex::new(r"!\[[^\]]*\]\(<?([^)\s>]+)>?[^)]*\)").unwrap();
+        image
+            .replace_all(markdown, |caps: &Captures| {
+                match replacements.get(&caps[1]) {
+                    Some(new_url) => caps[0].replacen(&caps[1], new_url, 1),
+                    None => caps[0].to_string(),
+                }
+            })
+            .to_string()
+    }
+
+    /// 按扩展名判断图片类型
+    fn image_mime(url: &str) -> &'static str {
+        let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+        match path.rsplit('.').next().unwrap_or("") {
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            "webp" => "image/webp",
+            _ => "image/png",
+        }
+    }
+
+    pub fn markdown_to_html(markdown: &str) -> String {
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_FOOTNOTES;
+        let mut content = String::new();
+        html::push_html(&mut content, Parser::new_ext(markdown, options));
+        content
+    }
+
+    pub fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::fixtures::tree_node;
+
+    struct MockYuque;
+
+    impl ImageSource for MockYuque {
+        async fn get_image(&self, url: &str) -> Result<Vec<u8>, bool> {
+            if url.ends_with("missing.png") {
+                Err(false)
+            } else {
+                Ok(vec![137, 80, 78, 71])
+            }
+        }
+    }
+
+    fn node(
+        title: &str,
+        slug: &str,
+        node_type: &str,
+        visible: u8,
+        children: Vec<TreeNone>,
+    ) -> TreeNone {
+        TreeNone {
+            uuid: format!("uuid-{}", slug),
+            node_type: node_type.to_string(),
+            children,
+            visible,
+            p_slug: "book".to_string(),
+            user: "vannvan".to_string(),
+            url: slug.to_string(),
+            ..tree_node(&format!("book/{}", title))
+        }
+    }
+
+    fn docs() -> Vec<BundleDoc> {
+        let tree = vec![
+            node(
+                "分组",
+                "g",
+                "TITLE",
+                1,
+                vec![
+                    node("第一篇", "a", "DOC", 1, vec![]),
+                    node(
+                        "草稿",
+                        "d",
+                        "DOC",
+                        0,
+                        vec![node("草稿下的文档", "e", "DOC", 1, vec![])],
+                    ),
+                ],
+            ),
+            node("空分组", "x", "TITLE", 1, vec![]),
+            node("第二篇", "b", "DOC", 1, vec![]),
+        ];
+        let mut docs = Bundle::collect_docs(&tree, 1, false);
+        docs[1].body =
+            "# 小节\n\n见[第二篇](https://www.yuque.com/vannvan/book/b#abc)\n\n```\n# 注释\n```"
+                .to_string();
+        docs[3].body = "![图](https://cdn.nlark.com/a.png#averageHue=%23f00)".to_string();
+        docs
+    }
+
+    #[test]
+    fn test_collect_docs() {
+        let docs = docs();
+        let titles: Vec<(&str, usize)> = docs
+            .iter()
+            .map(|doc| (doc.title.as_str(), doc.depth))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("分组", 1),
+                ("第一篇", 2),
+                ("草稿下的文档", 2),
+                ("第二篇", 1)
+            ]
+        );
+        assert_eq!(docs[0].anchor, "title-uuid-g");
+        assert_eq!(docs[1].anchor, "doc-a");
+    }
+
+    #[test]
+    fn test_shift_headings() {
+        assert_eq!(
+            Bundle::shift_headings("# 标题\n#不是标题\n```\n# 代码\n```\n##### 五级", 2),
+            "### 标题\n#不是标题\n```\n# 代码\n```\n###### 五级"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let content = Bundle::render_markdown("测试知识库", "vannvan/book", &docs());
+
+        assert!(content.starts_with(
+            "# 测试知识库\n\n## 目录\n\n- [分组](#title-uuid-g)\n  - [第一篇](#doc-a)\n"
+        ));
+        // 第二级文档的标题为三级，文档内的标题依次下移
+        assert!(content.contains("<a id=\"doc-a\"></a>\n\n### 第一篇\n\n#### 小节"));
+        assert!(content.contains("```\n# 注释\n```"));
+        // 知识库内的链接改为锚点
+        assert!(content.contains("见[第二篇](#doc-b)"));
+    }
+
+    #[tokio::test]
+    async fn test_render_html() {
+        let mut docs = docs();
+        docs[0].body = "![缺失](https://cdn.nlark.com/missing.png)".to_string();
+        let content = Bundle::render_html(&MockYuque, "测试知识库", "vannvan/book", &docs).await;
+
+        assert!(content.contains("<title>测试知识库</title>"));
+        assert!(content.contains("src=\"data:image/png;base64,iVBORw==\""));
+        assert!(content.contains("src=\"https://cdn.nlark.com/missing.png\""));
+        assert!(content.contains("href=\"#doc-b\""));
+    }
+
+    #[tokio::test]
+    async fn test_render_epub() {
+        let content = Bundle::render_epub(&MockYuque, "测试知识库", "vannvan/book", &docs())
+            .await
+            .unwrap();
+        // zip文件头
+        assert_eq!(&content[..2], b"PK");
+    }
+}
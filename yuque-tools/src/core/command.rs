@@ -7,59 +7,448 @@
  * Copyright (c) https://github.com/vannvan
  */
 
-use crate::libs::{
-    constants::{schema::UserCliConfig, GLOBAL_CONFIG},
-    file::File,
-    log::Log,
+use std::env;
+
+use crate::{
+    libs::{
+        constants::{
+            schema::{
+                DocFilter, DocLayout, EncryptConfig, EncryptOptions, ExportTarget, PathTemplates,
+                Profile, PullOptions, UserCliConfig,
+            },
+            ACTIVE_PROFILE, GLOBAL_CONFIG,
+        },
+        file::File,
+        i18n::{self, Locale},
+        log::Log,
+        profile::{self, LEGACY_IDENTITY_FILE},
+    },
+    t,
 };
 
-use super::scheduler::Scheduler;
-use clap::{Parser, Subcommand};
+use super::{
+    browse::Browse,
+    bundle::{Bundle, BundleFormat},
+    completion::{Completion, CompletionShell, COMPLETE_ENV},
+    decrypt::Decrypt,
+    mirror::Mirror,
+    push::Push,
+    scheduler::Scheduler,
+    serve::Serve,
+    stats::{Stats, StatsFormat},
+    upgrade::Upgrade,
+    watch::Watch,
+    webhook::Webhook,
+};
+use clap::{ArgAction, Args, Command, CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::{ArgValueCandidates, CompleteEnv};
+use tracing::debug;
 
 #[derive(Debug, Parser)] // requires `derive` feature
 #[command(name = "ytool")]
 #[command(about = "语雀知识库内容批量导出工具", long_about = None)]
 pub struct Cli {
+    /// 使用指定的配置档案(账号/空间)，见 profile 命令
+    #[arg(long, global = true, add = ArgValueCandidates::new(Completion::profile_candidates))]
+    profile: Option<String>,
+    /// 输出调试信息(请求地址、失败响应等)，-vv 输出更详细的信息
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    /// 同时以JSON格式把日志写入该文件，密码和cookie会被隐藏
+    #[arg(long, global = true)]
+    log_file: Option<String>,
+    /// 把导出过程中的目录树等中间数据输出到该目录，用于排查问题
+    #[arg(long, global = true)]
+    dump_dir: Option<String>,
+    /// 提示信息的语言，默认根据 LANG 环境变量选择
+    #[arg(long, global = true, value_enum)]
+    lang: Option<Locale>,
     #[command(subcommand)]
     command: Commands,
 }
+
+impl Cli {
+    /// 命令定义，说明和参数帮助按当前语言替换
+    pub fn localized_command() -> Command {
+        i18n::localize(Cli::command())
+    }
+}
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// 获取知识库(交互流程请参考文档说明)
     #[command(arg_required_else_help = false)]
-    Pull,
+    Pull {
+        /// 只输出导出计划(下载/跳过/冲突及预计耗时)，不修改任何本地文件
+        #[arg(long)]
+        dry_run: bool,
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// 导出目标，覆盖配置文件中的target
+        #[arg(long, value_enum)]
+        target: Option<ExportTarget>,
+        /// 导出后把变更提交到输出目录的git仓库，不是仓库时自动初始化
+        #[arg(long)]
+        git: bool,
+        /// 每篇文档单独提交，作者为文档的最后编辑者
+        #[arg(long, requires = "git")]
+        git_author: bool,
+        /// 文档路径模板，如 "{book_slug}/{path}/{title}.{ext}"，覆盖配置文件中的path_template.doc
+        #[arg(long)]
+        path_template: Option<String>,
+        /// 导出到归档文件(.tar.zst/.zip)而不是输出目录，附带manifest.json
+        #[arg(long, conflicts_with = "git")]
+        archive: Option<String>,
+        /// 每个知识库一个归档文件，文件名为 归档名-知识库.扩展名
+        #[arg(long, requires = "archive")]
+        split_by_book: bool,
+        /// 加密导出，密钥使用配置档案中的设置，没有公钥时使用口令
+        #[arg(long, conflicts_with = "git")]
+        encrypt: bool,
+        /// 接收方公钥(age1...)，可多次指定，指定后自动加密
+        #[arg(long = "recipient", value_name = "KEY", conflicts_with = "git")]
+        recipients: Vec<String>,
+        /// 使用口令加密，口令从 YTOOL_PASSPHRASE 读取，未设置时交互输入
+        #[arg(long, conflicts_with = "git")]
+        passphrase: bool,
+    },
     /// 下载任意知识库
     #[command(arg_required_else_help = false)]
     Down,
     /// 团队资源下载(group-resource-download)
     #[command(arg_required_else_help = false)]
-    Grd,
+    Grd {
+        /// 只输出下载计划，不修改任何本地文件
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// 初始化工具配置
     #[command(arg_required_else_help = false)]
     Init,
     /// 清除本地缓存
     #[command(arg_required_else_help = false)]
     Clear,
-    // #[command(arg_required_else_help = false)]
-    // Upgrade,
+    /// 管理配置档案(多账号/多空间)
+    #[command(arg_required_else_help = true)]
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// 查看本地缓存的知识库及文档目录(无需登录)
+    #[command(arg_required_else_help = false)]
+    Ls {
+        /// 知识库名称或slug，可带目录，如 book/目录
+        #[arg(add = ArgValueCandidates::new(Completion::book_candidates))]
+        target: Option<String>,
+        /// 以JSON格式输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 按标题模糊搜索本地缓存的文档(无需登录)
+    #[command(arg_required_else_help = true)]
+    Search {
+        /// 搜索内容
+        query: String,
+        /// 最多显示的数量
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// 以JSON格式输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 为导出的文档建立全文索引，之后导出时会自动增量更新
+    #[command(arg_required_else_help = false)]
+    Index {
+        /// 丢弃旧索引重新建立
+        #[arg(long)]
+        rebuild: bool,
+    },
+    /// 在导出的文档中全文搜索
+    #[command(arg_required_else_help = true)]
+    Grep {
+        /// 搜索内容
+        query: String,
+        /// 最多显示的数量
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// 以JSON格式输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 统计知识库的文档数量、层级、字数、图片、过期文档和失效链接(无需登录)
+    #[command(arg_required_else_help = false)]
+    Stats {
+        /// 知识库名称或slug，不填时统计所有缓存的知识库
+        #[arg(add = ArgValueCandidates::new(Completion::book_candidates))]
+        books: Vec<String>,
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t = StatsFormat::Table)]
+        format: StatsFormat,
+        /// 超过这么多天没有更新的文档视为过期
+        #[arg(long, default_value_t = 365)]
+        stale_days: i64,
+        /// 列出字数最多的文档数量
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+    },
+    /// 把本地Markdown目录推送到知识库，已推送过的文档原地更新
+    #[command(arg_required_else_help = true)]
+    Push {
+        /// 本地文档目录
+        dir: String,
+        /// 目标知识库slug，或 用户/slug
+        #[arg(long, add = ArgValueCandidates::new(Completion::namespace_candidates))]
+        book: String,
+    },
+    /// 把整个知识库复制到另一个空间的知识库
+    #[command(arg_required_else_help = true)]
+    Mirror {
+        /// 源知识库，如 user/book
+        #[arg(long, add = ArgValueCandidates::new(Completion::namespace_candidates))]
+        from: String,
+        /// 目标知识库，如 group/book
+        #[arg(long, add = ArgValueCandidates::new(Completion::namespace_candidates))]
+        to: String,
+        /// 只列出计划执行的操作，不做任何修改
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 把知识库合并导出为单个文件，含目录，知识库内的链接改为文内跳转
+    #[command(arg_required_else_help = true)]
+    Bundle {
+        /// 知识库名称或slug，可指定多个
+        #[arg(required = true, add = ArgValueCandidates::new(Completion::book_candidates))]
+        books: Vec<String>,
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t = BundleFormat::Md)]
+        format: BundleFormat,
+    },
+    /// 解密加密导出的文件、归档或目录
+    #[command(arg_required_else_help = true)]
+    Decrypt {
+        /// 加密的文件(.age)或目录，目录时解密其中所有 .age 文件
+        path: String,
+        /// 私钥文件，默认使用配置档案中的identity_file，口令加密的文件不需要
+        #[arg(long)]
+        identity: Option<String>,
+        /// 输出目录，默认写到加密文件旁边
+        #[arg(long)]
+        output: Option<String>,
+        /// 解密成功后删除加密文件
+        #[arg(long)]
+        remove: bool,
+    },
+    /// 常驻运行，定时检查知识库的变化并同步到本地
+    #[command(arg_required_else_help = false)]
+    Watch {
+        /// 检查间隔，如 30s、10m、1h
+        #[arg(long, default_value = "10m")]
+        interval: String,
+    },
+    /// 接收语雀的webhook，文档发布/更新后重新导出该文档
+    #[command(arg_required_else_help = false)]
+    Webhook {
+        /// 监听地址
+        #[arg(long, default_value = "127.0.0.1:9000")]
+        listen: String,
+        /// 校验用的token，设置后webhook地址需带上 ?token=xxx
+        #[arg(long)]
+        token: Option<String>,
+        /// 同一文档在这段时间内没有新的编辑才导出，如 5s、1m
+        #[arg(long, default_value = "5s")]
+        debounce: String,
+    },
+    /// 启动本地服务预览导出的文档
+    #[command(arg_required_else_help = false)]
+    Serve {
+        /// 文档目录，默认为文档输出目录
+        dir: Option<String>,
+        /// 端口
+        #[arg(long, default_value_t = 8071)]
+        port: u16,
+    },
+    /// 输出shell补全脚本，如 source <(ytool completions bash)
+    #[command(arg_required_else_help = true)]
+    Completions {
+        /// shell类型
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+    /// 输出man手册
+    #[command(arg_required_else_help = false)]
+    Man {
+        /// 输出目录，为每个子命令生成一个手册，不填时输出主手册到终端
+        #[arg(long)]
+        dir: Option<String>,
+    },
+    /// 更新ytool到最新版本
+    #[command(arg_required_else_help = false)]
+    Upgrade {
+        /// 只检查是否有新版本，不更新
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+/// 导出过滤条件，填写了的项覆盖配置文件中的filter
+#[derive(Debug, Args)]
+pub struct FilterArgs {
+    /// 只导出匹配的路径，glob格式，如 "book/目录/**"，可多次指定
+    #[arg(long)]
+    include: Vec<String>,
+    /// 排除匹配的路径，glob格式，可多次指定
+    #[arg(long)]
+    exclude: Vec<String>,
+    /// 只导出该日期之后更新过的文档，如 2023-09-01
+    #[arg(long, default_value = "")]
+    updated_since: String,
+    /// 只导出该作者(语雀login)的文档
+    #[arg(long, default_value = "")]
+    author: String,
+    /// 标题正则
+    #[arg(long, default_value = "")]
+    title: String,
+    /// 最大目录深度，知识库下第一级为1
+    #[arg(long, default_value_t = 0)]
+    max_depth: usize,
+}
+
+impl From<FilterArgs> for DocFilter {
+    fn from(args: FilterArgs) -> Self {
+        DocFilter {
+            include: args.include,
+            exclude: args.exclude,
+            updated_since: args.updated_since,
+            author: args.author,
+            title: args.title,
+            max_depth: args.max_depth,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProfileAction {
+    /// 新增配置档案，同名的会被覆盖
+    Add {
+        /// 档案名称
+        name: String,
+        /// 空间域名(如：https://xxx.yuque.com)，个人知识库不填
+        #[arg(long, default_value = "")]
+        host: String,
+        /// 语雀账号
+        #[arg(long, default_value = "")]
+        username: String,
+        /// 语雀密码，不填时在登录环节询问
+        #[arg(long, default_value = "")]
+        password: String,
+        /// 开放接口的Token，推送/复制知识库时使用
+        #[arg(long, default_value = "")]
+        token: String,
+        /// 文档输出目录，默认 ./docs/{name}
+        #[arg(long, default_value = "")]
+        output: String,
+        /// 缓存目录，默认 .meta/profiles/{name}
+        #[arg(long, default_value = "")]
+        meta_dir: String,
+        /// 加密导出的接收方公钥(age1...)，可多次指定
+        #[arg(long = "recipient", value_name = "KEY")]
+        recipients: Vec<String>,
+        /// 解密用的私钥文件
+        #[arg(long, default_value = "")]
+        identity_file: String,
+        /// 每次导出都加密
+        #[arg(long)]
+        encrypt: bool,
+    },
+    /// 为配置档案生成加密导出用的密钥，公钥写入档案
+    Keygen {
+        /// 档案名称
+        #[arg(add = ArgValueCandidates::new(Completion::profile_candidates))]
+        name: String,
+        /// 私钥文件，默认为配置档案文件旁的 yuque.keys/{name}.txt
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// 查看所有配置档案
+    List,
+    /// 删除配置档案
+    Remove {
+        /// 档案名称
+        #[arg(add = ArgValueCandidates::new(Completion::profile_candidates))]
+        name: String,
+    },
 }
 
 pub struct YCommand;
 
 impl YCommand {
     pub async fn new() -> Result<(), &'static str> {
-        let args = Cli::parse();
+        // 帮助信息在解析参数时输出，所以要先确定语言
+        i18n::init(i18n::lang_from_args(env::args_os()));
+
+        // 补全请求由shell通过环境变量发起，输出候选项后直接退出
+        CompleteEnv::with_factory(Cli::localized_command)
+            .var(COMPLETE_ENV)
+            .complete();
+
+        let args = Cli::from_arg_matches(&Cli::localized_command().get_matches())
+            .unwrap_or_else(|err| err.exit());
+        Log::init(
+            args.verbose,
+            args.log_file.as_deref(),
+            args.dump_dir.as_deref(),
+        )?;
+
+        // 必须在首次访问GLOBAL_CONFIG之前设置，缓存和输出目录才会切换到档案目录
+        if let Some(profile_name) = &args.profile {
+            let _ = ACTIVE_PROFILE.set(profile_name.to_string());
+            if GLOBAL_CONFIG.profile.is_none() {
+                return Err(t!("profile-not-found"));
+            }
+            Log::info(&t!("profile-using", name = profile_name));
+        }
+
         match args.command {
-            Commands::Pull => {
-                let _ = Scheduler::start().await;
-                Ok(())
+            Commands::Pull {
+                dry_run,
+                filter,
+                target,
+                git,
+                git_author,
+                path_template,
+                archive,
+                split_by_book,
+                encrypt,
+                recipients,
+                passphrase,
+            } => {
+                let encrypt = if encrypt || passphrase || !recipients.is_empty() {
+                    Some(EncryptOptions {
+                        recipients,
+                        passphrase,
+                    })
+                } else {
+                    None
+                };
+                let options = PullOptions {
+                    dry_run,
+                    filter: filter.into(),
+                    target,
+                    git,
+                    git_author,
+                    changed_docs: None,
+                    path_template,
+                    archive,
+                    archive_split: split_by_book,
+                    encrypt,
+                };
+                Scheduler::start(options).await
             }
             Commands::Down => {
                 let _ = Scheduler::download_any_knowledge_stock().await;
                 Ok(())
             }
-            Commands::Grd => {
-                let _ = Scheduler::start_grd().await;
+            Commands::Grd { dry_run } => {
+                let _ = Scheduler::start_grd(dry_run).await;
                 Ok(())
             }
             Commands::Clear => {
@@ -70,6 +459,146 @@ impl YCommand {
                 let _ = Self::generate_cli_config();
                 Ok(())
             }
+            Commands::Profile { action } => {
+                let _ = Self::manage_profile(action);
+                Ok(())
+            }
+            Commands::Ls { target, json } => Browse::list(target, json),
+            Commands::Search { query, limit, json } => Browse::search(&query, limit, json),
+            Commands::Index { rebuild } => Browse::index(rebuild),
+            Commands::Grep { query, limit, json } => Browse::grep(&query, limit, json),
+            Commands::Stats {
+                books,
+                format,
+                stale_days,
+                top,
+            } => Stats::start(books, format, stale_days, top),
+            Commands::Push { dir, book } => Push::start(&dir, &book).await,
+            Commands::Mirror { from, to, dry_run } => Mirror::start(&from, &to, dry_run).await,
+            Commands::Bundle { books, format } => Bundle::start(books, format).await,
+            Commands::Decrypt {
+                path,
+                identity,
+                output,
+                remove,
+            } => Decrypt::start(&path, identity, output, remove),
+            Commands::Completions { shell } => Completion::completions(shell),
+            Commands::Man { dir } => Completion::man(dir),
+            Commands::Upgrade { check } => Upgrade::start(check).await,
+            Commands::Serve { dir, port } => Serve::start(dir, port).await,
+            Commands::Watch { interval } => Watch::start(&interval).await,
+            Commands::Webhook {
+                listen,
+                token,
+                debounce,
+            } => Webhook::start(&listen, token, &debounce).await,
+        }
+    }
+
+    /// 配置档案管理
+    fn manage_profile(action: ProfileAction) -> Result<bool, bool> {
+        let profiles_file = &GLOBAL_CONFIG.profiles_file;
+
+        match action {
+            ProfileAction::Add {
+                name,
+                host,
+                username,
+                password,
+                token,
+                output,
+                meta_dir,
+                recipients,
+                identity_file,
+                encrypt,
+            } => {
+                let new_profile = Profile {
+                    name: name.to_string(),
+                    username,
+                    password,
+                    host,
+                    token,
+                    meta_dir,
+                    output,
+                    encrypt: EncryptConfig {
+                        always: encrypt,
+                        recipients,
+                        identity_file,
+                        passphrase_env: "".to_string(),
+                    },
+                };
+                match profile::add_profile(profiles_file, new_profile) {
+                    Ok(true) => Log::success(&t!("profile-updated", name = name)),
+                    Ok(false) => Log::success(&t!("profile-added", name = name)),
+                    Err(err) => {
+                        Log::error(t!("profile-write-failed"));
+                        debug!("{}", err);
+                        return Err(false);
+                    }
+                }
+                Ok(true)
+            }
+            ProfileAction::List => {
+                let profiles = profile::read_profiles(profiles_file);
+                if profiles.is_empty() {
+                    Log::warn(t!("profile-empty"));
+                    return Ok(true);
+                }
+                for item in &profiles {
+                    let space = if item.host.is_empty() {
+                        t!("profile-personal")
+                    } else {
+                        &item.host
+                    };
+                    let account = if item.username.is_empty() {
+                        t!("profile-no-account")
+                    } else {
+                        &item.username
+                    };
+                    println!(
+                        "  {}",
+                        t!(
+                            "profile-list-item",
+                            name = item.name,
+                            space = space,
+                            account = account,
+                            dir = profile::profile_meta_dir(&GLOBAL_CONFIG, item)
+                        )
+                    );
+                }
+                Ok(true)
+            }
+            ProfileAction::Keygen { name, output } => {
+                match profile::generate_profile_key(&GLOBAL_CONFIG, &name, output) {
+                    Ok((identity_file, recipient)) => {
+                        Log::success(&t!(
+                            "profile-keygen-done",
+                            name = name,
+                            file = identity_file
+                        ));
+                        println!("{}", recipient);
+                        Ok(true)
+                    }
+                    Err(err) => {
+                        Log::error(&err);
+                        Err(false)
+                    }
+                }
+            }
+            ProfileAction::Remove { name } => match profile::remove_profile(profiles_file, &name) {
+                Ok(true) => {
+                    Log::success(&t!("profile-removed", name = name));
+                    Ok(true)
+                }
+                Ok(false) => {
+                    Log::warn(&t!("profile-missing", name = name));
+                    Err(false)
+                }
+                Err(_) => {
+                    Log::error(t!("profile-write-failed"));
+                    Err(false)
+                }
+            },
         }
     }
 
@@ -83,6 +612,13 @@ impl YCommand {
             line_break: true,
             host: "".to_string(),
             output: "".to_string(),
+            token: "".to_string(),
+            layout: DocLayout::Sibling,
+            include_hidden: false,
+            filter: DocFilter::default(),
+            target: ExportTarget::Markdown,
+            attachment_dir: "".to_string(),
+            path_template: PathTemplates::default(),
         };
 
         // 格式化json文件
@@ -92,11 +628,7 @@ impl YCommand {
 
         match f.write(&GLOBAL_CONFIG.user_cli_config_file, json_string) {
             Ok(_) => {
-                let success_info = format!(
-                    "配置文件已初始化，见👉{}",
-                    GLOBAL_CONFIG.user_cli_config_file
-                );
-                Log::info(&success_info);
+                Log::info(&t!("init-done", file = GLOBAL_CONFIG.user_cli_config_file));
                 return Ok(true);
             }
             Err(err) => {
@@ -109,22 +641,24 @@ impl YCommand {
     fn clear_local_cache() -> Result<bool, bool> {
         let f = File::new();
 
+        // 旧版本把配置档案的私钥放在缓存目录中，清除时保留
         match f.exists(&GLOBAL_CONFIG.meta_dir) {
-            true => match f.rmdir(&GLOBAL_CONFIG.meta_dir) {
+            true => match f.rmdir_keep(&GLOBAL_CONFIG.meta_dir, LEGACY_IDENTITY_FILE) {
                 Err(err) => {
-                    Log::error("缓存清除失败");
-                    if cfg!(debug_assertions) {
-                        println!("{}", err);
-                    }
+                    Log::error(t!("clear-failed"));
+                    debug!("{}", err);
                     Err(false)
                 }
-                Ok(_) => {
-                    Log::success("缓存已清除~");
+                Ok(kept) => {
+                    if kept > 0 {
+                        Log::warn(&t!("clear-kept-keys", count = kept));
+                    }
+                    Log::success(t!("clear-done"));
                     Ok(true)
                 }
             },
             false => {
-                Log::warn("暂无缓存");
+                Log::warn(t!("clear-empty"));
                 Err(false)
             }
         }
@@ -0,0 +1,181 @@
+/*
+ * Description: 命令补全和man手册，均由clap的命令定义生成，知识库名称等从本地缓存动态补全
+ * Created: 2026-10-19 01:35:50
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{env, fs, io};
+
+use clap::{Command, ValueEnum};
+use clap_complete::{
+    env::{Bash, EnvCompleter, Fish, Powershell, Zsh},
+    CompletionCandidate,
+};
+use tracing::debug;
+
+use super::command::Cli;
+use crate::{
+    libs::{
+        constants::{
+            schema::{
+                cache_book::{BookInfo, BookItem},
+                Profile,
+            },
+            GLOBAL_CONFIG,
+        },
+        file::File,
+        log::Log,
+    },
+    t,
+};
+
+/// 补全时调用ytool所用的环境变量
+pub const COMPLETE_ENV: &str = "COMPLETE";
+
+/// 支持补全的shell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl CompletionShell {
+    fn completer(&self) -> &'static dyn EnvCompleter {
+        match self {
+            CompletionShell::Bash => &Bash,
+            CompletionShell::Zsh => &Zsh,
+            CompletionShell::Fish => &Fish,
+            CompletionShell::Powershell => &Powershell,
+        }
+    }
+}
+
+pub struct Completion;
+
+impl Completion {
+    /// 完整的命令定义
+    pub fn command() -> Command {
+        Cli::localized_command()
+    }
+
+    /// 输出补全脚本，脚本在补全时回调ytool，所以知识库名称等总是最新的
+    /// # Arguments
+    /// * shell - shell类型
+    pub fn completions(shell: CompletionShell) -> Result<(), &'static str> {
+        // 以完整路径回调，不要求ytool在PATH中
+        let completer = env::current_exe()
+            .map(|exe| exe.to_string_lossy().to_string())
+            .unwrap_or("ytool".to_string());
+        let cmd = Self::command();
+        shell
+            .completer()
+            .write_registration(
+                COMPLETE_ENV,
+                cmd.get_name(),
+                cmd.get_name(),
+                &completer,
+                &mut io::stdout(),
+            )
+            .map_err(|_| t!("completion-script-failed"))
+    }
+
+    /// 输出man手册
+    /// # Arguments
+    /// * dir - 输出目录，为每个子命令生成一个手册；为空时把主手册输出到终端
+    pub fn man(dir: Option<String>) -> Result<(), &'static str> {
+        let cmd = Self::command();
+        match dir {
+            None => clap_mangen::Man::new(cmd)
+                .render(&mut io::stdout())
+                .map_err(|_| t!("completion-man-failed")),
+            Some(dir) => {
+                if File::new().mkdir(&dir).is_err() {
+                    return Err(t!("completion-man-dir-failed"));
+                }
+                clap_mangen::generate_to(cmd, &dir).map_err(|err| {
+                    debug!("{}", err);
+                    t!("completion-man-generate-failed")
+                })?;
+                Log::success(&t!("completion-man-done", dir = dir));
+                Ok(())
+            }
+        }
+    }
+
+    /// 缓存中的知识库名称，说明为slug
+    pub fn book_candidates() -> Vec<CompletionCandidate> {
+        Self::cached_books()
+            .into_iter()
+            .map(|book| CompletionCandidate::new(book.name).help(Some(book.slug.into())))
+            .collect()
+    }
+
+    /// 缓存中的知识库，用户/slug 格式，说明为名称
+    pub fn namespace_candidates() -> Vec<CompletionCandidate> {
+        Self::cached_books()
+            .into_iter()
+            .map(|book| {
+                CompletionCandidate::new(format!("{}/{}", book.user_login, book.slug))
+                    .help(Some(book.name.into()))
+            })
+            .collect()
+    }
+
+    /// 缓存的知识库，补全时不能输出任何其他内容，读取失败直接返回空
+    fn cached_books() -> Vec<BookItem> {
+        fs::read_to_string(&GLOBAL_CONFIG.books_info_file)
+            .ok()
+            .and_then(|data| serde_json::from_str::<BookInfo>(&data).ok())
+            .map(|info| info.books_info)
+            .unwrap_or_default()
+    }
+
+    /// 配置档案名称，说明为空间域名
+    pub fn profile_candidates() -> Vec<CompletionCandidate> {
+        let profiles = fs::read_to_string(&GLOBAL_CONFIG.profiles_file)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Vec<Profile>>(&data).ok())
+            .unwrap_or_default();
+        profiles
+            .into_iter()
+            .map(|profile| {
+                let space = if profile.host.is_empty() {
+                    "个人".to_string()
+                } else {
+                    profile.host
+                };
+                CompletionCandidate::new(profile.name).help(Some(space.into()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_definition() {
+        Completion::command().debug_assert();
+    }
+
+    #[test]
+    fn test_man() {
+        let mut buf = vec![];
+        clap_mangen::Man::new(Completion::command())
+            .render(&mut buf)
+            .unwrap();
+        let man = String::from_utf8(buf).unwrap();
+        assert!(man.contains("ytool"));
+        assert!(man.contains("pull"));
+
+        let dir = tempfile::tempdir().unwrap();
+        clap_mangen::generate_to(Completion::command(), dir.path()).unwrap();
+        assert!(dir.path().join("ytool-pull.1").exists());
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * Description: 解密加密导出的文件、归档或目录
+ * Created: 2026-10-19 02:43:19
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    fs,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use tracing::debug;
+
+use crate::{
+    libs::{
+        cipher::{self, Keyring, ENCRYPTED_EXT},
+        constants::GLOBAL_CONFIG,
+        log::Log,
+    },
+    t,
+};
+
+pub struct Decrypt;
+
+impl Decrypt {
+    /// 解密文件或目录
+    /// # Arguments
+    /// * path - 加密文件或目录，目录时解密其中所有 .age 文件
+    /// * identity - 私钥文件，为空时使用配置档案中的identity_file
+    /// * output - 输出目录，为空时写到加密文件旁边
+    /// * remove - 解密成功后删除加密文件
+    pub fn start(
+        path: &str,
+        identity: Option<String>,
+        output: Option<String>,
+        remove: bool,
+    ) -> Result<(), &'static str> {
+        let config = GLOBAL_CONFIG
+            .profile
+            .as_ref()
+            .map(|profile| &profile.encrypt);
+        let identity_file = identity.or_else(|| {
+            config
+                .map(|config| config.identity_file.to_string())
+                .filter(|file| !file.is_empty())
+        });
+        let identities = match &identity_file {
+            Some(file) => cipher::read_identities(file)?,
+            None => vec![],
+        };
+        let passphrase_env = config.map(|config| config.passphrase_env.to_string());
+        let mut keyring = Keyring::new(identities, passphrase_env);
+
+        let (success, failed) = Self::run(path, &mut keyring, output.as_deref(), remove)?;
+        if success == 0 {
+            return Err(t!("decrypt-failed"));
+        }
+        Log::success(&t!("decrypt-done", count = success, failed = failed));
+        Ok(())
+    }
+
+    /// 逐个解密，单个文件失败时继续，返回 (成功数量, 失败数量)
+    fn run(
+        path: &str,
+        keyring: &mut Keyring,
+        output: Option<&str>,
+        remove: bool,
+    ) -> Result<(usize, usize), &'static str> {
+        let files = Self::collect_encrypted_files(Path::new(path))?;
+        if files.is_empty() {
+            return Err(t!("decrypt-no-files"));
+        }
+
+        let mut failed = 0;
+        for (source, relative_path) in &files {
+            let target = Self::target_path(source, relative_path, output);
+            match Self::decrypt_file(keyring, source, &target) {
+                Ok(size) => {
+                    debug!(file = %target.display(), size, "解密完成");
+                    if remove {
+                        let _ = fs::remove_file(source);
+                    }
+                }
+                Err(err) => {
+                    failed += 1;
+                    Log::warn(&t!(
+                        "decrypt-file-failed",
+                        file = source.display(),
+                        reason = err
+                    ));
+                }
+            }
+        }
+        Ok((files.len() - failed, failed))
+    }
+
+    /// 需要解密的文件和相对路径，单个文件时相对路径为文件名
+    fn collect_encrypted_files(path: &Path) -> Result<Vec<(PathBuf, String)>, &'static str> {
+        if path.is_file() {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            return Ok(vec![(path.to_path_buf(), name)]);
+        }
+        if !path.is_dir() {
+            return Err(t!("decrypt-path-missing"));
+        }
+
+        let mut files = vec![];
+        Self::walk(path, path, &mut files).map_err(|_| t!("decrypt-path-missing"))?;
+        files.sort();
+        Ok(files)
+    }
+
+    fn walk(
+        dir: &Path,
+        root: &Path,
+        files: &mut Vec<(PathBuf, String)>,
+    ) -> Result<(), std::io::Error> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            if name.starts_with(".") {
+                continue;
+            }
+            if path.is_dir() {
+                Self::walk(&path, root, files)?;
+            } else if name.ends_with(ENCRYPTED_EXT) {
+                let relative_path = path
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace("\\", "/");
+                files.push((path, relative_path));
+            }
+        }
+        Ok(())
+    }
+
+    /// 解密后的路径，去掉 .age，指定了输出目录时保持相对路径
+    fn target_path(source: &Path, relative_path: &str, output: Option<&str>) -> PathBuf {
+        match output {
+            Some(output) => {
+                let relative_path = relative_path
+                    .strip_suffix(ENCRYPTED_EXT)
+                    .unwrap_or(relative_path);
+                Path::new(output).join(relative_path)
+            }
+            None => {
+                let source = source.to_string_lossy();
+                PathBuf::from(source.strip_suffix(ENCRYPTED_EXT).unwrap_or(&source))
+            }
+        }
+    }
+
+    /// 解密单个文件，失败时不留下不完整的文件
+    fn decrypt_file(
+        keyring: &mut Keyring,
+        source: &Path,
+        target: &Path,
+    ) -> Result<u64, &'static str> {
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir).map_err(|_| t!("decrypt-write-failed"))?;
+        }
+        let input = fs::File::open(source).map_err(|_| t!("decrypt-path-missing"))?;
+        let output = fs::File::create(target).map_err(|_| t!("decrypt-write-failed"))?;
+
+        let result = keyring.decrypt(input, BufWriter::new(output));
+        if result.is_err() {
+            let _ = fs::remove_file(target);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::cipher::Cipher;
+
+    #[test]
+    fn test_decrypt_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let identity = age::x25519::Identity::generate();
+        let cipher = Cipher::from_recipients(&[identity.to_public().to_string()]).unwrap();
+
+        fs::create_dir_all(dir.path().join("book/目录")).unwrap();
+        cipher
+            .write_file(&format!("{}/book/目录/a.md", root), b"# a")
+            .unwrap();
+        fs::create_dir_all(dir.path().join("attachments")).unwrap();
+        cipher
+            .write_file(&format!("{}/attachments/b.png", root), b"png")
+            .unwrap();
+        fs::write(dir.path().join("broken.md.age"), b"not age").unwrap();
+        fs::write(dir.path().join("plain.md"), b"plain").unwrap();
+
+        // 输出到其他目录，保持相对路径
+        let output = dir.path().join("out").to_string_lossy().to_string();
+        let mut keyring = Keyring::new(vec![identity.clone()], None);
+        let (success, failed) = Decrypt::run(&root, &mut keyring, Some(&output), false).unwrap();
+        assert_eq!((success, failed), (2, 1));
+        assert_eq!(
+            fs::read_to_string(format!("{}/book/目录/a.md", output)).unwrap(),
+            "# a"
+        );
+        assert!(!Path::new(&format!("{}/broken.md", output)).exists());
+
+        // 写到加密文件旁边并删除加密文件
+        let file = format!("{}/book/目录/a.md.age", root);
+        let (success, _) = Decrypt::run(&file, &mut keyring, None, true).unwrap();
+        assert_eq!(success, 1);
+        assert!(!Path::new(&file).exists());
+        assert_eq!(
+            fs::read_to_string(format!("{}/book/目录/a.md", root)).unwrap(),
+            "# a"
+        );
+
+        assert!(Decrypt::run(&format!("{}/none", root), &mut keyring, None, false).is_err());
+    }
+}
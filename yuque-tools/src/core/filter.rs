@@ -0,0 +1,239 @@
+/*
+ * Description: 导出过滤，按路径、更新时间、作者、标题和目录深度筛选文档，匹配规则在 yuque-common 中
+ * Created: 2026-10-19 00:33:21
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use crate::{
+    libs::{constants::schema::TreeNone, i18n},
+    t,
+};
+
+pub use yuque_common::filter::{DocFilter, DocMatcher};
+
+/// 校验并编译过滤条件，规则与GUI端共用，见 yuque_common::filter
+pub fn compile(filter: &DocFilter) -> Result<DocMatcher, String> {
+    DocMatcher::compile(filter).map_err(|err| {
+        let (key, name, value) = err.message();
+        i18n::tf(key, &[(name, &value)])
+    })
+}
+
+/// 文档树的过滤
+pub trait TreeFilter {
+    /// 文档节点是否符合条件
+    fn match_node(&self, node: &TreeNone) -> bool;
+
+    /// 按条件裁剪文档树，返回裁剪后的树和被排除的文档数量
+    /// 不符合条件但有符合条件的下级文档的文档会转为分组，保留目录结构
+    fn prune(&self, nodes: Vec<TreeNone>) -> (Vec<TreeNone>, usize);
+}
+
+impl TreeFilter for DocMatcher {
+    fn match_node(&self, node: &TreeNone) -> bool {
+        node.node_type == "DOC"
+            && self.match_doc(&node.full_path, &node.title, &node.updated_at, &node.author)
+    }
+
+    fn prune(&self, nodes: Vec<TreeNone>) -> (Vec<TreeNone>, usize) {
+        let mut excluded = 0;
+        let mut result = vec![];
+
+        for mut node in nodes {
+            let (children, count) = self.prune(node.children);
+            excluded += count;
+            node.children = children;
+
+            let is_matched = self.match_node(&node);
+            if node.node_type == "DOC" && !is_matched {
+                excluded += 1;
+            }
+
+            if is_matched {
+                result.push(node);
+            } else if !node.children.is_empty() {
+                node.node_type = "TITLE".to_string();
+                result.push(node);
+            }
+        }
+
+        (result, excluded)
+    }
+}
+
+/// 过滤条件的说明，每项一行，未设置的条件不列出
+/// # Arguments
+/// * filter - 过滤条件
+/// * toc_range - 导出的目录范围
+pub fn describe(filter: &DocFilter, toc_range: &[String]) -> Vec<String> {
+    let mut lines = vec![];
+    for (key, values) in [
+        ("filter-summary-toc-range", toc_range),
+        ("filter-summary-include", filter.include.as_slice()),
+        ("filter-summary-exclude", filter.exclude.as_slice()),
+    ] {
+        if !values.is_empty() {
+            lines.push(t!(key, value = values.join(", ")));
+        }
+    }
+    for (key, value) in [
+        ("filter-summary-updated-since", &filter.updated_since),
+        ("filter-summary-author", &filter.author),
+        ("filter-summary-title", &filter.title),
+    ] {
+        if !value.is_empty() {
+            lines.push(t!(key, value = value));
+        }
+    }
+    if filter.max_depth > 0 {
+        lines.push(t!("filter-summary-max-depth", value = filter.max_depth));
+    }
+    lines
+}
+
+/// 合并配置文件和命令行的过滤条件，命令行中填写了的项覆盖配置文件
+pub fn merge_filter(config: DocFilter, cli: DocFilter) -> DocFilter {
+    let pick = |cli: String, config: String| if cli.is_empty() { config } else { cli };
+    DocFilter {
+        include: if cli.include.is_empty() {
+            config.include
+        } else {
+            cli.include
+        },
+        exclude: if cli.exclude.is_empty() {
+            config.exclude
+        } else {
+            cli.exclude
+        },
+        updated_since: pick(cli.updated_since, config.updated_since),
+        author: pick(cli.author, config.author),
+        title: pick(cli.title, config.title),
+        max_depth: if cli.max_depth == 0 {
+            config.max_depth
+        } else {
+            cli.max_depth
+        },
+    }
+}
+
+/// 文档是否在选择的知识库范围内，范围可以是知识库，也可以是知识库下的目录
+/// 按路径逐级比较，名称中的特殊字符不会影响匹配
+pub fn in_toc_range(full_path: &str, toc_range: &[String]) -> bool {
+    toc_range.iter().any(|range| {
+        let range = range.trim_end_matches('/');
+        full_path == range || full_path.starts_with(&format!("{}/", range))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::fixtures::tree_node;
+
+    fn doc(full_path: &str, updated_at: &str, author: &str, children: Vec<TreeNone>) -> TreeNone {
+        TreeNone {
+            uuid: full_path.to_string(),
+            children,
+            updated_at: updated_at.to_string(),
+            author: author.to_string(),
+            ..tree_node(full_path)
+        }
+    }
+
+    #[test]
+    fn test_compile() {
+        let err = compile(&DocFilter {
+            title: "(".to_string(),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(err, "标题正则有误：(");
+    }
+
+    #[test]
+    fn test_describe() {
+        let filter = DocFilter {
+            include: vec!["book/目录/**".to_string(), "book/a".to_string()],
+            exclude: vec!["book/目录/草稿".to_string()],
+            author: "mock-user".to_string(),
+            max_depth: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            describe(&filter, &["book".to_string()]),
+            vec![
+                "目录范围：book",
+                "包含路径：book/目录/**, book/a",
+                "排除路径：book/目录/草稿",
+                "作者：mock-user",
+                "最大目录深度：2",
+            ]
+        );
+        assert!(describe(&DocFilter::default(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_prune() {
+        let tree = vec![
+            doc(
+                "book/旧文档",
+                "2022-01-01T00:00:00.000Z",
+                "vannvan",
+                vec![doc(
+                    "book/旧文档/新文档",
+                    "2023-10-01T00:00:00.000Z",
+                    "vannvan",
+                    vec![],
+                )],
+            ),
+            doc(
+                "book/旧文档2",
+                "2022-01-01T00:00:00.000Z",
+                "vannvan",
+                vec![],
+            ),
+        ];
+        let matcher = DocMatcher::compile(&DocFilter {
+            updated_since: "2023-01-01".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let (pruned, excluded) = matcher.prune(tree);
+        assert_eq!(excluded, 2);
+        assert_eq!(pruned.len(), 1);
+        // 保留目录结构
+        assert_eq!(pruned[0].node_type, "TITLE");
+        assert_eq!(pruned[0].children[0].full_path, "book/旧文档/新文档");
+    }
+
+    #[test]
+    fn test_merge_filter() {
+        let config = DocFilter {
+            exclude: vec!["book/草稿/**".to_string()],
+            author: "vannvan".to_string(),
+            max_depth: 2,
+            ..Default::default()
+        };
+        let cli = DocFilter {
+            author: "other".to_string(),
+            ..Default::default()
+        };
+        let merged = merge_filter(config, cli);
+        assert_eq!(merged.exclude, vec!["book/草稿/**".to_string()]);
+        assert_eq!(merged.author, "other");
+        assert_eq!(merged.max_depth, 2);
+    }
+
+    #[test]
+    fn test_in_toc_range() {
+        let toc_range = vec!["c++(入门)".to_string(), "book/目录".to_string()];
+        assert!(in_toc_range("c++(入门)/文档", &toc_range));
+        assert!(in_toc_range("book/目录/文档", &toc_range));
+        assert!(!in_toc_range("book/目录2/文档", &toc_range));
+        assert!(!in_toc_range("book/文档", &toc_range));
+    }
+}
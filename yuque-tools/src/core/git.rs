@@ -0,0 +1,200 @@
+/*
+ * Description: 导出目录作为git仓库，每次导出提交一次，保留知识库的历史版本
+ * Created: 2026-10-19 00:43:34
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::process::Command;
+
+use tracing::debug;
+
+use crate::{
+    libs::{file::File, i18n, log::Log},
+    t,
+};
+
+/// 暂存区中的变更
+#[derive(Debug, Default, PartialEq)]
+pub struct GitChanges {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl GitChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+
+    /// 解析 `git diff --cached --name-status --no-renames -z` 的输出
+    pub fn parse(output: &str) -> Self {
+        let mut changes = GitChanges::default();
+        let mut fields = output.split('\0').filter(|field| !field.is_empty());
+        while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+            let path = path.to_string();
+            match status.chars().next() {
+                Some('A') => changes.added.push(path),
+                Some('D') => changes.deleted.push(path),
+                _ => changes.modified.push(path),
+            }
+        }
+        changes
+    }
+
+    /// 提交信息，第一行为汇总，之后按类别列出文档
+    pub fn summary(&self) -> String {
+        let is_doc = |path: &&String| path.ends_with(".md");
+        let count = |paths: &Vec<String>| paths.iter().filter(is_doc).count();
+        let mut message = t!(
+            "git-summary",
+            added = count(&self.added),
+            modified = count(&self.modified),
+            deleted = count(&self.deleted)
+        );
+
+        for (label, paths) in [
+            (t!("git-summary-added"), &self.added),
+            (t!("git-summary-modified"), &self.modified),
+            (t!("git-summary-deleted"), &self.deleted),
+        ] {
+            let docs: Vec<&String> = paths.iter().filter(is_doc).collect();
+            if docs.is_empty() {
+                continue;
+            }
+            message.push_str(&format!("\n\n{}", label));
+            for doc in docs {
+                message.push_str(&format!("\n- {}", doc.trim_end_matches(".md")));
+            }
+        }
+
+        let others = [&self.added, &self.modified, &self.deleted]
+            .iter()
+            .map(|paths| paths.len() - count(paths))
+            .sum::<usize>();
+        if others > 0 {
+            message.push_str(&format!("\n\n{}", t!("git-summary-assets", count = others)));
+        }
+        message
+    }
+}
+
+/// 文档输出目录对应的git仓库
+pub struct GitArchive {
+    dir: String,
+}
+
+impl GitArchive {
+    /// 打开输出目录的仓库，还不是仓库时初始化
+    pub fn open(dir: &str) -> Result<Self, &'static str> {
+        let archive = GitArchive {
+            dir: dir.to_string(),
+        };
+        archive
+            .git(&["--version"])
+            .map_err(|_| t!("git-not-found"))?;
+
+        if !File::new().exists(&format!("{}/.git", dir)) {
+            archive.git(&["init"])?;
+            Log::info(&t!("git-init", dir = dir));
+        }
+        Ok(archive)
+    }
+
+    /// 仓库中已跟踪的文件，路径相对于仓库根目录
+    pub fn tracked_files(&self) -> Result<Vec<String>, &'static str> {
+        let output = self.git(&["ls-files", "-z"])?;
+        Ok(output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| path.to_string())
+            .collect())
+    }
+
+    /// 暂存变更，paths为空时暂存整个仓库
+    /// 导出报告每次都会重写，不纳入版本管理
+    pub fn stage(&self, paths: &[String]) -> Result<(), &'static str> {
+        let excludes: Vec<String> = i18n::all("report-file")
+            .iter()
+            .map(|name| format!(":(exclude){}", name))
+            .collect();
+        let mut args = vec!["add", "-A", "--"];
+        if paths.is_empty() {
+            args.push(".");
+        } else {
+            args.extend(paths.iter().map(|path| path.as_str()));
+        }
+        args.extend(excludes.iter().map(|exclude| exclude.as_str()));
+        self.git(&args).map(|_| ())
+    }
+
+    /// 暂存区中的变更
+    pub fn staged_changes(&self) -> Result<GitChanges, &'static str> {
+        let output = self.git(&["diff", "--cached", "--name-status", "--no-renames", "-z"])?;
+        Ok(GitChanges::parse(&output))
+    }
+
+    /// 提交暂存区
+    /// # Arguments
+    /// * message - 提交信息
+    /// * author - 作者，为空时使用git配置中的用户
+    pub fn commit(&self, message: &str, author: Option<&str>) -> Result<(), &'static str> {
+        let author = author.map(|name| format!("{} <>", name));
+        let mut args = vec![];
+        // 定时任务的环境中常常没有配置git用户
+        if self.git(&["config", "user.name"]).is_err() {
+            args.extend(["-c", "user.name=ytool", "-c", "user.email="]);
+        }
+        args.extend(["commit", "--quiet", "-m", message]);
+        if let Some(author) = &author {
+            args.push("--author");
+            args.push(author);
+        }
+        self.git(&args).map(|_| ())
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, &'static str> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(["-c", "core.quotepath=false"])
+            .args(args)
+            .output()
+            .map_err(|_| t!("git-exec-error"))?;
+
+        debug!(
+            "git {:?}: {}{}",
+            args,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(t!("git-exec-failed"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_changes() {
+        let changes = GitChanges::parse(
+            "A\0book/新文档.md\0M\0book/目录/旧文档.md\0D\0book/删除.md\0A\0attachments/a.png\0",
+        );
+        assert_eq!(changes.added, vec!["book/新文档.md", "attachments/a.png"]);
+        assert_eq!(changes.modified, vec!["book/目录/旧文档.md"]);
+        assert_eq!(changes.deleted, vec!["book/删除.md"]);
+        assert_eq!(
+            changes.summary(),
+            "ytool pull: 新增 1 篇，修改 1 篇，删除 1 篇\n\n新增：\n- book/新文档\n\n修改：\n- book/目录/旧文档\n\n删除：\n- book/删除\n\n附件变更 1 个"
+        );
+        assert!(GitChanges::parse("").is_empty());
+    }
+}
@@ -0,0 +1,455 @@
+/*
+ * Description: 把整个知识库(目录结构和内容)复制到另一个空间的知识库
+ * Created: 2026-10-19 00:24:32
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::collections::HashMap;
+
+use super::{
+    push::{Push, PushApi},
+    scheduler::Scheduler,
+    yuque::YuqueApi,
+};
+use crate::{
+    libs::{
+        constants::{
+            schema::{
+                cache_book::{BookItem, DocItem},
+                PushMap, PushedNode, TreeNone,
+            },
+            GLOBAL_CONFIG,
+        },
+        file::File,
+        log::Log,
+    },
+    t,
+};
+
+/// 读取源知识库用到的语雀接口，测试时替换为本地模拟
+pub trait MirrorSource {
+    /// 知识库目录
+    async fn get_toc(&self, namespace: &str) -> Result<Vec<DocItem>, bool>;
+    /// 文档markdown内容
+    async fn get_body(&self, namespace: &str, url: &str) -> Result<String, bool>;
+}
+
+impl MirrorSource for YuqueApi {
+    async fn get_toc(&self, namespace: &str) -> Result<Vec<DocItem>, bool> {
+        match YuqueApi::get_book_docs_info(&format!("/{}", namespace)).await {
+            Ok(toc) => serde_json::from_value(toc).map_err(|_| false),
+            Err(_) => Err(false),
+        }
+    }
+
+    async fn get_body(&self, namespace: &str, url: &str) -> Result<String, bool> {
+        YuqueApi::get_markdown_content(&format!("/{}/{}", namespace, url), true)
+            .await
+            .map_err(|_| false)
+    }
+}
+
+/// 计划执行的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum MirrorAction {
+    /// 目标知识库中还没有，新建
+    Create,
+    /// 之前复制过的文档，更新内容
+    Update,
+    /// 之前复制过的分组，不用处理
+    Exists,
+}
+
+/// 复制结果
+#[derive(Debug, Default, PartialEq)]
+pub struct MirrorSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub exists: usize,
+    pub failed: usize,
+}
+
+pub struct Mirror;
+
+impl Mirror {
+    /// 复制启动程序
+    /// # Arguments
+    /// * from - 源知识库 用户/slug
+    /// * to - 目标知识库 用户或团队/slug
+    /// * dry_run - 只列出计划执行的操作
+    pub async fn start(from: &str, to: &str, dry_run: bool) -> Result<(), &'static str> {
+        let from = from.trim_matches('/');
+        let to = to.trim_matches('/');
+        if !from.contains("/") || !to.contains("/") {
+            return Err(t!("mirror-namespace-invalid"));
+        }
+        if from == to {
+            return Err(t!("mirror-same-book"));
+        }
+
+        if !dry_run {
+            Push::ensure_token()?;
+        }
+        Push::ensure_login().await?;
+
+        Log::info(&t!("mirror-start", from = from, to = to));
+        let summary = Self::mirror_book(
+            &YuqueApi {},
+            from,
+            to,
+            &GLOBAL_CONFIG.push_map_file,
+            dry_run,
+        )
+        .await?;
+
+        if dry_run {
+            Log::info(&t!(
+                "mirror-plan",
+                created = summary.created,
+                updated = summary.updated,
+                exists = summary.exists
+            ));
+            return Ok(());
+        }
+        if summary.failed > 0 {
+            Log::warn(&t!("mirror-failed-count", count = summary.failed));
+        }
+        Log::success(&t!(
+            "mirror-done",
+            created = summary.created,
+            updated = summary.updated,
+            exists = summary.exists
+        ));
+        Ok(())
+    }
+
+    /// 复制知识库，复制过的节点记录在推送记录中，再次执行时原地更新
+    /// # Arguments
+    /// * api - 语雀接口
+    /// * from - 源知识库
+    /// * to - 目标知识库
+    /// * map_file - 推送记录文件
+    /// * dry_run - 只列出计划执行的操作
+    pub async fn mirror_book(
+        api: &(impl PushApi + MirrorSource),
+        from: &str,
+        to: &str,
+        map_file: &str,
+        dry_run: bool,
+    ) -> Result<MirrorSummary, &'static str> {
+        let f = File::new();
+
+        let docs = api
+            .get_toc(from)
+            .await
+            .map_err(|_| t!("mirror-toc-failed"))?;
+        let tree = Self::build_source_tree(from, docs);
+
+        let mut push_maps: HashMap<String, PushMap> = match f.read(map_file) {
+            Ok(data) => serde_json::from_str(&data).map_err(|_| t!("push-map-parse-failed"))?,
+            Err(_) => HashMap::new(),
+        };
+        let mut push_map = push_maps.get(to).cloned().unwrap_or_default();
+
+        let plan = Self::make_plan(&tree, &push_map, from);
+        let mut summary = MirrorSummary::default();
+
+        for (node, parent_key, action) in plan {
+            let key = Self::node_key(from, &node);
+
+            if dry_run {
+                let action_name = match action {
+                    MirrorAction::Create => "新建",
+                    MirrorAction::Update => "更新",
+                    MirrorAction::Exists => "已存在",
+                };
+                println!("  [{}] {} {}", action_name, node.node_type, node.full_path);
+            }
+
+            match action {
+                MirrorAction::Exists => {
+                    summary.exists += 1;
+                    continue;
+                }
+                _ if dry_run => {
+                    if action == MirrorAction::Create {
+                        summary.created += 1;
+                    } else {
+                        summary.updated += 1;
+                    }
+                    continue;
+                }
+                _ => (),
+            }
+
+            let parent_uuid = match push_map.nodes.get(&parent_key) {
+                Some(parent) => parent.uuid.to_string(),
+                None if parent_key.is_empty() => "".to_string(),
+                // 父级复制失败，子级也无法挂载
+                None => {
+                    summary.failed += 1;
+                    continue;
+                }
+            };
+
+            let result =
+                Self::mirror_node(api, &mut push_map, from, to, &node, &key, &parent_uuid).await;
+            match result {
+                Ok(_) => {
+                    if action == MirrorAction::Create {
+                        summary.created += 1;
+                    } else {
+                        summary.updated += 1;
+                    }
+                    Log::success(&t!("mirror-node-success", path = node.full_path));
+                }
+                Err(_) => {
+                    summary.failed += 1;
+                    Log::error(&t!("mirror-node-failed", path = node.full_path));
+                }
+            }
+
+            // 每个节点复制后都保存记录，中断后重新执行不会重复创建
+            push_maps.insert(to.to_string(), push_map.clone());
+            Push::save_push_maps(map_file, &push_maps)?;
+        }
+
+        Ok(summary)
+    }
+
+    /// 复制单个节点
+    async fn mirror_node(
+        api: &(impl PushApi + MirrorSource),
+        push_map: &mut PushMap,
+        from: &str,
+        to: &str,
+        node: &TreeNone,
+        key: &str,
+        parent_uuid: &str,
+    ) -> Result<(), bool> {
+        let existing = push_map.nodes.get(key).cloned();
+
+        let doc_id = if node.node_type == "DOC" {
+            let body = api.get_body(from, &node.url).await?;
+            match &existing {
+                Some(pushed) => {
+                    api.update_doc(to, pushed.doc_id, &node.title, &body)
+                        .await?;
+                    pushed.doc_id
+                }
+                None => api.create_doc(to, &node.title, &body).await?,
+            }
+        } else {
+            0
+        };
+
+        if existing.is_none() {
+            let uuid = api
+                .append_toc_node(to, parent_uuid, &node.node_type, &node.title, doc_id)
+                .await?;
+            push_map.nodes.insert(
+                key.to_string(),
+                PushedNode {
+                    uuid,
+                    doc_id,
+                    node_type: node.node_type.to_string(),
+                    modified: 0,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// 源知识库目录转成树，同级顺序和源知识库一致
+    fn build_source_tree(from: &str, docs: Vec<DocItem>) -> Vec<TreeNone> {
+        let (user_login, slug) = from.split_once("/").unwrap_or(("", from));
+        Scheduler::build_book_tree(&BookItem {
+            name: slug.to_string(),
+            slug: slug.to_string(),
+            docs,
+            user_login: user_login.to_string(),
+            book_type: "".to_string(),
+        })
+    }
+
+    /// 按先序生成执行计划，保证父级先于子级
+    pub fn make_plan(
+        tree: &Vec<TreeNone>,
+        push_map: &PushMap,
+        from: &str,
+    ) -> Vec<(TreeNone, String, MirrorAction)> {
+        let mut plan: Vec<(TreeNone, String, MirrorAction)> = vec![];
+
+        fn each(
+            nodes: &Vec<TreeNone>,
+            parent_key: &str,
+            push_map: &PushMap,
+            from: &str,
+            plan: &mut Vec<(TreeNone, String, MirrorAction)>,
+        ) {
+            for node in nodes {
+                let key = Mirror::node_key(from, node);
+                let action = match push_map.nodes.get(&key) {
+                    None => MirrorAction::Create,
+                    Some(_) if node.node_type == "DOC" => MirrorAction::Update,
+                    Some(_) => MirrorAction::Exists,
+                };
+                plan.push((node.clone(), parent_key.to_string(), action));
+                each(&node.children, &key, push_map, from, plan);
+            }
+        }
+        each(tree, "", push_map, from, &mut plan);
+
+        plan
+    }
+
+    /// 推送记录中的key，和本地推送的路径区分开
+    fn node_key(from: &str, node: &TreeNone) -> String {
+        format!("mirror:{}:{}", from, node.uuid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::fixtures::doc_item;
+    use std::cell::RefCell;
+
+    /// 本地模拟的语雀接口，记录每次写操作
+    #[derive(Default)]
+    struct MockYuque {
+        next_id: RefCell<u64>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl MirrorSource for MockYuque {
+        async fn get_toc(&self, _namespace: &str) -> Result<Vec<DocItem>, bool> {
+            let doc = |title: &str, node_type: &str, uuid: &str, parent_uuid: &str| DocItem {
+                node_type: node_type.to_string(),
+                url: format!("url-{}", uuid),
+                ..doc_item(title, uuid, parent_uuid)
+            };
+            Ok(vec![
+                doc("分组", "TITLE", "a", ""),
+                doc("第二篇", "DOC", "c", "a"),
+                doc("第一篇", "DOC", "b", "a"),
+                doc("随笔", "DOC", "d", ""),
+            ])
+        }
+
+        async fn get_body(&self, _namespace: &str, url: &str) -> Result<String, bool> {
+            Ok(format!("内容 {}", url))
+        }
+    }
+
+    impl PushApi for MockYuque {
+        async fn create_doc(
+            &self,
+            _namespace: &str,
+            title: &str,
+            _body: &str,
+        ) -> Result<u64, bool> {
+            *self.next_id.borrow_mut() += 1;
+            self.calls.borrow_mut().push(format!("create {}", title));
+            Ok(*self.next_id.borrow())
+        }
+
+        async fn update_doc(
+            &self,
+            _namespace: &str,
+            _doc_id: u64,
+            title: &str,
+            _body: &str,
+        ) -> Result<(), bool> {
+            self.calls.borrow_mut().push(format!("update {}", title));
+            Ok(())
+        }
+
+        async fn append_toc_node(
+            &self,
+            _namespace: &str,
+            parent_uuid: &str,
+            node_type: &str,
+            title: &str,
+            _doc_id: u64,
+        ) -> Result<String, bool> {
+            self.calls
+                .borrow_mut()
+                .push(format!("toc {} {} <- {}", node_type, title, parent_uuid));
+            Ok(format!("new-{}", title))
+        }
+
+        async fn upload_image(&self, _doc_id: u64, _file: &str) -> Result<String, bool> {
+            Ok("".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mirror_book() {
+        let map_file = "./dev/mirror.map.json";
+        let _ = File::new().mkdir("./dev");
+        let _ = File::new().remove(map_file);
+        let api = MockYuque::default();
+
+        // 预演不产生任何写操作
+        let summary = Mirror::mirror_book(&api, "vannvan/src", "team/dst", map_file, true)
+            .await
+            .unwrap();
+        assert_eq!(summary.created, 4);
+        assert!(api.calls.borrow().is_empty());
+
+        Mirror::mirror_book(&api, "vannvan/src", "team/dst", map_file, false)
+            .await
+            .unwrap();
+        // 同级顺序和源知识库一致
+        assert_eq!(
+            api.calls.borrow().clone(),
+            vec![
+                "toc TITLE 分组 <- ",
+                "create 第二篇",
+                "toc DOC 第二篇 <- new-分组",
+                "create 第一篇",
+                "toc DOC 第一篇 <- new-分组",
+                "create 随笔",
+                "toc DOC 随笔 <- ",
+            ]
+        );
+
+        // 再次执行时分组保留，文档原地更新
+        api.calls.borrow_mut().clear();
+        let summary = Mirror::mirror_book(&api, "vannvan/src", "team/dst", map_file, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            MirrorSummary {
+                created: 0,
+                updated: 3,
+                exists: 1,
+                failed: 0
+            }
+        );
+        assert!(api
+            .calls
+            .borrow()
+            .iter()
+            .all(|call| call.starts_with("update")));
+
+        let _ = File::new().remove(map_file);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_map_save_failed() {
+        // 记录文件路径是目录，无法保存时第一个节点之后就停止
+        let map_file = "./dev/mirror-map-dir";
+        let _ = File::new().mkdir(map_file);
+        let api = MockYuque::default();
+
+        let result = Mirror::mirror_book(&api, "vannvan/src", "team/dst", map_file, false).await;
+        assert_eq!(result, Err(t!("push-map-save-failed")));
+        assert_eq!(api.calls.borrow().len(), 1);
+    }
+}
@@ -1,3 +1,20 @@
+pub mod archive;
+pub mod browse;
+pub mod bundle;
 pub mod command;
+pub mod completion;
+pub mod decrypt;
+pub mod filter;
+pub mod git;
+pub mod mirror;
+pub mod plan;
+pub mod push;
 pub mod scheduler;
+pub mod serve;
+pub mod stats;
+pub mod template;
+pub mod upgrade;
+pub mod vault;
+pub mod watch;
+pub mod webhook;
 pub mod yuque;
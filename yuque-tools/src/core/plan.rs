@@ -0,0 +1,151 @@
+/*
+ * Description: 导出计划，先确定每个文档/资源要做什么，再交给下载环节执行
+ * Created: 2026-10-19 00:26:42
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::collections::HashSet;
+
+use crate::{libs::log::Log, t};
+
+/// 计划执行的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanAction {
+    /// 下载(本地已有时覆盖)
+    Download,
+    /// 本地已存在，跳过
+    SkipExists,
+    /// 隐藏/草稿文档，跳过
+    SkipHidden,
+    /// 和前面的文档输出到同一个路径，跳过
+    Conflict,
+}
+
+impl PlanAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlanAction::Download => "download",
+            PlanAction::SkipExists => "skip-exists",
+            PlanAction::SkipHidden => "skip-hidden",
+            PlanAction::Conflict => "conflict",
+        }
+    }
+}
+
+/// 计划中的一项
+#[derive(Debug, Clone)]
+pub struct PlanItem<T> {
+    /// 来源，文档为知识库中的路径，资源为资源名称
+    pub source: String,
+    /// 本地保存路径
+    pub target: String,
+    pub action: PlanAction,
+    /// 执行时需要的数据
+    pub payload: T,
+}
+
+/// 导出计划
+#[derive(Debug)]
+pub struct ExportPlan<T> {
+    pub items: Vec<PlanItem<T>>,
+    targets: HashSet<String>,
+}
+
+impl<T> ExportPlan<T> {
+    pub fn new() -> Self {
+        ExportPlan {
+            items: vec![],
+            targets: HashSet::new(),
+        }
+    }
+
+    /// 添加一项，本地保存路径和前面重复的会标记为冲突
+    pub fn push(&mut self, source: &str, target: &str, action: PlanAction, payload: T) {
+        // 隐藏文档不会输出，不占用路径
+        let is_duplicated =
+            action != PlanAction::SkipHidden && !self.targets.insert(target.to_string());
+        let action = if is_duplicated {
+            PlanAction::Conflict
+        } else {
+            action
+        };
+
+        self.items.push(PlanItem {
+            source: source.to_string(),
+            target: target.to_string(),
+            action,
+            payload,
+        });
+    }
+
+    /// 某个动作的数量
+    pub fn count(&self, action: PlanAction) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.action == action)
+            .count()
+    }
+
+    /// 某个动作的所有项
+    pub fn filter(&self, action: PlanAction) -> Vec<&PlanItem<T>> {
+        self.items
+            .iter()
+            .filter(|item| item.action == action)
+            .collect()
+    }
+
+    /// 预计耗时(秒)，只有需要下载的才耗时
+    /// # Arguments
+    /// * duration - 每个下载的间隔(毫秒)
+    pub fn estimate_secs(&self, duration: usize) -> usize {
+        self.count(PlanAction::Download) * duration / 1000
+    }
+
+    /// 输出计划明细和统计
+    pub fn print(&self, duration: usize) {
+        for item in &self.items {
+            println!(
+                "  [{}] {} -> {}",
+                item.action.label(),
+                item.source,
+                item.target
+            );
+        }
+        Log::info(&t!(
+            "plan-summary",
+            total = self.items.len(),
+            download = self.count(PlanAction::Download),
+            exists = self.count(PlanAction::SkipExists),
+            hidden = self.count(PlanAction::SkipHidden),
+            conflict = self.count(PlanAction::Conflict),
+            secs = self.estimate_secs(duration)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_plan() {
+        let mut plan: ExportPlan<u8> = ExportPlan::new();
+        plan.push("book/a", "./docs/book/a.md", PlanAction::Download, 1);
+        plan.push("book/b", "./docs/book/b.md", PlanAction::SkipExists, 2);
+        // 标题去掉特殊字符后重名
+        plan.push("book/a?", "./docs/book/a.md", PlanAction::Download, 3);
+        plan.push("book/c", "./docs/book/c.md", PlanAction::SkipHidden, 4);
+        plan.push("book/c", "./docs/book/c.md", PlanAction::Download, 5);
+
+        assert_eq!(plan.items[2].action, PlanAction::Conflict);
+        // 隐藏文档不占用路径
+        assert_eq!(plan.items[4].action, PlanAction::Download);
+
+        assert_eq!(plan.count(PlanAction::Download), 2);
+        assert_eq!(plan.filter(PlanAction::Conflict)[0].payload, 3);
+        assert_eq!(plan.estimate_secs(500), 1);
+    }
+}
@@ -0,0 +1,848 @@
+/*
+ * Description: 把本地Markdown目录推送(创建/更新)到语雀知识库
+ * Created: 2026-10-19 00:23:37
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use regex::Regex;
+use reqwest::Method;
+use serde_json::json;
+
+use super::yuque::YuqueApi;
+use crate::{
+    libs::{
+        constants::{
+            schema::{PushMap, PushedNode, YuqueAccount},
+            GLOBAL_CONFIG,
+        },
+        file::File,
+        inquiry,
+        log::Log,
+        request::Request,
+        tools,
+    },
+    t,
+};
+
+/// 作为目录自身内容的文件
+const DIR_DOC_FILES: [&str; 2] = ["index.md", "README.md"];
+
+/// 推送用到的语雀接口，测试时替换为本地模拟
+pub trait PushApi {
+    /// 创建文档，返回文档id
+    async fn create_doc(&self, namespace: &str, title: &str, body: &str) -> Result<u64, bool>;
+    /// 更新文档
+    async fn update_doc(
+        &self,
+        namespace: &str,
+        doc_id: u64,
+        title: &str,
+        body: &str,
+    ) -> Result<(), bool>;
+    /// 在目录中追加节点，parent_uuid为空时追加到根目录，返回节点uuid
+    async fn append_toc_node(
+        &self,
+        namespace: &str,
+        parent_uuid: &str,
+        node_type: &str,
+        title: &str,
+        doc_id: u64,
+    ) -> Result<String, bool>;
+    /// 上传图片，返回图片地址
+    async fn upload_image(&self, doc_id: u64, file: &str) -> Result<String, bool>;
+}
+
+impl PushApi for YuqueApi {
+    async fn create_doc(&self, namespace: &str, title: &str, body: &str) -> Result<u64, bool> {
+        let url = GLOBAL_CONFIG
+            .yuque_repo_docs
+            .replace("{namespace}", namespace);
+        let params = json!({ "title": title, "body": body, "format": "markdown" });
+        match Request::send_json(Method::POST, &url, &params).await {
+            Ok(resp) => resp
+                .get("data")
+                .and_then(|data| data["id"].as_u64())
+                .ok_or(false),
+            Err(_) => Err(false),
+        }
+    }
+
+    async fn update_doc(
+        &self,
+        namespace: &str,
+        doc_id: u64,
+        title: &str,
+        body: &str,
+    ) -> Result<(), bool> {
+        let url = format!(
+            "{}/{}",
+            GLOBAL_CONFIG
+                .yuque_repo_docs
+                .replace("{namespace}", namespace),
+            doc_id
+        );
+        let params = json!({ "title": title, "body": body, "format": "markdown" });
+        match Request::send_json(Method::PUT, &url, &params).await {
+            Ok(resp) if resp.contains_key("data") => Ok(()),
+            _ => Err(false),
+        }
+    }
+
+    async fn append_toc_node(
+        &self,
+        namespace: &str,
+        parent_uuid: &str,
+        node_type: &str,
+        title: &str,
+        doc_id: u64,
+    ) -> Result<String, bool> {
+        let url = GLOBAL_CONFIG
+            .yuque_repo_toc
+            .replace("{namespace}", namespace);
+        let mut params = json!({
+            "action": "appendNode",
+            "action_mode": "child",
+            "type": node_type,
+            "title": title,
+        });
+        if !parent_uuid.is_empty() {
+            params["target_uuid"] = json!(parent_uuid);
+        }
+        if doc_id > 0 {
+            params["doc_ids"] = json!([doc_id]);
+        }
+
+        let resp = Request::send_json(Method::PUT, &url, &params)
+            .await
+            .map_err(|_| false)?;
+        let toc = resp
+            .get("data")
+            .and_then(|data| data.as_array())
+            .ok_or(false)?;
+
+        // 接口返回的是整个目录，按文档id或标题找到新节点
+        toc.iter()
+            .rev()
+            .find(|node| {
+                if doc_id > 0 {
+                    node["doc_id"].as_u64() == Some(doc_id)
+                } else {
+                    node["type"] == node_type
+                        && node["title"] == title
+                        && node["parent_uuid"].as_str().unwrap_or("") == parent_uuid
+                }
+            })
+            .and_then(|node| node["uuid"].as_str())
+            .map(|uuid| uuid.to_string())
+            .ok_or(false)
+    }
+
+    async fn upload_image(&self, doc_id: u64, file: &str) -> Result<String, bool> {
+        let url = GLOBAL_CONFIG
+            .yuque_upload_image
+            .replace("{doc_id}", &doc_id.to_string());
+        match Request::upload(&url, file).await {
+            Ok(resp) => resp
+                .get("data")
+                .and_then(|data| data["url"].as_str())
+                .map(|url| url.to_string())
+                .ok_or(false),
+            Err(_) => Err(false),
+        }
+    }
+}
+
+/// 本地目录结构中的节点
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalNode {
+    pub title: String,
+    /// 推送记录中的key，文档为md文件相对路径，分组为目录相对路径
+    pub key: String,
+    /// DOC 或 TITLE
+    pub node_type: String,
+    /// 文档内容所在文件
+    pub doc_file: Option<PathBuf>,
+    pub children: Vec<LocalNode>,
+}
+
+/// 推送结果
+#[derive(Debug, Default, PartialEq)]
+pub struct PushSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+pub struct Push;
+
+impl Push {
+    /// 推送启动程序
+    /// # Arguments
+    /// * dir - 本地文档目录
+    /// * book - 知识库slug，或 用户/slug
+    pub async fn start(dir: &str, book: &str) -> Result<(), &'static str> {
+        if !Path::new(dir).is_dir() {
+            return Err(t!("push-dir-not-found"));
+        }
+
+        Self::ensure_token()?;
+        Self::ensure_login().await?;
+        let namespace = Self::resolve_namespace(book).await?;
+
+        Log::info(&t!("push-start", dir = dir, book = namespace));
+        let summary =
+            Self::push_dir(&YuqueApi {}, dir, &namespace, &GLOBAL_CONFIG.push_map_file).await?;
+
+        if summary.failed > 0 {
+            Log::warn(&t!("push-failed-count", count = summary.failed));
+        }
+        Log::success(&t!(
+            "push-done",
+            created = summary.created,
+            updated = summary.updated,
+            skipped = summary.skipped
+        ));
+        Ok(())
+    }
+
+    /// 写入文档用的是 /api/v2 开放接口，需要Token
+    pub fn ensure_token() -> Result<(), &'static str> {
+        if Request::auth_token().is_empty() {
+            return Err(t!("push-token-missing"));
+        }
+        Ok(())
+    }
+
+    /// 没有cookies时先登录
+    pub async fn ensure_login() -> Result<(), &'static str> {
+        if !tools::get_local_cookies().is_empty() {
+            return Ok(());
+        }
+
+        let account = match tools::get_user_config() {
+            Ok(user_config)
+                if !user_config.username.is_empty() && !user_config.password.is_empty() =>
+            {
+                YuqueAccount {
+                    username: user_config.username,
+                    password: user_config.password,
+                }
+            }
+            _ => inquiry::ask_user_account(),
+        };
+
+        match YuqueApi::login(&account.username, &account.password).await {
+            Ok(_) => {
+                Log::success(t!("login-success"));
+                Ok(())
+            }
+            Err(_) => Err(t!("login-failed")),
+        }
+    }
+
+    /// 知识库slug转成 用户/slug 的形式
+    async fn resolve_namespace(book: &str) -> Result<String, &'static str> {
+        if book.contains("/") {
+            return Ok(book.trim_matches('/').to_string());
+        }
+
+        if tools::get_cache_books_info().is_err() {
+            let _ = YuqueApi::get_user_bookstacks().await;
+        }
+
+        tools::get_cache_books_info()
+            .unwrap_or(vec![])
+            .iter()
+            .find(|item| item.slug == book || item.name == book)
+            .map(|item| format!("{}/{}", item.user_login, item.slug))
+            .ok_or(t!("book-not-found"))
+    }
+
+    /// 推送目录，已推送过的文档原地更新
+    /// # Arguments
+    /// * api - 语雀接口
+    /// * dir - 本地文档目录
+    /// * namespace - 知识库 用户/slug
+    /// * map_file - 推送记录文件
+    pub async fn push_dir(
+        api: &impl PushApi,
+        dir: &str,
+        namespace: &str,
+        map_file: &str,
+    ) -> Result<PushSummary, &'static str> {
+        let f = File::new();
+        let root = Path::new(dir);
+
+        let mut push_maps: HashMap<String, PushMap> = match f.read(map_file) {
+            Ok(data) => serde_json::from_str(&data).map_err(|_| t!("push-map-parse-failed"))?,
+            Err(_) => HashMap::new(),
+        };
+        let mut push_map = push_maps.get(namespace).cloned().unwrap_or_default();
+
+        // 先序展开，保证父级节点先创建
+        let mut flat_nodes: Vec<(LocalNode, String)> = vec![];
+        fn each(nodes: &Vec<LocalNode>, parent_key: &str, list: &mut Vec<(LocalNode, String)>) {
+            for node in nodes {
+                list.push((node.clone(), parent_key.to_string()));
+                each(&node.children, &node.key, list);
+            }
+        }
+        each(
+            &Self::scan_dir(root, root, false).map_err(|_| t!("push-dir-read-failed"))?,
+            "",
+            &mut flat_nodes,
+        );
+
+        let mut summary = PushSummary::default();
+        for (node, parent_key) in flat_nodes {
+            let parent_uuid = match push_map.nodes.get(&parent_key) {
+                Some(parent) if !parent.uuid.is_empty() => parent.uuid.to_string(),
+                None if parent_key.is_empty() => "".to_string(),
+                // 父级创建失败或还没挂到目录中，子级也无法挂载
+                _ => {
+                    summary.failed += 1;
+                    continue;
+                }
+            };
+
+            let modified = node
+                .doc_file
+                .as_ref()
+                .map_or(0, |doc_file| Self::modified_millis(doc_file));
+
+            let pushed = match push_map.nodes.get(&node.key).cloned() {
+                Some(pushed) if pushed.modified == modified && !pushed.uuid.is_empty() => {
+                    summary.skipped += 1;
+                    continue;
+                }
+                Some(pushed) => Ok(pushed),
+                // 文档新建后立即保存记录，后续步骤失败时重新推送只补全剩余步骤，不会重复新建
+                None => match Self::create_node(api, root, namespace, &node, modified).await {
+                    Ok(pushed) => {
+                        push_map.nodes.insert(node.key.to_string(), pushed.clone());
+                        push_maps.insert(namespace.to_string(), push_map.clone());
+                        Self::save_push_maps(map_file, &push_maps)?;
+                        Ok(pushed)
+                    }
+                    Err(err) => Err(err),
+                },
+            };
+            let result = match pushed {
+                Ok(pushed) => {
+                    let is_new = pushed.uuid.is_empty();
+                    Self::sync_node(
+                        api,
+                        &mut push_map,
+                        root,
+                        namespace,
+                        &node,
+                        &parent_uuid,
+                        pushed,
+                    )
+                    .await
+                    .map(|_| {
+                        if is_new {
+                            summary.created += 1
+                        } else {
+                            summary.updated += 1
+                        }
+                    })
+                }
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(_) => Log::success(&t!("push-node-success", path = node.key)),
+                Err(_) => {
+                    summary.failed += 1;
+                    Log::error(&t!("push-node-failed", path = node.key));
+                }
+            }
+
+            // 每个节点推送后都保存记录，中断后重新推送不会重复创建
+            push_maps.insert(namespace.to_string(), push_map.clone());
+            Self::save_push_maps(map_file, &push_maps)?;
+        }
+
+        Ok(summary)
+    }
+
+    /// 新建文档，返回还没挂到目录中的推送记录，分组不需要新建文档
+    /// 文档引用了本地图片时先建空文档，内容在上传图片后更新，记录中的修改时间为0
+    async fn create_node(
+        api: &impl PushApi,
+        root: &Path,
+        namespace: &str,
+        node: &LocalNode,
+        modified: u64,
+    ) -> Result<PushedNode, bool> {
+        let (doc_id, modified) = match &node.doc_file {
+            Some(doc_file) => {
+                let content = File::new()
+                    .read(&doc_file.to_string_lossy())
+                    .map_err(|_| false)?;
+                if Self::find_local_images(&content, root, doc_file).is_empty() {
+                    (
+                        api.create_doc(namespace, &node.title, &content).await?,
+                        modified,
+                    )
+                } else {
+                    // 上传图片需要文档id
+                    (api.create_doc(namespace, &node.title, "").await?, 0)
+                }
+            }
+            None => (0, modified),
+        };
+
+        Ok(PushedNode {
+            uuid: "".to_string(),
+            doc_id,
+            node_type: node.node_type.to_string(),
+            modified,
+        })
+    }
+
+    /// 补全推送：内容有变化时更新文档，还没挂到目录中时挂到目录
+    async fn sync_node(
+        api: &impl PushApi,
+        push_map: &mut PushMap,
+        root: &Path,
+        namespace: &str,
+        node: &LocalNode,
+        parent_uuid: &str,
+        mut pushed: PushedNode,
+    ) -> Result<(), bool> {
+        let modified = node
+            .doc_file
+            .as_ref()
+            .map_or(0, |doc_file| Self::modified_millis(doc_file));
+        if pushed.modified != modified {
+            if let Some(doc_file) = &node.doc_file {
+                let content = File::new()
+                    .read(&doc_file.to_string_lossy())
+                    .map_err(|_| false)?;
+                let body =
+                    Self::upload_images(api, push_map, root, doc_file, &content, pushed.doc_id)
+                        .await?;
+                api.update_doc(namespace, pushed.doc_id, &node.title, &body)
+                    .await?;
+            }
+        }
+
+        if pushed.uuid.is_empty() {
+            pushed.uuid = api
+                .append_toc_node(
+                    namespace,
+                    parent_uuid,
+                    &node.node_type,
+                    &node.title,
+                    pushed.doc_id,
+                )
+                .await?;
+        }
+
+        push_map
+            .nodes
+            .insert(node.key.to_string(), PushedNode { modified, ..pushed });
+        Ok(())
+    }
+
+    /// 上传文档中引用的本地图片，返回替换成线上地址后的内容
+    async fn upload_images(
+        api: &impl PushApi,
+        push_map: &mut PushMap,
+        root: &Path,
+        doc_file: &Path,
+        content: &str,
+        doc_id: u64,
+    ) -> Result<String, bool> {
+        let mut body = content.to_string();
+
+        for (link, image_key) in Self::find_local_images(content, root, doc_file) {
+            let image_url = match push_map.images.get(&image_key) {
+                Some(image_url) => image_url.to_string(),
+                None => {
+                    let image_file = root.join(&image_key);
+                    let image_url = api
+                        .upload_image(doc_id, &image_file.to_string_lossy())
+                        .await?;
+                    push_map
+                        .images
+                        .insert(image_key.to_string(), image_url.to_string());
+                    image_url
+                }
+            };
+            body = body.replace(&format!("]({})", link), &format!("]({})", image_url));
+            body = body.replace(&format!("](<{}>)", link), &format!("]({})", image_url));
+        }
+
+        Ok(body)
+    }
+
+    /// 找出文档中引用的本地图片，返回(原链接, 相对推送目录的路径)
+    pub fn find_local_images(content: &str, root: &Path, doc_file: &Path) -> Vec<(String, String)> {
+        let reg = Regex::new(r#"!\[[^\]]*\]\(<?([^)\s>]+)>?(\s+"[^"]*")?\)"#).unwrap();
+        let doc_dir = doc_file.parent().unwrap_or(root);
+        let root_abs = match root.canonicalize() {
+            Ok(root_abs) => root_abs,
+            Err(_) => return vec![],
+        };
+
+        let mut images: Vec<(String, String)> = vec![];
+        for captured in reg.captures_iter(content) {
+            let link = captured[1].to_string();
+            if link.starts_with("http://")
+                || link.starts_with("https://")
+                || link.starts_with("//")
+                || link.starts_with("data:")
+            {
+                continue;
+            }
+
+            // 转成绝对路径，../ 形式的链接才能对应到同一张图片
+            let image_file = match doc_dir.join(link.replace("%20", " ")).canonicalize() {
+                Ok(image_file) if image_file.is_file() => image_file,
+                _ => continue,
+            };
+            if let Ok(rel_path) = image_file.strip_prefix(&root_abs) {
+                let image_key = Self::normalize_key(rel_path);
+                if !images.iter().any(|(l, _)| l == &link) {
+                    images.push((link, image_key));
+                }
+            }
+        }
+        images
+    }
+
+    /// 扫描本地目录，转成语雀的目录结构
+    /// - xxx.md 是文档
+    /// - 目录有 index.md/README.md 或同名的 xxx.md 时，是带子文档的文档
+    /// - 其他目录是分组(TITLE)
+    pub fn scan_dir(
+        root: &Path,
+        dir: &Path,
+        skip_dir_doc: bool,
+    ) -> Result<Vec<LocalNode>, std::io::Error> {
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                let name = path.file_name().unwrap().to_string_lossy();
+                !name.starts_with(".")
+            })
+            .collect();
+        entries.sort();
+
+        let mut nodes: Vec<LocalNode> = vec![];
+        let mut consumed: Vec<PathBuf> = vec![];
+
+        for path in &entries {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+            if path.is_dir() {
+                let rel_path = Self::normalize_key(path.strip_prefix(root).unwrap());
+                let inner_doc = DIR_DOC_FILES
+                    .iter()
+                    .map(|file_name| path.join(file_name))
+                    .find(|file| file.is_file());
+                let sibling_doc = dir.join(format!("{}.md", name));
+
+                let (doc_file, skip_inner) = if let Some(inner_doc) = inner_doc {
+                    (Some(inner_doc), true)
+                } else if sibling_doc.is_file() {
+                    consumed.push(sibling_doc.clone());
+                    (Some(sibling_doc), false)
+                } else {
+                    (None, false)
+                };
+
+                let children = Self::scan_dir(root, path, skip_inner)?;
+                // 空目录不推送
+                if doc_file.is_none() && children.is_empty() {
+                    continue;
+                }
+
+                nodes.push(LocalNode {
+                    title: name,
+                    key: doc_file.as_ref().map_or(rel_path, |doc_file| {
+                        Self::normalize_key(doc_file.strip_prefix(root).unwrap())
+                    }),
+                    node_type: if doc_file.is_some() { "DOC" } else { "TITLE" }.to_string(),
+                    doc_file,
+                    children,
+                });
+            }
+        }
+
+        for path in &entries {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            if !path.is_file() || !name.ends_with(".md") || consumed.contains(path) {
+                continue;
+            }
+            if skip_dir_doc && DIR_DOC_FILES.contains(&name.as_str()) {
+                continue;
+            }
+            let key = Self::normalize_key(path.strip_prefix(root).unwrap());
+            // 导出报告不参与推送
+            if tools::is_report_file(&key) {
+                continue;
+            }
+
+            nodes.push(LocalNode {
+                title: name.trim_end_matches(".md").to_string(),
+                key,
+                node_type: "DOC".to_string(),
+                doc_file: Some(path.to_path_buf()),
+                children: vec![],
+            });
+        }
+
+        // 和本地目录顺序保持一致
+        nodes.sort_by(|a, b| a.title.cmp(&b.title));
+        Ok(nodes)
+    }
+
+    fn normalize_key(path: &Path) -> String {
+        path.to_string_lossy().replace("\\", "/")
+    }
+
+    fn modified_millis(file: &Path) -> u64 {
+        fs::metadata(file)
+            .and_then(|meta| meta.modified())
+            .map(|time| time.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    /// 保存推送记录，保存失败时再次执行会重复创建，调用方应停止推送
+    pub fn save_push_maps(
+        map_file: &str,
+        push_maps: &HashMap<String, PushMap>,
+    ) -> Result<(), &'static str> {
+        let f = File::new();
+        if let Some(parent_dir) = Path::new(map_file).parent() {
+            let _ = f.mkdir(&parent_dir.to_string_lossy());
+        }
+        f.write(
+            map_file,
+            serde_json::to_string_pretty(push_maps).unwrap_or_default(),
+        )
+        .map_err(|_| t!("push-map-save-failed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// 本地模拟的语雀接口，记录每次调用
+    #[derive(Default)]
+    struct MockYuque {
+        next_id: RefCell<u64>,
+        calls: RefCell<Vec<String>>,
+        docs: RefCell<HashMap<u64, String>>,
+        /// 挂到目录时失败一次的文档标题
+        fail_toc: RefCell<Vec<String>>,
+    }
+
+    impl MockYuque {
+        fn gen_id(&self) -> u64 {
+            *self.next_id.borrow_mut() += 1;
+            *self.next_id.borrow()
+        }
+    }
+
+    impl PushApi for MockYuque {
+        async fn create_doc(&self, _namespace: &str, title: &str, body: &str) -> Result<u64, bool> {
+            let id = self.gen_id();
+            self.calls.borrow_mut().push(format!("create {}", title));
+            self.docs.borrow_mut().insert(id, body.to_string());
+            Ok(id)
+        }
+
+        async fn update_doc(
+            &self,
+            _namespace: &str,
+            doc_id: u64,
+            title: &str,
+            body: &str,
+        ) -> Result<(), bool> {
+            self.calls.borrow_mut().push(format!("update {}", title));
+            self.docs.borrow_mut().insert(doc_id, body.to_string());
+            Ok(())
+        }
+
+        async fn append_toc_node(
+            &self,
+            _namespace: &str,
+            parent_uuid: &str,
+            node_type: &str,
+            title: &str,
+            _doc_id: u64,
+        ) -> Result<String, bool> {
+            let mut fail_toc = self.fail_toc.borrow_mut();
+            if let Some(index) = fail_toc.iter().position(|item| item == title) {
+                fail_toc.remove(index);
+                return Err(false);
+            }
+            self.calls
+                .borrow_mut()
+                .push(format!("toc {} {} <- {}", node_type, title, parent_uuid));
+            Ok(format!("uuid-{}", title))
+        }
+
+        async fn upload_image(&self, _doc_id: u64, file: &str) -> Result<String, bool> {
+            let name = Path::new(file).file_name().unwrap().to_string_lossy();
+            self.calls.borrow_mut().push(format!("upload {}", name));
+            Ok(format!("https://cdn.nlark.com/{}", name))
+        }
+    }
+
+    fn setup_dir(dir: &str) {
+        let f = File::new();
+        let _ = f.rmdir(dir);
+        let _ = f.mkdir(&format!("{}/分组/父文档", dir));
+        let _ = f.mkdir(&format!("{}/带索引", dir));
+        let _ = f.write(&format!("{}/文档.md", dir), "![图](./img.png)".to_string());
+        let _ = f.write(&format!("{}/img.png", dir), "png".to_string());
+        let _ = f.write(&format!("{}/分组/父文档.md", dir), "父".to_string());
+        let _ = f.write(
+            &format!("{}/分组/父文档/子文档.md", dir),
+            "![图](<../../img.png>)".to_string(),
+        );
+        let _ = f.write(&format!("{}/带索引/index.md", dir), "索引".to_string());
+        let _ = f.write(&format!("{}/带索引/a.md", dir), "a".to_string());
+        let _ = f.write(&format!("{}/导出报告.md", dir), "# 导出报告".to_string());
+    }
+
+    #[test]
+    fn test_scan_dir() {
+        let dir = "./dev/push-scan";
+        setup_dir(dir);
+
+        let root = Path::new(dir);
+        let nodes = Push::scan_dir(root, root, false).unwrap();
+        let titles: Vec<&str> = nodes.iter().map(|node| node.title.as_str()).collect();
+        assert_eq!(titles, vec!["分组", "带索引", "文档"]);
+
+        assert_eq!(nodes[0].node_type, "TITLE");
+        assert_eq!(nodes[0].children[0].key, "分组/父文档.md");
+        assert_eq!(nodes[0].children[0].children[0].title, "子文档");
+        assert_eq!(nodes[1].key, "带索引/index.md");
+        assert_eq!(nodes[1].children.len(), 1);
+
+        let _ = File::new().rmdir(dir);
+    }
+
+    #[tokio::test]
+    async fn test_push_dir() {
+        let dir = "./dev/push-docs";
+        let map_file = "./dev/push-docs.map.json";
+        setup_dir(dir);
+        let _ = File::new().remove(map_file);
+
+        let api = MockYuque::default();
+        let summary = Push::push_dir(&api, dir, "vannvan/test", map_file)
+            .await
+            .unwrap();
+        assert_eq!(summary.created, 6);
+        assert_eq!(summary.failed, 0);
+
+        let calls = api.calls.borrow().clone();
+        assert!(calls.contains(&"toc TITLE 分组 <- ".to_string()));
+        assert!(calls.contains(&"toc DOC 子文档 <- uuid-父文档".to_string()));
+        // 同一张图片只上传一次，链接替换为线上地址
+        assert_eq!(
+            calls
+                .iter()
+                .filter(|call| call.starts_with("upload"))
+                .count(),
+            1
+        );
+        assert!(api
+            .docs
+            .borrow()
+            .values()
+            .any(|body| body == "![图](https://cdn.nlark.com/img.png)"));
+
+        // 没有变化时不再推送
+        let summary = Push::push_dir(&api, dir, "vannvan/test", map_file)
+            .await
+            .unwrap();
+        assert_eq!(summary.skipped, 6);
+
+        // 修改过的文档原地更新
+        let mut push_maps: HashMap<String, PushMap> =
+            serde_json::from_str(&File::new().read(map_file).unwrap()).unwrap();
+        push_maps
+            .get_mut("vannvan/test")
+            .unwrap()
+            .nodes
+            .get_mut("文档.md")
+            .unwrap()
+            .modified = 0;
+        let _ = File::new().write(map_file, serde_json::to_string(&push_maps).unwrap());
+
+        let summary = Push::push_dir(&api, dir, "vannvan/test", map_file)
+            .await
+            .unwrap();
+        assert_eq!(summary.updated, 1);
+        assert_eq!(api.calls.borrow().last().unwrap(), "update 文档");
+
+        let _ = File::new().rmdir(dir);
+        let _ = File::new().remove(map_file);
+    }
+
+    #[tokio::test]
+    async fn test_push_resume() {
+        let dir = "./dev/push-resume";
+        let map_file = "./dev/push-resume.map.json";
+        setup_dir(dir);
+        let _ = File::new().remove(map_file);
+
+        let api = MockYuque::default();
+        api.fail_toc.borrow_mut().push("文档".to_string());
+        let summary = Push::push_dir(&api, dir, "vannvan/test", map_file)
+            .await
+            .unwrap();
+        assert_eq!(summary.created, 5);
+        assert_eq!(summary.failed, 1);
+
+        // 文档已新建并记录了id，只是还没挂到目录中
+        let push_maps: HashMap<String, PushMap> =
+            serde_json::from_str(&File::new().read(map_file).unwrap()).unwrap();
+        let pushed = &push_maps["vannvan/test"].nodes["文档.md"];
+        assert!(pushed.doc_id > 0);
+        assert!(pushed.uuid.is_empty());
+
+        // 重新推送时沿用已新建的文档，只补全剩余步骤
+        let summary = Push::push_dir(&api, dir, "vannvan/test", map_file)
+            .await
+            .unwrap();
+        assert_eq!(summary.created, 1);
+        assert_eq!(summary.skipped, 5);
+        let calls = api.calls.borrow().clone();
+        assert_eq!(
+            calls.iter().filter(|call| *call == "create 文档").count(),
+            1
+        );
+        assert_eq!(calls.last().unwrap(), "toc DOC 文档 <- ");
+        assert_eq!(
+            api.docs.borrow()[&pushed.doc_id],
+            "![图](https://cdn.nlark.com/img.png)"
+        );
+
+        let _ = File::new().rmdir(dir);
+        let _ = File::new().remove(map_file);
+    }
+}
@@ -7,43 +7,68 @@
  * Copyright (c) https://github.com/vannvan
  */
 
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use progress_bar::*;
-use regex::Regex;
+use chrono::Local;
 use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::process;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use std::{cell::RefCell, thread::sleep};
 use terminal_link::Link;
+use tracing::{debug, trace, Instrument};
+use yuque_common::export::ExportPolicy;
 
 use crate::libs::request::Request;
 use crate::{
-    core::yuque::YuqueApi,
+    core::{
+        archive::{ArchiveFormat, ExportArchive},
+        filter::{self, DocMatcher, TreeFilter},
+        git::GitArchive,
+        plan::{ExportPlan, PlanAction},
+        template::{self, OutputTemplates},
+        vault::{Vault, VaultContext},
+        yuque::YuqueApi,
+    },
     libs::{
+        cipher::{Cipher, ENCRYPTED_EXT},
         constants::{
-            schema::{MutualAnswer, ResourceItem, TreeNone, YuqueAccount},
+            schema::{
+                cache_book::BookItem, DocFilter, DocLayout, ExportTarget, MutualAnswer,
+                PathTemplates, PullOptions, ResourceItem, TreeNone, YuqueAccount,
+            },
             GLOBAL_CONFIG,
         },
         file::File,
         inquiry,
         log::Log,
+        progress::Progress,
+        search_index::SearchIndex,
         tools,
     },
+    t,
 };
 
 pub struct Scheduler;
 impl Scheduler {
     /// 知识库启动程序
-    pub async fn start() -> Result<(), &'static str> {
+    /// # Arguments
+    /// * options - 命令行参数，覆盖配置文件中的同名项
+    pub async fn start(options: PullOptions) -> Result<(), &'static str> {
+        // dry_run只根据已缓存的知识库信息生成计划，不登录，也不写入任何缓存
+        if options.dry_run {
+            if tools::get_cache_books_info().is_err() {
+                return Err(t!("pull-dry-run-no-cache"));
+            }
+            return Self::handle_inquiry(options).await;
+        }
+
         let cookies = tools::get_local_cookies();
 
         // 没有cookie缓存，进入登录环节
         if cookies.is_empty() {
             match tools::get_user_config() {
                 Ok(user_config) => {
-                    if cfg!(debug_assertions) {
-                        println!("user_config: {:?}", user_config);
-                    }
+                    debug!("user_config: {:?}", user_config);
                     // 尝试默认使用配置中的账号信息
                     let account = YuqueAccount {
                         username: user_config.username.to_string(),
@@ -52,17 +77,15 @@ impl Scheduler {
 
                     // 如果配置中缺少账户信息，就进入询问环节
                     if account.username.is_empty() || account.password.is_empty() {
-                        Self::start_program(None).await;
+                        Self::start_program(None, options).await
                     } else {
                         // 填入用户的配置进入后面的流程
-                        Self::start_program(Some(account)).await;
+                        Self::start_program(Some(account), options).await
                     }
                 }
                 Err(_err) => {
-                    if cfg!(debug_assertions) {
-                        println!("没有配置文件开始问询");
-                    }
-                    Self::start_program(None).await;
+                    debug!("没有配置文件开始问询");
+                    Self::start_program(None, options).await
                 }
             }
         } else {
@@ -70,19 +93,22 @@ impl Scheduler {
             let books_info = tools::get_cache_books_info();
 
             if books_info.is_ok() {
-                Self::handle_inquiry()
+                Self::handle_inquiry(options).await
             } else {
                 if let Ok(_books_info) = YuqueApi::get_user_bookstacks().await {
-                    Log::success("获取知识库成功");
-                    Self::handle_inquiry()
+                    Log::success(t!("books-fetch-success"));
+                    return Self::handle_inquiry(options).await;
                 }
+                Ok(())
             }
         }
-        Ok(())
     }
 
     /// 所有环节进入问询程序
-    async fn start_program(arg: Option<YuqueAccount>) {
+    async fn start_program(
+        arg: Option<YuqueAccount>,
+        options: PullOptions,
+    ) -> Result<(), &'static str> {
         let account = match arg {
             Some(config_account) => config_account,
             None => inquiry::ask_user_account(),
@@ -90,82 +116,278 @@ impl Scheduler {
 
         match YuqueApi::login(&account.username, &account.password).await {
             Ok(_resp) => {
-                Log::success("登录成功!");
+                Log::success(t!("login-success"));
                 // 接着就开始获取知识库
                 if let Ok(_books_info) = YuqueApi::get_user_bookstacks().await {
-                    Log::success("获取知识库成功");
-                    Self::handle_inquiry()
+                    Log::success(t!("books-fetch-success"));
+                    return Self::handle_inquiry(options).await;
                 }
+                Ok(())
             }
-            Err(_err) => {
-                Log::error("登录失败，请检查账号信息是否正确或重试");
-                process::exit(1)
-            }
+            Err(_err) => Err(t!("login-failed")),
         }
     }
 
     /// 执行询问程序
-    fn handle_inquiry() {
+    /// # Arguments
+    /// * options - 命令行参数
+    pub async fn handle_inquiry(options: PullOptions) -> Result<(), &'static str> {
+        let mut answer = Self::config_answer(&options);
+
+        // 如果从配置传入的参数有效就不进入询问环节
+        if answer.toc_range.is_empty() {
+            let filter = answer.filter;
+            let target = answer.target;
+            let attachment_dir = answer.attachment_dir;
+            let path_template = answer.path_template;
+            answer = inquiry::ask_user_toc_options();
+            answer.filter = filter;
+            answer.target = target;
+            answer.attachment_dir = attachment_dir;
+            answer.path_template = path_template;
+            if answer.toc_range.is_empty() {
+                return Err(t!("inquiry-no-book-selected"));
+            }
+        }
+        Self::download_task_pre_construction(answer, &options).await
+    }
+
+    /// 配置文件中的导出配置，命令行参数覆盖同名项
+    /// # Arguments
+    /// * options - 命令行参数
+    fn config_answer(options: &PullOptions) -> MutualAnswer {
         let mut answer = MutualAnswer {
             toc_range: vec![],
             line_break: true,
             skip: true,
+            layout: DocLayout::Sibling,
+            include_hidden: false,
+            filter: DocFilter::default(),
+            target: ExportTarget::Markdown,
+            attachment_dir: "".to_string(),
+            path_template: PathTemplates::default(),
         };
+        let mut config_filter = DocFilter::default();
+        let mut config_target = ExportTarget::Markdown;
 
         if let Ok(user_config) = tools::get_user_config() {
-            if cfg!(debug_assertions) {
-                println!("用户配置的参数: {:?}", user_config);
-            }
+            debug!("用户配置的参数: {:?}", user_config);
             answer.toc_range = user_config.toc_range;
             answer.skip = user_config.skip;
-            answer.line_break = user_config.line_break
+            answer.line_break = user_config.line_break;
+            answer.layout = user_config.layout;
+            answer.include_hidden = user_config.include_hidden;
+            config_filter = user_config.filter;
+            config_target = user_config.target;
+            answer.attachment_dir = user_config.attachment_dir;
+            answer.path_template = user_config.path_template;
+        }
+        answer.filter = filter::merge_filter(config_filter, options.filter.clone());
+        answer.target = options.target.unwrap_or(config_target);
+        if let Some(doc_template) = &options.path_template {
+            answer.path_template.doc = doc_template.to_string();
         }
+        answer
+    }
 
-        // 如果从配置传入的参数有效就不进入询问环节
-        if answer.toc_range.len() > 0 {
-            Self::download_task_pre_construction(answer);
-        } else {
-            answer = inquiry::ask_user_toc_options();
-            if answer.toc_range.len() > 0 {
-                Self::download_task_pre_construction(answer);
-            } else {
-                Log::error("未选择知识库，程序退出");
-                process::exit(1)
-            }
+    /// 编译过滤条件和路径模板，有错误时不做任何导出
+    fn compile_matcher_and_templates(
+        answer: &MutualAnswer,
+    ) -> Result<(DocMatcher, OutputTemplates), &'static str> {
+        let matcher = filter::compile(&answer.filter).map_err(|err| {
+            Log::error(&err);
+            t!("filter-invalid")
+        })?;
+
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let mut templates =
+            OutputTemplates::compile(&answer.path_template, &today).map_err(|err| {
+                Log::error(&err);
+                t!("pull-aborted")
+            })?;
+        // 只有Obsidian会下载附件，普通Markdown配置附件模板没有意义
+        if answer.target == ExportTarget::Markdown && templates.has_asset_template() {
+            Log::error(t!("template-asset-obsidian-only"));
+            return Err(t!("pull-aborted"));
         }
+        // Logseq的页面和附件位置是固定的
+        if answer.target == ExportTarget::Logseq
+            && (templates.has_doc_template() || templates.has_asset_template())
+        {
+            Log::warn(t!("template-logseq-ignored"));
+            templates = OutputTemplates::default();
+        }
+        Ok((matcher, templates))
     }
 
     /// 导出任务预先构造程序
-    fn download_task_pre_construction(answer: MutualAnswer) {
-        let f = File::new();
+    /// # Arguments
+    /// * answer - 导出配置
+    /// * options - 命令行参数，dry_run时只输出导出计划，不修改任何本地文件
+    async fn download_task_pre_construction(
+        mut answer: MutualAnswer,
+        options: &PullOptions,
+    ) -> Result<(), &'static str> {
+        let dry_run = options.dry_run;
+        // 只同步有变化的文档时，其余文档本地已存在就跳过
+        if options.changed_docs.is_some() {
+            answer.skip = true;
+        }
+        // 导出为归档时全部写入归档，和本地文件无关
+        if let Some(archive_path) = &options.archive {
+            ArchiveFormat::from_path(archive_path)?;
+            answer.skip = false;
+        }
 
-        println!(
-            "将按以下配置进行导出：\n  知识库：{:?}\n  跳过本地：{}\n  保留换行：{}",
-            answer.toc_range, answer.skip, answer.line_break
-        );
+        println!("{}", t!("pull-config"));
+        for line in [
+            t!(
+                "pull-config-books",
+                value = format!("{:?}", answer.toc_range)
+            ),
+            t!("pull-config-skip", value = answer.skip),
+            t!("pull-config-line-break", value = answer.line_break),
+            t!("pull-config-hidden", value = answer.include_hidden),
+        ] {
+            println!("  {}", line);
+        }
+
+        // 模板在获取文档之前校验，有错误时不做任何导出
+        let (matcher, templates) = Self::compile_matcher_and_templates(&answer)?;
 
         // 获取知识库，去掉二级目录
         let toc_range = tools::get_top_level_toc_from_toc_range(&answer.toc_range);
 
         // 树形 docs列表
-        let new_nodes = Self::build_docs_nodes_for_tree(&toc_range);
+        let mut new_nodes = Self::build_docs_nodes_for_tree(&toc_range);
+        if !matcher.is_empty() {
+            println!("  {}", t!("filter-summary"));
+            for line in filter::describe(&answer.filter, &answer.toc_range) {
+                println!("    {}", line);
+            }
+            new_nodes = Self::apply_doc_filter(new_nodes, &matcher);
+        }
         // 扁平 docs列表
-        let flat_docs_list = Self::filter_valid_docs_to_flat(&new_nodes);
+        let flat_docs_list = Self::filter_valid_docs_to_flat(&new_nodes, &answer);
+        // 因隐藏被跳过的文档，用于导出报告
+        let hidden_docs_list = Self::filter_hidden_docs_to_flat(&new_nodes, &answer);
+
+        // 指定了 --dump-dir 时输出两个文件
+        if !dry_run {
+            Log::dump("tree-doc.json", &new_nodes);
+            Log::dump("flat-doc.json", &flat_docs_list);
+        }
 
-        // 输出两个文件
-        if cfg!(debug_assertions) {
-            let _ = f.write(
-                "./dev/tree-doc.json",
-                serde_json::to_string_pretty(&new_nodes).unwrap(),
-            );
+        let plan = Self::plan_doc_task(
+            &answer,
+            &templates,
+            flat_docs_list,
+            hidden_docs_list,
+            options.changed_docs.as_ref(),
+        );
 
-            let _ = f.write(
-                "./dev/flat-doc.json",
-                serde_json::to_string_pretty(&flat_docs_list).unwrap(),
-            );
+        // 使用模板时不允许两篇文档输出到同一路径，导出前就报错
+        let conflict_items = plan.filter(PlanAction::Conflict);
+        if templates.has_doc_template() && !conflict_items.is_empty() {
+            for item in conflict_items {
+                Log::error(&t!(
+                    "template-conflict",
+                    source = item.source,
+                    target = item.target
+                ));
+            }
+            Log::error(&t!(
+                "template-conflict-abort",
+                count = plan.count(PlanAction::Conflict)
+            ));
+            return Err(t!("pull-aborted"));
+        }
+
+        if dry_run {
+            Log::info(t!("pull-dry-run"));
+            plan.print(GLOBAL_CONFIG.duration);
+            if options.git {
+                Log::info(&t!(
+                    "pull-dry-run-git",
+                    dir = GLOBAL_CONFIG.target_output_dir
+                ));
+            }
+            if let Some(archive_path) = &options.archive {
+                Log::info(&t!("pull-dry-run-archive", file = archive_path));
+            }
+            return Ok(());
         }
 
-        Self::delay_download_doc_task(answer, flat_docs_list)
+        // 加密方式在导出之前确定，口令在这里输入
+        let cipher = Self::resolve_cipher(options)?;
+        if let Some(cipher) = &cipher {
+            // 逐个文件加密时git中只有 .age 文件，无法按文档提交
+            if options.git {
+                return Err(t!("encrypt-git-conflict"));
+            }
+            if cipher.is_passphrase() && options.archive.is_none() {
+                Log::warn(t!("encrypt-passphrase-slow"));
+            }
+        }
+
+        let archive = match &options.archive {
+            Some(archive_path) => Some(ExportArchive::new(
+                archive_path,
+                options.archive_split,
+                cipher.clone(),
+            )?),
+            None => None,
+        };
+
+        // 没有过滤且导出整个知识库时，本地多出的文档就是语雀上已删除的文档
+        // 使用文档模板时文档不一定在知识库目录下，不清理
+        let prune_stale = matcher.is_empty()
+            && !answer.toc_range.join("").contains('/')
+            && !templates.has_doc_template();
+        let git = if options.git {
+            Some((options.git_author, prune_stale))
+        } else {
+            None
+        };
+        Self::delay_download_doc_task(answer, templates, plan, git, archive, cipher).await
+    }
+
+    /// 命令行参数和配置档案决定的加密方式
+    fn resolve_cipher(options: &PullOptions) -> Result<Option<Cipher>, &'static str> {
+        let profile_encrypt = GLOBAL_CONFIG
+            .profile
+            .as_ref()
+            .map(|profile| &profile.encrypt);
+        Cipher::resolve(options.encrypt.as_ref(), profile_encrypt)
+    }
+
+    /// 按过滤条件裁剪各知识库的文档树
+    /// # Arguments
+    /// * nodes - 树形列表
+    /// * matcher - 过滤条件
+    fn apply_doc_filter(nodes: Vec<Vec<TreeNone>>, matcher: &DocMatcher) -> Vec<Vec<TreeNone>> {
+        // 旧缓存中没有更新时间和作者，这些文档会全部被排除
+        if matcher.need_meta() {
+            let missing_meta_docs = Self::collect_nodes_to_flat(&nodes, &|node| {
+                node.node_type == "DOC" && node.updated_at.is_empty()
+            });
+            if !missing_meta_docs.is_empty() {
+                Log::warn(&t!("pull-missing-meta", count = missing_meta_docs.len()));
+            }
+        }
+
+        let mut excluded = 0;
+        let pruned = nodes
+            .into_iter()
+            .map(|book_nodes| {
+                let (book_nodes, count) = matcher.prune(book_nodes);
+                excluded += count;
+                book_nodes
+            })
+            .collect();
+        Log::info(&t!("pull-filtered", count = excluded));
+
+        pruned
     }
 
     /// 构造便于递归操作的node结构,将便于操作的nodes结构返回
@@ -173,7 +395,6 @@ impl Scheduler {
     /// * target_toc_range - 选中的知识库范围
     fn build_docs_nodes_for_tree(target_toc_range: &Vec<String>) -> Vec<Vec<TreeNone>> {
         let cached_toc_info = tools::get_cache_books_info();
-        let f = File::new();
 
         if cached_toc_info.is_err() {
             panic!("知识库信息读取失败，程序退出");
@@ -182,55 +403,13 @@ impl Scheduler {
 
             let nodes: Vec<TreeNone> = cached_toc_info
                 .iter_mut()
-                .filter_map(|item| {
-                    if target_toc_range.contains(&item.name) {
-                        let children = item
-                            .docs
-                            .iter()
-                            .map(|child| TreeNone {
-                                children: vec![],
-                                name: "".to_string(),   // 文档级别没有name
-                                user: "".to_string(),   // 在没递归之前是空的
-                                p_slug: "".to_string(), // 在没递归之前是空的
-                                uuid: child.uuid.clone(),
-                                visible: child.visible,
-                                full_path: child.title.to_string(),
-                                parent_id: child.parent_uuid.to_string(),
-                                title: child.title.to_string(),
-                                child_uuid: child.child_uuid.to_string(),
-                                node_type: child.node_type.to_string(), // DOC 或 TITLE
-                                url: child.url.clone(),                 // 只有文档级别有
-                            })
-                            .collect();
-                        // 这一级是知识库级别
-                        Some(TreeNone {
-                            parent_id: "".to_string(),
-                            uuid: "".to_string(),
-                            full_path: "".to_string(),
-                            title: "".to_string(), // 知识库级别没有标题
-                            child_uuid: "".to_string(),
-                            node_type: "".to_string(),
-                            url: "".to_string(),
-                            visible: 1,
-                            p_slug: item.slug.to_string(), // 作为文档上一级slug拼接
-                            name: item.name.clone(),       // 知识库名称
-                            user: item.user_login.to_string(), // 当前文档所属用户
-                            children,
-                        })
-                    } else {
-                        None
-                    }
-                })
+                .filter(|item| target_toc_range.contains(&item.name))
+                .map(|item| Self::book_to_tree_node(item))
                 .collect();
 
             let new_nodes: Vec<_> = nodes
                 .iter()
                 .map(|node| {
-                    // 这里要提前创建知识库顶级目录,makeup_tree_toc_dir是创建知识库下每一层目录
-                    let target_dir = format!("{}/{}", GLOBAL_CONFIG.target_output_dir, node.name);
-                    if let Err(_) = f.mkdir(target_dir.as_str()) {
-                        Log::error("知识库目录创建失败")
-                    }
                     Self::makeup_tree_toc_dir(
                         &node.children,
                         "",
@@ -245,146 +424,700 @@ impl Scheduler {
         }
     }
 
-    /// 定时导出任务
+    /// 将缓存的知识库转换成知识库级别的节点，children是未经递归的扁平文档列表
+    fn book_to_tree_node(item: &BookItem) -> TreeNone {
+        let children = item
+            .docs
+            .iter()
+            .map(|child| TreeNone {
+                children: vec![],
+                name: "".to_string(),   // 文档级别没有name
+                user: "".to_string(),   // 在没递归之前是空的
+                p_slug: "".to_string(), // 在没递归之前是空的
+                uuid: child.uuid.clone(),
+                visible: child.visible,
+                full_path: child.title.to_string(),
+                parent_id: child.parent_uuid.to_string(),
+                title: child.title.to_string(),
+                child_uuid: child.child_uuid.to_string(),
+                node_type: child.node_type.to_string(), // DOC 或 TITLE
+                url: child.url.clone(),                 // 只有文档级别有
+                updated_at: child.updated_at.to_string(),
+                author: child.author.to_string(),
+                last_editor: child.last_editor.to_string(),
+            })
+            .collect();
+        // 这一级是知识库级别
+        TreeNone {
+            parent_id: "".to_string(),
+            uuid: "".to_string(),
+            full_path: "".to_string(),
+            title: "".to_string(), // 知识库级别没有标题
+            child_uuid: "".to_string(),
+            node_type: "".to_string(),
+            url: "".to_string(),
+            updated_at: "".to_string(),
+            author: "".to_string(),
+            last_editor: "".to_string(),
+            visible: 1,
+            p_slug: item.slug.to_string(),     // 作为文档上一级slug拼接
+            name: item.name.clone(),           // 知识库名称
+            user: item.user_login.to_string(), // 当前文档所属用户
+            children,
+        }
+    }
+
+    /// 构造单个知识库的文档树，不涉及任何文件操作
+    pub fn build_book_tree(item: &BookItem) -> Vec<TreeNone> {
+        let node = Self::book_to_tree_node(item);
+        Self::makeup_tree_toc_dir(
+            &node.children,
+            "",
+            node.name.to_owned(),
+            &node.user,
+            &node.p_slug,
+        )
+    }
+
+    /// 生成导出计划，只读取本地文件是否存在，不做任何修改
     /// # Arguments
     /// * download_config - 导出配置
+    /// * templates - 输出路径模板
     /// * flat_docs_list -  扁平文档列表
-    fn delay_download_doc_task(download_config: MutualAnswer, flat_docs_list: Vec<TreeNone>) {
+    /// * hidden_docs_list - 因隐藏被跳过的文档列表
+    /// * changed_docs - 有变化的文档，本地已存在也重新下载
+    fn plan_doc_task(
+        download_config: &MutualAnswer,
+        templates: &OutputTemplates,
+        flat_docs_list: Vec<TreeNone>,
+        hidden_docs_list: Vec<TreeNone>,
+        changed_docs: Option<&HashSet<String>>,
+    ) -> ExportPlan<TreeNone> {
         let f = File::new();
 
         let mut target_doc_list = flat_docs_list.clone();
-
-        // 导出报告文件
-        let report_file_name_ref: String =
-            format!("{}/导出报告.md", &GLOBAL_CONFIG.target_output_dir);
-
-        if cfg!(debug_assertions) {
-            println!("导出任务配置： {:?}", download_config);
-        }
+        let mut target_hidden_list = hidden_docs_list.clone();
 
         // 二次过滤，因为可能只需要导出知识库下某目录的文档
         // 如果配置知识库范围中有反斜杠就认为有二级目录
         let is_have_sub_dir = download_config.toc_range.join("").contains("/");
-
-        let target_toc_range_str = download_config.toc_range.join("|");
-        if cfg!(debug_assertions) {
-            println!("匹配正则：{}", target_toc_range_str)
-        }
-
-        let reg_set = Regex::new(&target_toc_range_str).unwrap();
         if is_have_sub_dir {
+            let toc_range = &download_config.toc_range;
             target_doc_list = flat_docs_list
                 .into_iter()
-                .filter(|item| reg_set.is_match(&item.full_path))
-                .collect::<Vec<TreeNone>>()
-                .to_vec();
+                .filter(|item| filter::in_toc_range(&item.full_path, toc_range))
+                .collect::<Vec<TreeNone>>();
+            target_hidden_list = hidden_docs_list
+                .into_iter()
+                .filter(|item| filter::in_toc_range(&item.full_path, toc_range))
+                .collect::<Vec<TreeNone>>();
         }
 
-        if cfg!(debug_assertions) {
-            let _ = f.write(
-                "./dev/secend_filter_doc.json",
-                serde_json::to_string_pretty(&target_doc_list)
-                    .unwrap()
-                    .to_string(),
+        let mut plan = ExportPlan::new();
+        for item in target_doc_list {
+            let target_save_path = format!(
+                "{}/{}",
+                GLOBAL_CONFIG.target_output_dir,
+                Self::resolve_doc_save_path(&item, download_config, templates)
+            );
+            let is_changed = changed_docs.is_some_and(|docs| docs.contains(&Vault::doc_key(&item)));
+            // 加密导出的文档为同名的 .age 文件
+            let exists = f.exists(&target_save_path)
+                || f.exists(&format!("{}{}", target_save_path, ENCRYPTED_EXT));
+            let action = if download_config.skip && !is_changed && exists {
+                PlanAction::SkipExists
+            } else {
+                PlanAction::Download
+            };
+            plan.push(&item.full_path.clone(), &target_save_path, action, item);
+        }
+        for item in target_hidden_list {
+            let target_save_path = format!(
+                "{}/{}",
+                GLOBAL_CONFIG.target_output_dir,
+                Self::resolve_doc_save_path(&item, download_config, templates)
+            );
+            plan.push(
+                &item.full_path.clone(),
+                &target_save_path,
+                PlanAction::SkipHidden,
+                item,
+            );
+        }
+
+        plan
+    }
+
+    /// 定时导出任务
+    /// # Arguments
+    /// * download_config - 导出配置
+    /// * templates - 输出路径模板
+    /// * plan - 导出计划
+    /// * git - 导出后提交到git仓库，(是否按文档的最后编辑者逐篇提交, 是否删除语雀上已删除的文档)
+    /// * archive - 导出为归档，文档和附件写入归档，不写入输出目录
+    /// * cipher - 加密方式，导出为归档时整体加密，否则逐个文件加密
+    async fn delay_download_doc_task(
+        download_config: MutualAnswer,
+        templates: OutputTemplates,
+        plan: ExportPlan<TreeNone>,
+        git: Option<(bool, bool)>,
+        archive: Option<ExportArchive>,
+        cipher: Option<Cipher>,
+    ) -> Result<(), &'static str> {
+        let f = File::new();
+
+        // 导出报告文件，导出为归档时放在归档文件旁边
+        let report_dir = match &archive {
+            Some(archive) => archive.dir(),
+            None => GLOBAL_CONFIG.target_output_dir.to_string(),
+        };
+        let report_file_name_ref: String = format!("{}/{}", report_dir, t!("report-file"));
+
+        debug!("导出任务配置： {:?}", download_config);
+
+        let target_doc_list: Vec<&TreeNone> = plan.items.iter().map(|item| &item.payload).collect();
+        Log::dump("secend_filter_doc.json", &target_doc_list);
+
+        // 目录在写入文档时按需创建，这里只清理之前导出遗留的空目录
+        if archive.is_none() {
+            for book_name in tools::get_top_level_toc_from_toc_range(&download_config.toc_range) {
+                let target_dir = format!("{}/{}", GLOBAL_CONFIG.target_output_dir, book_name);
+                if f.exists(&target_dir) && f.remove_empty_dirs(&target_dir).is_err() {
+                    Log::warn(t!("pull-clean-dir-failed"))
+                }
+            }
+        }
+
+        if f.mkdir(&report_dir).is_err() {
+            return Err(t!("output-dir-create-failed"));
+        }
+        let _ = f.write(&report_file_name_ref, format!("# {}\n", t!("report-title")));
+
+        // 隐藏文档直接记录到报告中
+        let hidden_items = plan.filter(PlanAction::SkipHidden);
+        if !hidden_items.is_empty() {
+            Log::warn(&t!("pull-hidden-skipped", count = hidden_items.len()));
+            for item in hidden_items {
+                let _ = f.append(
+                    &report_file_name_ref,
+                    format!("- 🙈 Hidden {}\n", &item.source),
+                );
+            }
+        }
+
+        // 输出路径重复的文档不导出，避免相互覆盖
+        let conflict_items = plan.filter(PlanAction::Conflict);
+        if !conflict_items.is_empty() {
+            Log::warn(&t!("pull-conflict-skipped", count = conflict_items.len()));
+            for item in conflict_items {
+                let _ = f.append(
+                    &report_file_name_ref,
+                    format!("- ⚠️ Conflict {} -> {}\n", &item.source, &item.target),
+                );
+            }
+        }
+
+        for item in plan.filter(PlanAction::SkipExists) {
+            let _ = f.append(
+                &report_file_name_ref,
+                format!("- 🌈 Skip {}\n", &item.source),
             );
         }
-        let _ = f.write(&report_file_name_ref, "# 导出报告\n".to_string());
 
         // 文档数量
-        let target_doc_count = target_doc_list.iter().len();
+        let target_doc_count = plan.count(PlanAction::Download);
         // 耗时计算
-        let need_time = target_doc_count * &GLOBAL_CONFIG.duration / 1000;
+        let need_time = plan.estimate_secs(GLOBAL_CONFIG.duration);
+
+        Log::info(&t!(
+            "pull-start",
+            count = target_doc_count,
+            skipped = plan.count(PlanAction::SkipExists),
+            secs = need_time
+        ));
+
+        let output_prefix = format!("{}/", GLOBAL_CONFIG.target_output_dir);
+        let mut vault_context = Self::build_vault_context(&download_config, templates, &plan);
+        if archive.is_none() {
+            vault_context.cipher = cipher.clone();
+        }
+        vault_context.archive = archive.map(|archive| Arc::new(Mutex::new(archive)));
+        let vault_context = Arc::new(vault_context);
 
-        Log::info(
-            &format!(
-                "开始执行导出任务，共 {} 篇文档，预计需要 {} 秒",
-                target_doc_count, need_time
+        // 提交git时用到的文档路径和最后编辑者
+        let plan_paths: HashSet<String> = plan
+            .items
+            .iter()
+            .map(|item| {
+                item.target
+                    .strip_prefix(&output_prefix)
+                    .unwrap_or(&item.target)
+                    .to_string()
+            })
+            .collect();
+        let downloaded_docs: Vec<(String, String)> = plan
+            .items
+            .iter()
+            .filter(|item| item.action == PlanAction::Download)
+            .map(|item| {
+                (
+                    item.target
+                        .strip_prefix(&output_prefix)
+                        .unwrap_or(&item.target)
+                        .to_string(),
+                    item.payload.last_editor.to_string(),
+                )
+            })
+            .collect();
+
+        // 每个知识库一个进度条
+        let mut progress_groups: Vec<(String, u64)> = vec![];
+        for item in &plan.items {
+            let book_name = Self::book_name_of(&item.source);
+            if !progress_groups.iter().any(|(name, _)| name == &book_name) {
+                progress_groups.push((book_name.to_string(), 0));
+            }
+            if item.action == PlanAction::Download {
+                if let Some(group) = progress_groups
+                    .iter_mut()
+                    .find(|(name, _)| name == &book_name)
+                {
+                    group.1 += 1;
+                }
+            }
+        }
+        let progress = Arc::new(Progress::new(t!("progress-docs"), progress_groups));
+
+        let mut handles = vec![];
+        for item in plan.items {
+            if item.action != PlanAction::Download {
+                progress.skip(&Self::book_name_of(&item.source));
+                continue;
+            }
+            // 每篇文档的日志都带上所在的知识库和路径
+            let span = tracing::info_span!(
+                "doc",
+                book = %item.payload.p_slug,
+                path = %item.payload.full_path
+            );
+            let full_path = item.payload.full_path.to_string();
+            let handle = tokio::spawn(
+                Self::get_and_save_content(
+                    item.payload,
+                    item.target,
+                    download_config.clone(),
+                    report_file_name_ref.clone(),
+                    vault_context.clone(),
+                    progress.clone(),
+                )
+                .instrument(span),
+            );
+            handles.push((full_path, handle));
+
+            tokio::time::sleep(Duration::from_millis(GLOBAL_CONFIG.duration as u64)).await;
+        }
+
+        // 等待还在进行中的导出任务，报告和索引才是完整的，中途panic的任务记为失败
+        for (full_path, handle) in handles {
+            if let Err(err) = handle.await {
+                debug!(path = %full_path, "导出任务异常结束：{}", err);
+                Self::record_failed_doc(&full_path, &report_file_name_ref, &progress);
+            }
+        }
+
+        progress.finish_all();
+
+        // 所有文档写完后再写入清单，结束归档
+        if let Some(archive) = &vault_context.archive {
+            match archive.lock().unwrap().finish() {
+                Ok(files) => Log::success(&t!("pull-archive-done", files = files.join(", "))),
+                Err(err) => Log::error(err),
+            }
+        }
+
+        // 报告中有文档路径，加密导出时一并加密
+        if let Some(cipher) = &cipher {
+            if let Err(err) = cipher.encrypt_file(&report_file_name_ref) {
+                Log::error(err);
+            }
+        }
+
+        // 加密导出时没有明文可以索引
+        if cipher.is_none() && vault_context.archive.is_none() {
+            Self::refresh_search_index();
+        }
+
+        if let Some((by_editor, prune_stale)) = git {
+            let stale_scope = if prune_stale {
+                Some(tools::get_top_level_toc_from_toc_range(
+                    &download_config.toc_range,
+                ))
+            } else {
+                None
+            };
+            if let Err(err) = Self::commit_export_run(
+                &download_config,
+                &plan_paths,
+                &downloaded_docs,
+                by_editor,
+                stale_scope,
+            ) {
+                Log::error(&t!("pull-git-failed", reason = err));
+            }
+        }
+
+        let star_link = Link::new(t!("star-link"), "https://github.com/vannvan/rust-explore");
+        Log::success(&t!("pull-done", count = target_doc_count, link = star_link));
+        Ok(())
+    }
+
+    /// 只重新导出指定的文档，返回导出成功的文档数量，用于webhook
+    /// 不清理目录、不重写导出报告，其余文档仍然参与导出计划，保证路径和文档间链接与完整导出一致
+    /// # Arguments
+    /// * doc_keys - 需要导出的文档，知识库slug/文档slug
+    pub async fn export_docs(doc_keys: &HashSet<String>) -> Result<u64, &'static str> {
+        let mut answer = Self::config_answer(&PullOptions::default());
+        answer.skip = true;
+        let (matcher, templates) = Self::compile_matcher_and_templates(&answer)?;
+
+        let toc_range = tools::get_top_level_toc_from_toc_range(&answer.toc_range);
+        let mut new_nodes = Self::build_docs_nodes_for_tree(&toc_range);
+        if !matcher.is_empty() {
+            new_nodes = Self::apply_doc_filter(new_nodes, &matcher);
+        }
+        let flat_docs_list = Self::filter_valid_docs_to_flat(&new_nodes, &answer);
+        let plan = Self::plan_doc_task(&answer, &templates, flat_docs_list, vec![], Some(doc_keys));
+
+        let cipher = Self::resolve_cipher(&PullOptions::default())?;
+        let mut vault_context = Self::build_vault_context(&answer, templates, &plan);
+        vault_context.cipher = cipher.clone();
+        let vault_context = Arc::new(vault_context);
+
+        let report_file_name = format!("{}/{}", GLOBAL_CONFIG.target_output_dir, t!("report-file"));
+        let items: Vec<_> = plan
+            .items
+            .into_iter()
+            .filter(|item| {
+                item.action == PlanAction::Download
+                    && doc_keys.contains(&Vault::doc_key(&item.payload))
+            })
+            .collect();
+        let mut progress_groups: Vec<(String, u64)> = vec![];
+        for item in &items {
+            let book_name = Self::book_name_of(&item.source);
+            match progress_groups
+                .iter_mut()
+                .find(|(name, _)| name == &book_name)
+            {
+                Some(group) => group.1 += 1,
+                None => progress_groups.push((book_name, 1)),
+            }
+        }
+        let progress = Arc::new(Progress::new(t!("progress-docs"), progress_groups));
+
+        for (index, item) in items.into_iter().enumerate() {
+            if index > 0 {
+                tokio::time::sleep(Duration::from_millis(GLOBAL_CONFIG.duration as u64)).await;
+            }
+            let span = tracing::info_span!(
+                "doc",
+                book = %item.payload.p_slug,
+                path = %item.payload.full_path
+            );
+            Self::get_and_save_content(
+                item.payload,
+                item.target,
+                answer.clone(),
+                report_file_name.clone(),
+                vault_context.clone(),
+                progress.clone(),
             )
-            .to_string(),
-        );
+            .instrument(span)
+            .await;
+        }
 
-        init_progress_bar(target_doc_count);
-        set_progress_bar_action("Loading", Color::Blue, Style::Bold);
+        let stats = progress.finish_all();
+        if cipher.is_none() {
+            Self::refresh_search_index();
+        }
+        Ok(stats.iter().map(|stat| stat.success).sum())
+    }
 
-        for item in target_doc_list {
-            tokio::spawn(Self::get_and_save_content(
-                item,
-                download_config.clone(),
-                report_file_name_ref.clone(),
-            ));
+    /// 文档转换用到的上下文
+    /// Obsidian/Logseq需要知道所有导出文档的位置，才能把文档间链接转为wikilink
+    fn build_vault_context(
+        download_config: &MutualAnswer,
+        templates: OutputTemplates,
+        plan: &ExportPlan<TreeNone>,
+    ) -> VaultContext {
+        let output_prefix = format!("{}/", GLOBAL_CONFIG.target_output_dir);
+        let vault_docs = plan
+            .items
+            .iter()
+            .filter(|item| matches!(item.action, PlanAction::Download | PlanAction::SkipExists))
+            .map(|item| {
+                let save_path = item
+                    .target
+                    .strip_prefix(&output_prefix)
+                    .unwrap_or(&item.target);
+                (&item.payload, save_path.to_string())
+            })
+            .collect::<Vec<_>>();
+        Vault::build_context(
+            download_config.target,
+            &GLOBAL_CONFIG.target_output_dir,
+            &download_config.attachment_dir,
+            vault_docs,
+            &Request::get_match_host(),
+            templates,
+        )
+    }
 
-            sleep(Duration::from_millis(
-                GLOBAL_CONFIG.duration.try_into().unwrap(),
-            ));
-            inc_progress_bar();
+    /// 建立过全文索引的话，顺带增量更新
+    fn refresh_search_index() {
+        if !File::new().exists(&GLOBAL_CONFIG.search_index_file) {
+            return;
+        }
+        match SearchIndex::refresh(
+            &GLOBAL_CONFIG.search_index_file,
+            &GLOBAL_CONFIG.target_output_dir,
+            false,
+        ) {
+            Ok(summary) => {
+                debug!("索引更新：{:?}", summary);
+            }
+            Err(_) => Log::warn(t!("pull-index-failed")),
+        }
+    }
+
+    /// 把本次导出的变更提交到输出目录的git仓库
+    /// # Arguments
+    /// * download_config - 导出配置
+    /// * plan_paths - 导出计划中所有文档的保存路径，相对于输出目录
+    /// * downloaded_docs - 本次下载的文档路径和最后编辑者
+    /// * by_editor - 每篇文档单独提交，作者为最后编辑者
+    /// * stale_scope - 需要清理已删除文档的知识库，为空时不清理
+    fn commit_export_run(
+        download_config: &MutualAnswer,
+        plan_paths: &HashSet<String>,
+        downloaded_docs: &Vec<(String, String)>,
+        by_editor: bool,
+        stale_scope: Option<Vec<String>>,
+    ) -> Result<(), &'static str> {
+        let f = File::new();
+        let repo = GitArchive::open(&GLOBAL_CONFIG.target_output_dir)?;
+
+        // 语雀上已删除的文档，本地也删除，提交中记为删除
+        if let Some(books) = stale_scope {
+            let is_in_books = |path: &str| {
+                books.iter().any(|book| match download_config.target {
+                    ExportTarget::Logseq => {
+                        let page = Vault::logseq_page_file(book);
+                        path == page
+                            || path.starts_with(&format!("{}___", page.trim_end_matches(".md")))
+                    }
+                    _ => path.starts_with(&format!("{}/", book)),
+                })
+            };
+            let stale_docs: Vec<String> = repo
+                .tracked_files()?
+                .into_iter()
+                .filter(|path| {
+                    path.ends_with(".md") && is_in_books(path) && !plan_paths.contains(path)
+                })
+                .collect();
+            for path in &stale_docs {
+                let _ = f.remove(&format!("{}/{}", GLOBAL_CONFIG.target_output_dir, path));
+            }
+            if !stale_docs.is_empty() {
+                Log::info(&t!("pull-stale-docs", count = stale_docs.len()));
+            }
+        }
+
+        let mut doc_commits = 0;
+        if by_editor {
+            let mut missing_editor = 0;
+            for (path, editor) in downloaded_docs {
+                if editor.is_empty() {
+                    missing_editor += 1;
+                    continue;
+                }
+                repo.stage(&[path.to_string()])?;
+                let changes = repo.staged_changes()?;
+                if changes.is_empty() {
+                    continue;
+                }
+                let doc = path.trim_end_matches(".md");
+                let message = if changes.added.is_empty() {
+                    t!("git-commit-doc-updated", path = doc)
+                } else {
+                    t!("git-commit-doc-added", path = doc)
+                };
+                repo.commit(&message, Some(editor))?;
+                doc_commits += 1;
+            }
+            if missing_editor > 0 {
+                Log::warn(&t!("pull-missing-editor", count = missing_editor));
+            }
         }
 
-        finalize_progress_bar();
-        let star_link = Link::new("去点个⭐️吧", "https://github.com/vannvan/rust-explore");
-        Log::success(&format!(
-            "导出任务执行完毕，共导出{}个文档，{}",
-            target_doc_count, star_link
+        // 其余变更(附件、分组索引、删除的文档等)汇总成一次提交
+        repo.stage(&[])?;
+        let changes = repo.staged_changes()?;
+        if changes.is_empty() {
+            if doc_commits > 0 {
+                Log::success(&t!("pull-git-doc-commits", count = doc_commits));
+            } else {
+                Log::info(t!("pull-git-no-changes"));
+            }
+            return Ok(());
+        }
+        repo.commit(&changes.summary(), None)?;
+        Log::success(&t!(
+            "pull-git-committed",
+            summary = changes.summary().lines().next().unwrap_or_default()
         ));
+        Ok(())
     }
 
     /// 获取内容并保存文件
     async fn get_and_save_content(
         item: TreeNone,
+        target_save_path: String,
         download_config: MutualAnswer,
         report_file_name: String,
+        vault_context: Arc<VaultContext>,
+        progress: Arc<Progress>,
     ) {
         let f = File::new();
-
-        // 本地保存路径
-        let target_save_path =
-            format!("{}/{}.md", GLOBAL_CONFIG.target_output_dir, &item.full_path);
+        let book_name = Self::book_name_of(&item.full_path);
+        progress.start(&book_name, &item.title);
+
+        // 目录按需创建，避免子文档被过滤后留下空目录
+        if vault_context.archive.is_none() {
+            if let Some(parent_dir) = std::path::Path::new(&target_save_path).parent() {
+                if f.mkdir(&parent_dir.to_string_lossy()).is_err() {
+                    Log::error(t!("pull-book-dir-failed"))
+                }
+            }
+        }
 
         // yuque的文档地址
         let target_doc_url = format!("/{}/{}/{}", item.user, item.p_slug, item.url);
-
-        if let Ok(content) =
-            YuqueApi::get_markdown_content(&target_doc_url, download_config.line_break).await
-        {
-            if f.exists(&target_save_path) && download_config.skip {
-                print_progress_bar_info("Skip", &item.full_path, Color::Cyan, Style::Normal);
-                let _ = f.append(
-                    &report_file_name,
-                    format!("- 🌈 Skip {}\n", &item.full_path).to_string(),
-                );
-            } else {
-                print_progress_bar_info("Success", &item.full_path, Color::Green, Style::Bold);
-                // 写入文件
-                let _ = f.write(&target_save_path, content.to_string());
-                let _ = f.append(
-                    &report_file_name,
-                    format!("- 🌈 Success {}\n", &item.full_path).to_string(),
-                );
+        debug!(url = %target_doc_url, save_path = %target_save_path, "开始导出");
+
+        // TITLE分组没有正文，生成一份下级文档的索引
+        let content = if item.node_type == "TITLE" {
+            match download_config.target {
+                ExportTarget::Markdown => Ok(Self::gen_group_index_content(
+                    &item,
+                    &download_config,
+                    &vault_context.templates,
+                )),
+                _ => Ok(Vault::group_index(&item, &vault_context)),
             }
         } else {
-            print_progress_bar_info("Failed", &item.full_path, Color::Red, Style::Normal);
+            match YuqueApi::get_markdown_content(&target_doc_url, download_config.line_break).await
+            {
+                Ok(content) if download_config.target != ExportTarget::Markdown => {
+                    let content = Vault::save_attachments(&content, &item, &vault_context).await;
+                    Ok(Vault::convert(&content, &item, &vault_context))
+                }
+                other => other,
+            }
+        };
+
+        if let Ok(content) = content {
+            progress.finish(&book_name, &item.full_path, Some(content.len() as u64));
+            // 写入文件，导出为归档时写入归档
+            match &vault_context.archive {
+                Some(archive) => {
+                    let output_prefix = format!("{}/", GLOBAL_CONFIG.target_output_dir);
+                    let archive_path = target_save_path
+                        .strip_prefix(&output_prefix)
+                        .unwrap_or(&target_save_path);
+                    if let Err(err) = archive.lock().unwrap().add(
+                        &book_name,
+                        archive_path,
+                        content.as_bytes(),
+                        &item.uuid,
+                        &item.updated_at,
+                    ) {
+                        Log::error(err);
+                    }
+                }
+                None => match &vault_context.cipher {
+                    Some(cipher) => {
+                        if let Err(err) = cipher.write_file(&target_save_path, content.as_bytes()) {
+                            Log::error(err);
+                        }
+                    }
+                    None => {
+                        let _ = f.write(&target_save_path, content.to_string());
+                    }
+                },
+            }
             let _ = f.append(
                 &report_file_name,
-                format!("- ❌ Failed {}\n", &item.full_path).to_string(),
+                format!("- 🌈 Success {}\n", &item.full_path).to_string(),
             );
+        } else {
+            debug!("文档内容获取失败");
+            Self::record_failed_doc(&item.full_path, &report_file_name, &progress);
         }
     }
 
+    /// 记录导出失败的文档，结束它的进度并写入报告
+    fn record_failed_doc(full_path: &str, report_file_name: &str, progress: &Progress) {
+        progress.finish(&Self::book_name_of(full_path), full_path, None);
+        Log::warn(&t!("pull-doc-failed", path = full_path));
+        let _ = File::new().append(report_file_name, format!("- ❌ Failed {}\n", full_path));
+    }
+
+    /// 路径所在的知识库，用于按知识库显示进度
+    fn book_name_of(full_path: &str) -> String {
+        full_path.split('/').next().unwrap_or_default().to_string()
+    }
+
     /// 从树形列表中拿到有效的文档列表，并以扁平结构返回
     /// # Arguments
     /// * tree - 树形列表
-    fn filter_valid_docs_to_flat(tree: &Vec<Vec<TreeNone>>) -> Vec<TreeNone> {
+    /// * config - 导出配置，决定隐藏文档和TITLE分组是否输出
+    fn filter_valid_docs_to_flat(tree: &[Vec<TreeNone>], config: &MutualAnswer) -> Vec<TreeNone> {
+        Self::collect_nodes_to_flat(tree, &|node| Self::is_exportable_node(node, config))
+    }
+
+    /// 从树形列表中拿到因隐藏而不导出的文档列表，并以扁平结构返回
+    /// # Arguments
+    /// * tree - 树形列表
+    /// * config - 导出配置
+    fn filter_hidden_docs_to_flat(tree: &[Vec<TreeNone>], config: &MutualAnswer) -> Vec<TreeNone> {
+        Self::collect_nodes_to_flat(tree, &|node| {
+            node.node_type == "DOC" && !Self::is_visible_node(node, config)
+        })
+    }
+
+    /// 按条件从树形列表中收集节点，并以扁平结构返回
+    fn collect_nodes_to_flat(
+        tree: &[Vec<TreeNone>],
+        matcher: &dyn Fn(&TreeNone) -> bool,
+    ) -> Vec<TreeNone> {
         let list: RefCell<Vec<TreeNone>> = RefCell::new(vec![]);
 
-        fn each(list: &RefCell<Vec<TreeNone>>, docs: &Vec<TreeNone>) {
+        fn each(
+            list: &RefCell<Vec<TreeNone>>,
+            docs: &[TreeNone],
+            matcher: &dyn Fn(&TreeNone) -> bool,
+        ) {
             if !docs.is_empty() {
                 docs.iter().for_each(|doc| {
-                    if doc.node_type == "DOC" && doc.visible == 1 {
+                    if matcher(doc) {
                         let cloned_doc = doc.clone();
                         list.borrow_mut().push(cloned_doc);
                     }
 
                     if !doc.children.is_empty() {
-                        each(list, &doc.children);
+                        each(list, &doc.children, matcher);
                     }
                 });
             }
@@ -392,17 +1125,93 @@ impl Scheduler {
 
         tree.iter().for_each(|item| {
             item.iter().for_each(|sub_item| {
-                if sub_item.node_type == "DOC" && sub_item.visible == 1 {
+                if matcher(sub_item) {
                     list.borrow_mut().push(sub_item.clone());
                 }
-                each(&list, &sub_item.children);
+                each(&list, &sub_item.children, matcher);
             });
         });
 
         list.take()
     }
 
-    /// 递归创建树形目录，顺便将文档路径拼接完成，同时记录文档对应父级slug和user，用于最终download环节
+    /// 导出规则，与GUI端共用，GUI端不生成分组索引，相当于Sibling方式
+    fn export_policy(config: &MutualAnswer) -> ExportPolicy {
+        ExportPolicy {
+            include_hidden: config.include_hidden,
+            group_index: config.layout != DocLayout::Sibling,
+        }
+    }
+
+    /// 节点是否可见，visible为0的隐藏/草稿文档只在include_hidden时导出
+    fn is_visible_node(node: &TreeNone, config: &MutualAnswer) -> bool {
+        Self::export_policy(config).is_visible(node.visible)
+    }
+
+    /// 节点是否需要输出文件，TITLE分组只在有可导出的子文档且不是Sibling方式时输出
+    fn is_exportable_node(node: &TreeNone, config: &MutualAnswer) -> bool {
+        Self::export_policy(config).is_exportable(&node.node_type, node.visible, || {
+            Self::has_exportable_children(node, config)
+        })
+    }
+
+    /// 是否有需要导出的子文档，用于判断是否需要目录
+    fn has_exportable_children(node: &TreeNone, config: &MutualAnswer) -> bool {
+        node.children.iter().any(|child| {
+            Self::is_visible_node(child, config)
+                && (child.node_type == "DOC" || Self::has_exportable_children(child, config))
+        })
+    }
+
+    /// 文档相对于输出目录的保存路径
+    /// # Arguments
+    /// * item - 文档节点
+    /// * config - 导出配置，layout决定含子文档的文档输出方式
+    /// * templates - 输出路径模板，配置了文档模板时layout不影响路径
+    fn resolve_doc_save_path(
+        item: &TreeNone,
+        config: &MutualAnswer,
+        templates: &OutputTemplates,
+    ) -> String {
+        // Logseq的页面都在pages目录下，层级体现在文件名中
+        if config.target == ExportTarget::Logseq {
+            return Vault::logseq_page_file(&item.full_path);
+        }
+        if let Some(path) = templates.doc_path(item) {
+            return path;
+        }
+        if !Self::has_exportable_children(item, config) {
+            return format!("{}.md", item.full_path);
+        }
+        match config.layout {
+            DocLayout::Sibling => format!("{}.md", item.full_path),
+            DocLayout::Index => format!("{}/index.md", item.full_path),
+            DocLayout::Readme => format!("{}/README.md", item.full_path),
+        }
+    }
+
+    /// 生成TITLE分组的索引内容，按顺序列出下一级文档的相对链接
+    fn gen_group_index_content(
+        item: &TreeNone,
+        config: &MutualAnswer,
+        templates: &OutputTemplates,
+    ) -> String {
+        let mut content = format!("# {}\n\n", item.title);
+        let save_path = Self::resolve_doc_save_path(item, config, templates);
+
+        for child in item.children.iter().filter(|child| {
+            Self::is_visible_node(child, config)
+                && (child.node_type == "DOC" || Self::has_exportable_children(child, config))
+        }) {
+            let child_path = Self::resolve_doc_save_path(child, config, templates);
+            let relative_path = template::relative_path(&save_path, &child_path);
+            content.push_str(&format!("- [{}](<{}>)\n", child.title, relative_path));
+        }
+
+        content
+    }
+    /// 递归构造树形结构，顺便将文档路径拼接完成，同时记录文档对应父级slug和user，用于最终download环节
+    /// 目录不在这里创建，写入文档时按需创建
     /// # Arguments
     /// * items - 递归下一级
     /// * uuid - 用于匹配下一级的uuid
@@ -416,26 +1225,16 @@ impl Scheduler {
         p_user: &str,
         p_slug: &str,
     ) -> Vec<TreeNone> {
-        let f = File::new();
         items
             .iter()
             .filter(|item| item.parent_id == uuid)
             .map(|item| {
                 // 替换名称中的特殊字符
-                let regex = Regex::new(r#"[<>:"\/\\|?*\x00-\x1F]"#).unwrap();
-                let full_path = format!("{}/{}", prev_path, regex.replace_all(&item.title, ""));
-
-                // 目标路径
-                let target_dir = format!("{}/{}", GLOBAL_CONFIG.target_output_dir, full_path);
-                // 打印路径
-                // if cfg!(debug_assertions) {
-                //     println!("目标路径: {}", target_dir);
-                // }
-                if item.node_type == "TITLE" || !item.child_uuid.is_empty() {
-                    if let Err(_) = f.mkdir(target_dir.as_str()) {
-                        Log::error("知识库目录创建失败")
-                    }
-                }
+                let full_path = format!(
+                    "{}/{}",
+                    prev_path,
+                    tools::sanitize_file_name(&item.title, "")
+                );
 
                 // 当前层
                 let current_item = TreeNone {
@@ -447,6 +1246,9 @@ impl Scheduler {
                     child_uuid: item.child_uuid.clone(),
                     visible: item.visible.clone(),
                     url: item.url.clone(),
+                    updated_at: item.updated_at.clone(),
+                    author: item.author.clone(),
+                    last_editor: item.last_editor.clone(),
                     // 之后是来自上一级的信息
                     full_path: full_path.to_string(),
                     p_slug: p_slug.to_string(),
@@ -463,24 +1265,17 @@ impl Scheduler {
     }
 
     /// 团队支持库下载启动程序
-    pub async fn start_grd() -> Result<(), bool> {
-        Log::info("团队资源下载程序开始");
-
-        let f = File::new();
-
-        if let Err(_) = f.mkdir(&GLOBAL_CONFIG.target_resource_dir) {
-            Log::error("资源目录创建失败");
-            process::exit(1)
-        }
+    /// # Arguments
+    /// * dry_run - 只输出下载计划，不修改任何本地文件
+    pub async fn start_grd(dry_run: bool) -> Result<(), bool> {
+        Log::info(t!("grd-start"));
 
         let cookies = tools::get_local_cookies();
 
         match tools::get_user_config() {
             Ok(user_config) => {
                 if cookies.is_empty() {
-                    if cfg!(debug_assertions) {
-                        println!("user_config: {:?}", user_config);
-                    }
+                    debug!("user_config: {:?}", user_config);
                     // 尝试默认使用配置中的账号信息
                     let account = YuqueAccount {
                         username: user_config.username.to_string(),
@@ -489,23 +1284,21 @@ impl Scheduler {
 
                     match YuqueApi::login(&account.username, &account.password).await {
                         Ok(_resp) => {
-                            Log::success("登录成功!");
+                            Log::success(t!("login-success"));
                             // 接着就开始资源
-                            Self::get_group_resource_base_info().await
+                            Self::get_group_resource_base_info(dry_run).await
                         }
                         Err(_err) => {
-                            Log::error("登录失败，请检查账号信息是否正确或重试");
+                            Log::error(t!("login-failed"));
                             process::exit(1)
                         }
                     }
                 } else {
-                    Self::get_group_resource_base_info().await
+                    Self::get_group_resource_base_info(dry_run).await
                 }
             }
             Err(_err) => {
-                if cfg!(debug_assertions) {
-                    println!("没有配置文件开始问询");
-                }
+                debug!("没有配置文件开始问询");
             }
         }
 
@@ -513,14 +1306,24 @@ impl Scheduler {
     }
 
     /// 获取团队资源基础信息
-    async fn get_group_resource_base_info() {
-        Log::info("开始获取团队资源信息，请确保资源目录和文件名称不包含特殊字符哦～");
+    async fn get_group_resource_base_info(dry_run: bool) {
+        Log::info(t!("grd-fetching"));
 
         match tools::get_user_config() {
             Ok(user_config) => {
                 if !user_config.host.is_empty() {
+                    // 模板在获取资源列表之前校验
+                    let today = Local::now().format("%Y-%m-%d").to_string();
+                    let templates =
+                        match OutputTemplates::compile(&user_config.path_template, &today) {
+                            Ok(templates) => templates,
+                            Err(err) => {
+                                Log::error(&err);
+                                return;
+                            }
+                        };
                     if let Ok(source_info) = YuqueApi::get_group_resource_base_info().await {
-                        Log::info("获取团队资源基础信息成功");
+                        Log::info(t!("grd-base-info-success"));
                         // 所有资源的扁平列表
                         let mut all_resource_flat_list: Vec<ResourceItem> = vec![];
 
@@ -544,71 +1347,158 @@ impl Scheduler {
                                 .await
                             }
                         }
+                        let plan = Self::plan_resource_task(
+                            &all_resource_flat_list,
+                            user_config.skip,
+                            &templates,
+                        );
+                        // 使用模板时不允许两个资源保存到同一路径
+                        let conflict_items = plan.filter(PlanAction::Conflict);
+                        if templates.has_resource_template() && !conflict_items.is_empty() {
+                            for item in conflict_items {
+                                Log::error(&t!(
+                                    "template-conflict",
+                                    source = item.source,
+                                    target = item.target
+                                ));
+                            }
+                            Log::error(&t!(
+                                "template-conflict-abort",
+                                count = plan.count(PlanAction::Conflict)
+                            ));
+                            return;
+                        }
+                        if dry_run {
+                            Log::info(t!("grd-dry-run"));
+                            plan.print(GLOBAL_CONFIG.duration);
+                            return;
+                        }
                         // 正式开始下载
-                        Self::download_all_resource_task(&all_resource_flat_list).await;
+                        Self::download_all_resource_task(plan).await;
                     }
                 } else {
-                    Log::error("请配置团队空间域名")
+                    Log::error(t!("grd-host-missing"))
                 }
             }
-            Err(_) => Log::error("请配置团队空间域名"),
+            Err(_) => Log::error(t!("grd-host-missing")),
         }
     }
 
-    /// 正式下载所有资源的任务
-    async fn download_all_resource_task(all_resource_list: &Vec<ResourceItem>) {
-        let skip = tools::get_user_config()
-            .map(|user_config| user_config.skip)
-            .unwrap_or_default();
+    /// 生成资源下载计划，只读取本地文件是否存在，不做任何修改
+    /// # Arguments
+    /// * all_resource_list - 所有资源
+    /// * skip - 是否跳过本地已存在的资源
+    /// * templates - 输出路径模板，配置了资源模板时按模板生成保存路径
+    fn plan_resource_task(
+        all_resource_list: &Vec<ResourceItem>,
+        skip: bool,
+        templates: &OutputTemplates,
+    ) -> ExportPlan<ResourceItem> {
+        let f = File::new();
+        let mut plan = ExportPlan::new();
+
+        for item in all_resource_list {
+            let local_file_full_name = &templates
+                .resource_path(&item.folder, &item.file_name, &item.ext)
+                .map(|path| format!("{}/{}", GLOBAL_CONFIG.target_resource_dir, path))
+                .unwrap_or(item.target_save_full_path_name.to_string());
+            let action = if skip && f.exists(local_file_full_name) {
+                PlanAction::SkipExists
+            } else {
+                PlanAction::Download
+            };
+            plan.push(
+                &item.source_id,
+                local_file_full_name,
+                action,
+                ResourceItem {
+                    target_save_full_path_name: local_file_full_name.to_string(),
+                    source_id: item.source_id.to_string(),
+                    folder: item.folder.to_string(),
+                    file_name: item.file_name.to_string(),
+                    ext: item.ext.to_string(),
+                },
+            );
+        }
+
+        plan
+    }
 
+    /// 正式下载所有资源的任务
+    /// # Arguments
+    /// * plan - 资源下载计划
+    async fn download_all_resource_task(plan: ExportPlan<ResourceItem>) {
         let f = File::new();
 
-        let m = MultiProgress::new();
-        let sty = ProgressStyle::with_template(
-            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
-        )
-        .unwrap()
-        .progress_chars("##-");
+        if f.mkdir(&GLOBAL_CONFIG.target_resource_dir).is_err() {
+            Log::error(t!("grd-dir-failed"));
+            process::exit(1)
+        }
 
-        let pb = m.add(ProgressBar::new(
-            all_resource_list.len().try_into().unwrap(),
-        ));
-        pb.set_style(sty.clone());
-        // pb.set_message("正在下载，请耐心等待...");
+        // 每个资源库一个进度条
+        let resource_prefix = format!("{}/", GLOBAL_CONFIG.target_resource_dir);
+        let group_of = |target: &str| {
+            Self::book_name_of(target.strip_prefix(&resource_prefix).unwrap_or(target))
+        };
+        let mut progress_groups: Vec<(String, u64)> = vec![];
+        for item in &plan.items {
+            let group = group_of(&item.target);
+            if !progress_groups.iter().any(|(name, _)| name == &group) {
+                progress_groups.push((group.to_string(), 0));
+            }
+            if item.action == PlanAction::Download {
+                if let Some(entry) = progress_groups.iter_mut().find(|(name, _)| name == &group) {
+                    entry.1 += 1;
+                }
+            }
+        }
+        let progress = Progress::new(t!("progress-resources"), progress_groups);
+
+        for item in &plan.items {
+            let local_file_full_name = &item.target;
+            let resource_id = &item.payload.source_id;
+            let group = group_of(local_file_full_name);
+            match item.action {
+                PlanAction::SkipExists => {
+                    debug!("{} 跳过", &local_file_full_name);
+                    progress.skip(&group);
+                }
+                PlanAction::Conflict => {
+                    Log::warn(&t!("grd-conflict", file = local_file_full_name));
+                    progress.skip(&group);
+                }
+                _ => {
+                    progress.start(&group, local_file_full_name);
+                    // 资源目录按需创建
+                    if let Some(parent_dir) = std::path::Path::new(local_file_full_name).parent() {
+                        if f.mkdir(&parent_dir.to_string_lossy()).is_err() {
+                            Log::error(&t!(
+                                "grd-sub-dir-failed",
+                                dir = parent_dir.to_string_lossy()
+                            ));
+                            process::exit(1)
+                        }
+                    }
 
-        for item in all_resource_list {
-            let local_file_full_name = &item.target_save_full_path_name;
-            let resource_id = &item.source_id;
-            pb.set_message(format!("{}", local_file_full_name).to_string());
-            if f.exists(&local_file_full_name) && skip {
-                Log::info(&format!("{} 跳过", &local_file_full_name).to_string());
-                pb.inc(1);
-            } else {
-                let url = format!("/r/resources/download/{}", resource_id);
+                    let url = format!("/r/resources/download/{}", resource_id);
 
-                if let Ok(_res) = Request::download(&url, &local_file_full_name).await {
-                    // Log::success(&format!("{} 下载成功", local_file_full_name).to_string());
-                    pb.inc(1);
-                } else {
-                    Log::error(
-                        &format!(
-                            "{} 下载失败，请确认资源名称是否有特殊字符",
-                            local_file_full_name
-                        )
-                        .to_string(),
-                    );
-                    pb.inc(1);
-                    // process::exit(1)
+                    match Request::download(&url, local_file_full_name).await {
+                        Ok(bytes) => progress.finish(&group, local_file_full_name, Some(bytes)),
+                        Err(_) => {
+                            progress.finish(&group, local_file_full_name, None);
+                            Log::error(&t!("grd-download-failed", file = local_file_full_name));
+                            // process::exit(1)
+                        }
+                    }
                 }
             }
         }
-        pb.finish_with_message("下载完毕");
-        m.clear().unwrap();
-        let star_link = Link::new("去点个⭐️吧", "https://github.com/vannvan/rust-explore");
-        Log::success(&format!(
-            "团队资源下载任务完成，共下载{}个文件~，{}",
-            all_resource_list.len(),
-            star_link
+        progress.finish_all();
+        let star_link = Link::new(t!("star-link"), "https://github.com/vannvan/rust-explore");
+        Log::success(&t!(
+            "grd-done",
+            count = plan.count(PlanAction::Download),
+            link = star_link
         ));
         // println!("{}", link)
     }
@@ -620,53 +1510,38 @@ impl Scheduler {
         resource_base_id: Value,
         resource_list: &mut Vec<ResourceItem>,
     ) {
-        Log::info(format!("【{}】资源信息开始获取", title.to_string()).as_str());
+        Log::info(&t!("grd-detail-fetching", title = title));
         if let Ok(source_info) =
             YuqueApi::get_group_resource_detail_list(&resource_base_id.to_string()).await
         {
-            Log::success(format!("【{}】资源信息获取成功", title).as_str());
-            if cfg!(debug_assertions) {
-                // println!("资源详情：{:?}", source_info);
-            }
+            Log::success(&t!("grd-detail-success", title = title));
+            trace!("资源详情：{:?}", source_info);
 
             if source_info.as_array().unwrap().len() > 0 {
                 for item in source_info.as_array().unwrap() {
                     if item.get("type").unwrap().as_str().eq(&Some("Resource")) {
-                        Log::info(&format!(
-                            "【{}】资源列表开始获取",
-                            item.get("name").unwrap().to_string()
-                        ));
+                        Log::info(&t!("grd-list-fetching", name = item.get("name").unwrap()));
                         let id = item.get("id").unwrap();
+                        // 名称不带引号，否则会出现在本地目录名中
                         if let Ok(_list) = YuqueApi::get_group_resource_list(
                             &id.to_string(),
                             "",
                             &0,
-                            &item.get("name").unwrap().to_string(),
+                            item.get("name").unwrap().as_str().unwrap_or_default(),
                             resource_list,
                         )
+                        .instrument(tracing::info_span!("resource", id = %id))
                         .await
                         {
-                            Log::success(
-                                &format!(
-                                    "【{}】资源列表获取成功",
-                                    item.get("name").unwrap().to_string()
-                                )
-                                .to_string(),
-                            )
+                            Log::success(&t!("grd-list-success", name = item.get("name").unwrap()))
                         } else {
-                            Log::error(
-                                format!(
-                                    "【{}】资源列表获取失败",
-                                    item.get("name").unwrap().to_string()
-                                )
-                                .as_str(),
-                            );
+                            Log::error(&t!("grd-list-failed", name = item.get("name").unwrap()));
                         }
                     }
                 }
             }
         } else {
-            Log::error(format!("【{}】资源信息获取失败", title).as_str());
+            Log::error(&t!("grd-detail-failed", title = title));
         }
     }
 
@@ -681,37 +1556,316 @@ impl Scheduler {
 #[allow(non_snake_case)]
 mod tests {
     use super::*;
+    use crate::libs::fixtures;
     #[test]
+    #[ignore = "依赖本地缓存的知识库信息，缓存不存在时直接panic"]
     fn test_build_docs_nodes_for_tree() {
         Scheduler::build_docs_nodes_for_tree(&["test-book".to_string()].to_vec());
     }
     #[test]
+    #[ignore = "依赖本地缓存的知识库信息，缓存不存在时直接panic"]
     fn test_build_docs_nodes_for_tree_second_dir() {
         Scheduler::build_docs_nodes_for_tree(&["test-book/测试目录".to_string()].to_vec());
     }
-    #[test]
-    fn test_download_task_pre_construction() {
+    #[tokio::test]
+    #[ignore = "访问真实的语雀，找不到知识库时会退出进程，离线的导出流程见 tests/e2e.rs"]
+    async fn test_download_task_pre_construction() {
         let answer = MutualAnswer {
             toc_range: ["test-book".to_string()].to_vec(),
             skip: true,
             line_break: true,
+            layout: DocLayout::Sibling,
+            include_hidden: false,
+            filter: DocFilter::default(),
+            target: ExportTarget::Markdown,
+            attachment_dir: "".to_string(),
+            path_template: PathTemplates::default(),
         };
-        Scheduler::download_task_pre_construction(answer)
+        let _ = Scheduler::download_task_pre_construction(answer, &PullOptions::default()).await;
     }
-    #[test]
+    #[tokio::test]
+    #[ignore = "访问真实的语雀，找不到知识库时会退出进程，离线的导出流程见 tests/e2e.rs"]
     /// 二级目录
-    fn test_download_task_pre_construction_second_dir() {
+    async fn test_download_task_pre_construction_second_dir() {
         let answer = MutualAnswer {
             toc_range: ["test-book/测试目录".to_string()].to_vec(),
             skip: true,
             line_break: true,
+            layout: DocLayout::Sibling,
+            include_hidden: false,
+            filter: DocFilter::default(),
+            target: ExportTarget::Markdown,
+            attachment_dir: "".to_string(),
+            path_template: PathTemplates::default(),
         };
-        Scheduler::download_task_pre_construction(answer)
+        let _ = Scheduler::download_task_pre_construction(answer, &PullOptions::default()).await;
     }
+
+    fn answer_with(layout: DocLayout, include_hidden: bool) -> MutualAnswer {
+        MutualAnswer {
+            toc_range: vec![],
+            skip: true,
+            line_break: true,
+            layout,
+            include_hidden,
+            filter: DocFilter::default(),
+            target: ExportTarget::Markdown,
+            attachment_dir: "".to_string(),
+            path_template: PathTemplates::default(),
+        }
+    }
+
+    fn tree_node(
+        title: &str,
+        node_type: &str,
+        full_path: &str,
+        children: Vec<TreeNone>,
+    ) -> TreeNone {
+        TreeNone {
+            uuid: title.to_string(),
+            node_type: node_type.to_string(),
+            children,
+            title: title.to_string(),
+            ..fixtures::tree_node(full_path)
+        }
+    }
+
+    #[test]
+    fn test_resolve_doc_save_path() {
+        let leaf = tree_node("子文档", "DOC", "book/父文档/子文档", vec![]);
+        let parent = tree_node("父文档", "DOC", "book/父文档", vec![leaf.clone()]);
+        let templates = OutputTemplates::default();
+
+        assert_eq!(
+            Scheduler::resolve_doc_save_path(
+                &leaf,
+                &answer_with(DocLayout::Index, false),
+                &templates
+            ),
+            "book/父文档/子文档.md"
+        );
+        assert_eq!(
+            Scheduler::resolve_doc_save_path(
+                &parent,
+                &answer_with(DocLayout::Sibling, false),
+                &templates
+            ),
+            "book/父文档.md"
+        );
+        assert_eq!(
+            Scheduler::resolve_doc_save_path(
+                &parent,
+                &answer_with(DocLayout::Index, false),
+                &templates
+            ),
+            "book/父文档/index.md"
+        );
+        assert_eq!(
+            Scheduler::resolve_doc_save_path(
+                &parent,
+                &answer_with(DocLayout::Readme, false),
+                &templates
+            ),
+            "book/父文档/README.md"
+        );
+    }
+
+    #[test]
+    fn test_title_group_export() {
+        let mut hidden = tree_node("隐藏文档", "DOC", "book/分组/隐藏文档", vec![]);
+        hidden.visible = 0;
+        let empty_group = tree_node("空分组", "TITLE", "book/空分组", vec![hidden.clone()]);
+        let group = tree_node(
+            "分组",
+            "TITLE",
+            "book/分组",
+            vec![
+                tree_node("文档", "DOC", "book/分组/文档", vec![]),
+                hidden,
+                tree_node(
+                    "父文档",
+                    "DOC",
+                    "book/分组/父文档",
+                    vec![tree_node(
+                        "子文档",
+                        "DOC",
+                        "book/分组/父文档/子文档",
+                        vec![],
+                    )],
+                ),
+            ],
+        );
+
+        assert!(!Scheduler::is_exportable_node(
+            &group,
+            &answer_with(DocLayout::Sibling, false)
+        ));
+        assert!(Scheduler::is_exportable_node(
+            &group,
+            &answer_with(DocLayout::Index, false)
+        ));
+        assert!(!Scheduler::is_exportable_node(
+            &empty_group,
+            &answer_with(DocLayout::Index, false)
+        ));
+
+        let flat = Scheduler::filter_valid_docs_to_flat(
+            &[vec![group.clone(), empty_group]],
+            &answer_with(DocLayout::Readme, false),
+        );
+        let paths: Vec<&str> = flat.iter().map(|doc| doc.full_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "book/分组",
+                "book/分组/文档",
+                "book/分组/父文档",
+                "book/分组/父文档/子文档"
+            ]
+        );
+
+        assert_eq!(
+            Scheduler::gen_group_index_content(
+                &group,
+                &answer_with(DocLayout::Readme, false),
+                &OutputTemplates::default()
+            ),
+            "# 分组\n\n- [文档](<文档.md>)\n- [父文档](<父文档/README.md>)\n"
+        );
+    }
+
+    #[test]
+    fn test_hidden_docs_policy() {
+        let mut hidden = tree_node("草稿", "DOC", "book/草稿", vec![]);
+        hidden.visible = 0;
+        let tree = vec![vec![tree_node("文档", "DOC", "book/文档", vec![]), hidden]];
+
+        let skip_hidden = answer_with(DocLayout::Sibling, false);
+        let valid = Scheduler::filter_valid_docs_to_flat(&tree, &skip_hidden);
+        let skipped = Scheduler::filter_hidden_docs_to_flat(&tree, &skip_hidden);
+        assert_eq!(valid.len(), 1);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].full_path, "book/草稿");
+
+        let include_hidden = answer_with(DocLayout::Sibling, true);
+        let valid = Scheduler::filter_valid_docs_to_flat(&tree, &include_hidden);
+        let skipped = Scheduler::filter_hidden_docs_to_flat(&tree, &include_hidden);
+        assert_eq!(valid.len(), 2);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn test_plan_doc_task() {
+        let mut hidden = tree_node("草稿", "DOC", "plan-book/草稿", vec![]);
+        hidden.visible = 0;
+        let docs = vec![
+            tree_node("文档", "DOC", "plan-book/文档", vec![]),
+            // 标题去掉特殊字符后重名
+            tree_node("文档?", "DOC", "plan-book/文档", vec![]),
+        ];
+
+        let plan = Scheduler::plan_doc_task(
+            &answer_with(DocLayout::Sibling, false),
+            &OutputTemplates::default(),
+            docs,
+            vec![hidden],
+            None,
+        );
+        let actions: Vec<PlanAction> = plan.items.iter().map(|item| item.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                PlanAction::Download,
+                PlanAction::Conflict,
+                PlanAction::SkipHidden
+            ]
+        );
+        assert_eq!(
+            plan.items[0].target,
+            format!("{}/plan-book/文档.md", GLOBAL_CONFIG.target_output_dir)
+        );
+        // 生成计划不会创建任何目录
+        assert!(!File::new().exists(&format!("{}/plan-book", GLOBAL_CONFIG.target_output_dir)));
+    }
+
+    #[test]
+    fn test_plan_doc_task_with_template() {
+        let mut first = tree_node("文档", "DOC", "plan-book/目录/文档", vec![]);
+        first.p_slug = "plan".to_string();
+        first.url = "a".to_string();
+        let mut second = first.clone();
+        second.full_path = "plan-book/文档".to_string();
+        second.url = "b".to_string();
+        let mut group = tree_node("目录", "TITLE", "plan-book/目录", vec![first.clone()]);
+        group.p_slug = "plan".to_string();
+
+        let config = PathTemplates {
+            doc: "{book_slug}/{slug}.{ext}".to_string(),
+            ..Default::default()
+        };
+        let templates = OutputTemplates::compile(&config, "2026-10-21").unwrap();
+        let plan = Scheduler::plan_doc_task(
+            &answer_with(DocLayout::Index, false),
+            &templates,
+            vec![first.clone(), second.clone(), group.clone()],
+            vec![],
+            None,
+        );
+        let targets: Vec<&str> = plan.items.iter().map(|item| item.target.as_str()).collect();
+        assert_eq!(
+            targets,
+            vec![
+                format!("{}/plan/a.md", GLOBAL_CONFIG.target_output_dir),
+                format!("{}/plan/b.md", GLOBAL_CONFIG.target_output_dir),
+                format!("{}/plan/目录.md", GLOBAL_CONFIG.target_output_dir),
+            ]
+        );
+        // 分组索引中的链接相对于分组的保存路径
+        assert_eq!(
+            Scheduler::gen_group_index_content(
+                &group,
+                &answer_with(DocLayout::Index, false),
+                &templates
+            ),
+            "# 目录\n\n- [文档](<a.md>)\n"
+        );
+
+        // 按标题输出时两篇同名文档冲突
+        let config = PathTemplates {
+            doc: "{book}/{title}.{ext}".to_string(),
+            ..Default::default()
+        };
+        let templates = OutputTemplates::compile(&config, "2026-10-21").unwrap();
+        let plan = Scheduler::plan_doc_task(
+            &answer_with(DocLayout::Sibling, false),
+            &templates,
+            vec![first, second],
+            vec![],
+            None,
+        );
+        assert_eq!(plan.count(PlanAction::Conflict), 1);
+    }
+
+    #[test]
+    fn test_asset_template_target() {
+        let mut answer = answer_with(DocLayout::Sibling, false);
+        answer.path_template.asset = "assets/{book_slug}/{name}".to_string();
+        assert!(Scheduler::compile_matcher_and_templates(&answer).is_err());
+
+        answer.target = ExportTarget::Obsidian;
+        let (_, templates) = Scheduler::compile_matcher_and_templates(&answer).unwrap();
+        assert!(templates.has_asset_template());
+
+        answer.target = ExportTarget::Logseq;
+        let (_, templates) = Scheduler::compile_matcher_and_templates(&answer).unwrap();
+        assert!(!templates.has_asset_template());
+    }
+
     #[tokio::test]
+    #[ignore = "访问真实的语雀并下载全部资源，离线的流程见 tests/e2e.rs 的 test_grd"]
     async fn test_get_group_resource_base_info() {
         if let Ok(source_info) = YuqueApi::get_group_resource_base_info().await {
-            Log::info("获取团队资源信息成功");
+            Log::info(t!("grd-info-success"));
             let mut resource_list: Vec<ResourceItem> = vec![];
             for item in source_info.as_array().unwrap() {
                 if item
@@ -736,7 +1890,12 @@ mod tests {
 
             println!("资源总数量,{}", resource_list.len());
 
-            Scheduler::download_all_resource_task(&resource_list).await
+            let skip = tools::get_user_config()
+                .map(|user_config| user_config.skip)
+                .unwrap_or_default();
+            let plan =
+                Scheduler::plan_resource_task(&resource_list, skip, &OutputTemplates::default());
+            Scheduler::download_all_resource_task(plan).await
             // println!("所有资源列表,{:?}", resource_list);
         }
     }
@@ -0,0 +1,484 @@
+/*
+ * Description: 本地预览导出的文档，Markdown按需渲染为HTML，左侧为文档目录，支持全文搜索
+ * Created: 2026-10-19 00:44:57
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::RwLock,
+};
+
+use actix_web::{http::header, web, App, HttpResponse, HttpServer};
+use serde::Deserialize;
+
+use super::bundle::Bundle;
+use crate::{
+    libs::{constants::GLOBAL_CONFIG, log::Log, search_index::SearchIndex, tools},
+    t,
+};
+
+/// 搜索结果数量
+const SEARCH_LIMIT: usize = 20;
+/// 目录的索引文档
+const INDEX_FILES: [&str; 2] = ["index.md", "README.md"];
+
+/// 侧边栏中的一项，目录和文档同名时合并为一项
+#[derive(Debug, PartialEq)]
+pub struct SidebarNode {
+    pub name: String,
+    /// 相对于文档目录的路径，目录没有对应文档时为空
+    pub link: Option<String>,
+    pub children: Vec<SidebarNode>,
+}
+
+struct ServeState {
+    root: String,
+    index: RwLock<SearchIndex>,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    #[serde(default)]
+    q: String,
+}
+
+pub struct Serve;
+
+impl Serve {
+    /// 启动预览服务
+    /// # Arguments
+    /// * dir - 文档目录，默认为文档输出目录
+    /// * port - 端口
+    pub async fn start(dir: Option<String>, port: u16) -> Result<(), &'static str> {
+        let root = dir
+            .unwrap_or(GLOBAL_CONFIG.target_output_dir.to_string())
+            .trim_end_matches('/')
+            .to_string();
+        if !Path::new(&root).is_dir() {
+            return Err(t!("serve-dir-not-found"));
+        }
+
+        let mut index = SearchIndex::default();
+        if index.update(&root).is_err() {
+            Log::warn(t!("serve-index-failed"));
+        }
+        let state = web::Data::new(ServeState {
+            root,
+            index: RwLock::new(index),
+        });
+
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(state.clone())
+                .route("/_search", web::get().to(Self::search))
+                .route("/{path:.*}", web::get().to(Self::page))
+        })
+        .bind(("127.0.0.1", port))
+        .map_err(|_| t!("serve-port-in-use"))?;
+
+        Log::success(&t!("serve-started", port = port));
+        server.run().await.map_err(|_| t!("serve-exited"))
+    }
+
+    /// 文档、目录和附件
+    async fn page(state: web::Data<ServeState>, path: web::Path<String>) -> HttpResponse {
+        let rel_path = path.into_inner().trim_matches('/').to_string();
+        let full_path = match Self::resolve_path(&state.root, &rel_path) {
+            Some(full_path) => full_path,
+            None => return HttpResponse::NotFound().body("Not Found"),
+        };
+
+        if full_path.is_dir() {
+            // 保证文档中的相对链接基于当前目录
+            if !rel_path.is_empty() {
+                let location = format!("/{}/", Self::encode_path(&rel_path));
+                if let Some(index_file) = INDEX_FILES
+                    .iter()
+                    .find(|name| full_path.join(name).is_file())
+                {
+                    return Self::redirect(&format!(
+                        "{}{}",
+                        location,
+                        Self::encode_path(index_file)
+                    ));
+                }
+            }
+            let title = full_path.file_name().map_or("文档".to_string(), |name| {
+                name.to_string_lossy().to_string()
+            });
+            let listing = Self::render_sidebar(&Self::build_sidebar(&state.root, &rel_path), "");
+            return Self::html(Self::layout(&state.root, &title, &rel_path, &listing, ""));
+        }
+
+        if !full_path.is_file() {
+            return HttpResponse::NotFound().body("Not Found");
+        }
+
+        if rel_path.ends_with(".md") {
+            let content = match fs::read_to_string(&full_path) {
+                Ok(content) => content,
+                Err(_) => return HttpResponse::InternalServerError().body("文档读取失败"),
+            };
+            let title = full_path
+                .file_stem()
+                .map_or("".to_string(), |name| name.to_string_lossy().to_string());
+            let body = Bundle::markdown_to_html(&Self::strip_front_matter(&content));
+            return Self::html(Self::layout(&state.root, &title, &rel_path, &body, ""));
+        }
+
+        match fs::read(&full_path) {
+            Ok(bytes) => HttpResponse::Ok()
+                .insert_header((header::CONTENT_TYPE, Self::content_type(&rel_path)))
+                .body(bytes),
+            Err(_) => HttpResponse::InternalServerError().body("文件读取失败"),
+        }
+    }
+
+    /// 全文搜索，搜索前按修改时间增量更新索引
+    async fn search(state: web::Data<ServeState>, query: web::Query<SearchQuery>) -> HttpResponse {
+        let q = query.q.trim();
+        if let Ok(mut index) = state.index.write() {
+            let _ = index.update(&state.root);
+        }
+        let hits = match state.index.read() {
+            Ok(index) => index.search(q, SEARCH_LIMIT),
+            Err(_) => vec![],
+        };
+
+        let mut body = format!(
+            "<h1>搜索：{}</h1>\n<p>共 {} 条结果</p>\n<ul class=\"hits\">\n",
+            Bundle::escape_html(q),
+            hits.len()
+        );
+        for hit in &hits {
+            body.push_str(&format!(
+                "<li><a href=\"/{}\">{}</a><small>{}</small><p>{}</p></li>\n",
+                Self::encode_path(&hit.path),
+                Bundle::escape_html(&hit.title),
+                Bundle::escape_html(&hit.path),
+                Bundle::escape_html(&hit.snippet)
+            ));
+        }
+        body.push_str("</ul>\n");
+
+        Self::html(Self::layout(&state.root, "搜索", "", &body, q))
+    }
+
+    /// 构造侧边栏目录，目录和文档按名称排序
+    /// # Arguments
+    /// * root - 文档目录
+    /// * rel_dir - 相对于文档目录的子目录
+    pub fn build_sidebar(root: &str, rel_dir: &str) -> Vec<SidebarNode> {
+        let join = |name: &str| {
+            if rel_dir.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", rel_dir, name)
+            }
+        };
+
+        let mut entries: Vec<(String, bool)> = match fs::read_dir(Path::new(root).join(rel_dir)) {
+            Ok(dir) => dir
+                .filter_map(|entry| entry.ok())
+                .map(|entry| {
+                    (
+                        entry.file_name().to_string_lossy().to_string(),
+                        entry.path().is_dir(),
+                    )
+                })
+                .filter(|(name, _)| !name.starts_with('.') && !tools::is_report_file(name))
+                .collect(),
+            Err(_) => return vec![],
+        };
+        entries.sort();
+
+        let mut nodes = vec![];
+        for (name, is_dir) in &entries {
+            if *is_dir {
+                let children = Self::build_sidebar(root, &join(name));
+                // sibling布局的同名文档，或目录下的index.md/README.md
+                let sibling = format!("{}.md", name);
+                let link = if entries.contains(&(sibling.to_string(), false)) {
+                    Some(join(&sibling))
+                } else {
+                    INDEX_FILES
+                        .iter()
+                        .find(|index_file| {
+                            Path::new(root).join(join(name)).join(index_file).is_file()
+                        })
+                        .map(|index_file| format!("{}/{}", join(name), index_file))
+                };
+                // 没有文档的目录(如附件目录)不显示
+                if children.is_empty() && link.is_none() {
+                    continue;
+                }
+                nodes.push(SidebarNode {
+                    name: name.to_string(),
+                    link,
+                    children,
+                });
+            } else if let Some(stem) = name.strip_suffix(".md") {
+                let is_dir_doc = entries.contains(&(stem.to_string(), true))
+                    || (!rel_dir.is_empty() && INDEX_FILES.contains(&name.as_str()));
+                if is_dir_doc {
+                    continue;
+                }
+                nodes.push(SidebarNode {
+                    name: stem.to_string(),
+                    link: Some(join(name)),
+                    children: vec![],
+                });
+            }
+        }
+        nodes
+    }
+
+    fn render_sidebar(nodes: &Vec<SidebarNode>, current: &str) -> String {
+        let mut html = String::from("<ul>\n");
+        for node in nodes {
+            let name = Bundle::escape_html(&node.name);
+            match &node.link {
+                Some(link) => {
+                    let class = if link == current {
+                        " class=\"active\""
+                    } else {
+                        ""
+                    };
+                    html.push_str(&format!(
+                        "<li><a href=\"/{}\"{}>{}</a>",
+                        Self::encode_path(link),
+                        class,
+                        name
+                    ));
+                }
+                None => html.push_str(&format!("<li><span>{}</span>", name)),
+            }
+            if !node.children.is_empty() {
+                html.push_str(&Self::render_sidebar(&node.children, current));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+        html
+    }
+
+    fn layout(root: &str, title: &str, current: &str, body: &str, query: &str) -> String {
+        let sidebar = Self::render_sidebar(&Self::build_sidebar(root, ""), current);
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body {{ margin: 0; display: flex; font-family: -apple-system, "PingFang SC", "Microsoft YaHei", sans-serif; color: #262626; }}
+nav {{ width: 280px; height: 100vh; overflow: auto; position: sticky; top: 0; padding: 16px; box-sizing: border-box; border-right: 1px solid #eee; background: #fafafa; font-size: 14px; }}
+nav ul {{ list-style: none; padding-left: 12px; margin: 0; }}
+nav > ul {{ padding-left: 0; }}
+nav li {{ margin: 4px 0; }}
+nav a {{ color: #262626; text-decoration: none; }}
+nav a.active {{ color: #00b96b; font-weight: bold; }}
+nav input {{ width: 100%; box-sizing: border-box; padding: 6px; margin-bottom: 12px; }}
+main {{ flex: 1; max-width: 860px; padding: 24px 48px; line-height: 1.7; }}
+main img {{ max-width: 100%; }}
+main pre {{ background: #f6f8fa; padding: 12px; overflow: auto; }}
+main table {{ border-collapse: collapse; }}
+main td, main th {{ border: 1px solid #ddd; padding: 4px 8px; }}
+.hits small {{ margin-left: 8px; color: #8c8c8c; }}
+</style>
+</head>
+<body>
+<nav>
+<form action="/_search"><input name="q" placeholder="搜索文档" value="{query}"></form>
+{sidebar}</nav>
+<main>
+{body}</main>
+</body>
+</html>
+"#,
+            title = Bundle::escape_html(title),
+            query = Bundle::escape_html(query),
+            sidebar = sidebar,
+            body = body
+        )
+    }
+
+    fn html(content: String) -> HttpResponse {
+        HttpResponse::Ok()
+            .insert_header((header::CONTENT_TYPE, "text/html; charset=utf-8"))
+            .body(content)
+    }
+
+    fn redirect(location: &str) -> HttpResponse {
+        HttpResponse::Found()
+            .insert_header((header::LOCATION, location))
+            .finish()
+    }
+
+    /// 请求路径对应的本地文件，不允许访问文档目录之外的文件和隐藏文件
+    /// 只接受普通的路径片段，`..`、根目录和盘符都会被拒绝；符号链接解析后仍需在文档目录中
+    /// 导出的文件名中不会有 `:` 和 `\\`，出现时按Windows的盘符和分隔符处理
+    fn resolve_path(root: &str, rel_path: &str) -> Option<PathBuf> {
+        let is_normal = Path::new(rel_path).components().all(|component| match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                !name.starts_with('.') && !name.contains([':', '\\'])
+            }
+            _ => false,
+        });
+        if !is_normal {
+            return None;
+        }
+
+        let root = Path::new(root).canonicalize().ok()?;
+        let full_path = root.join(rel_path).canonicalize().ok()?;
+        if full_path.starts_with(&root) {
+            Some(full_path)
+        } else {
+            None
+        }
+    }
+
+    /// 去掉Obsidian导出的front matter，避免渲染成正文
+    fn strip_front_matter(content: &str) -> String {
+        if let Some(rest) = content.strip_prefix("---\n") {
+            if let Some(end) = rest.find("\n---\n") {
+                return rest[end + 5..].to_string();
+            }
+        }
+        content.to_string()
+    }
+
+    /// 路径中的每一段按URL编码，保留 `/`
+    pub fn encode_path(path: &str) -> String {
+        path.bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                    (byte as char).to_string()
+                }
+                _ => format!("%{:02X}", byte),
+            })
+            .collect()
+    }
+
+    fn content_type(path: &str) -> &'static str {
+        match path
+            .rsplit('.')
+            .next()
+            .unwrap_or("")
+            .to_lowercase()
+            .as_str()
+        {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            "webp" => "image/webp",
+            "pdf" => "application/pdf",
+            "html" => "text/html; charset=utf-8",
+            "css" => "text/css; charset=utf-8",
+            "js" => "text/javascript; charset=utf-8",
+            "json" => "application/json",
+            "txt" => "text/plain; charset=utf-8",
+            _ => "application/octet-stream",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::file::File;
+
+    #[test]
+    fn test_build_sidebar() {
+        let f = File::new();
+        let root = "./dev/serve-docs";
+        let _ = f.rmdir(root);
+        let _ = f.mkdir(&format!("{}/book/目录", root));
+        let _ = f.mkdir(&format!("{}/book/分组", root));
+        let _ = f.mkdir(&format!("{}/attachments", root));
+        let _ = f.write(&format!("{}/book/目录.md", root), "".to_string());
+        let _ = f.write(&format!("{}/book/目录/文档.md", root), "".to_string());
+        let _ = f.write(&format!("{}/book/分组/index.md", root), "".to_string());
+        let _ = f.write(&format!("{}/attachments/a.png", root), "".to_string());
+        let _ = f.write(&format!("{}/导出报告.md", root), "".to_string());
+
+        let leaf = |name: &str, link: &str| SidebarNode {
+            name: name.to_string(),
+            link: Some(link.to_string()),
+            children: vec![],
+        };
+        assert_eq!(
+            Serve::build_sidebar(root, ""),
+            vec![SidebarNode {
+                name: "book".to_string(),
+                link: None,
+                children: vec![
+                    leaf("分组", "book/分组/index.md"),
+                    SidebarNode {
+                        name: "目录".to_string(),
+                        link: Some("book/目录.md".to_string()),
+                        children: vec![leaf("文档", "book/目录/文档.md")],
+                    },
+                ],
+            }]
+        );
+
+        let _ = f.rmdir(root);
+    }
+
+    #[test]
+    fn test_paths() {
+        let f = File::new();
+        let root = "./dev/serve-paths";
+        let _ = f.rmdir(root);
+        let _ = f.mkdir(&format!("{}/book/目录", root));
+        let _ = f.mkdir(&format!("{}/.git", root));
+        let _ = f.write(&format!("{}/book/目录/文档.md", root), "".to_string());
+        let _ = f.write(&format!("{}/.git/config", root), "".to_string());
+
+        let real_root = Path::new(root).canonicalize().unwrap();
+        assert_eq!(
+            Serve::resolve_path(root, "book/目录/文档.md"),
+            Some(real_root.join("book/目录/文档.md"))
+        );
+        assert_eq!(Serve::resolve_path(root, ""), Some(real_root));
+        assert_eq!(Serve::resolve_path(root, "book/目录/缺失.md"), None);
+        for rel_path in [
+            "..",
+            "book/../../etc/passwd",
+            "book/../book/目录/文档.md",
+            "/etc/passwd",
+            "C:/x",
+            "C:\\x",
+            "book\\..\\..\\x",
+            ".git/config",
+        ] {
+            assert_eq!(Serve::resolve_path(root, rel_path), None, "{}", rel_path);
+        }
+
+        // 指向文档目录之外的符号链接
+        #[cfg(unix)]
+        {
+            let _ = std::os::unix::fs::symlink("/etc", format!("{}/book/etc", root));
+            assert_eq!(Serve::resolve_path(root, "book/etc/passwd"), None);
+        }
+
+        let _ = f.rmdir(root);
+        assert_eq!(
+            Serve::encode_path("book/文档 1.md"),
+            "book/%E6%96%87%E6%A1%A3%201.md"
+        );
+        assert_eq!(
+            Serve::strip_front_matter("---\naliases:\n  - \"文档\"\n---\n\n正文"),
+            "\n正文"
+        );
+    }
+}
@@ -0,0 +1,644 @@
+/*
+ * Description: 知识库统计，基于缓存的目录和导出的文档，统计文档数量、层级、字数、图片、过期文档及失效链接
+ * Created: 2026-10-19 01:25:31
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+use chrono::{Duration, Local};
+use clap::ValueEnum;
+use lazy_static::lazy_static;
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use serde::Serialize;
+
+use super::{browse::Browse, scheduler::Scheduler, template::OutputTemplates, vault::Vault};
+use crate::{
+    libs::{
+        constants::{
+            schema::{cache_book::BookItem, TreeNone},
+            GLOBAL_CONFIG,
+        },
+        file::File,
+        search_index::is_cjk,
+        tools,
+    },
+    t,
+};
+
+lazy_static! {
+    static ref IMAGE_REGEX: Regex = Regex::new(r"!\[[^\]]*\]\([^)]*\)|<img\s").unwrap();
+    /// 链接地址，统计字数时去掉
+    static ref LINK_URL_REGEX: Regex = Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").unwrap();
+    static ref HTML_TAG_REGEX: Regex = Regex::new(r"<[^>]+>").unwrap();
+    /// 语雀文档链接，捕获 用户/知识库slug/文档slug
+    static ref YUQUE_LINK_REGEX: Regex = Regex::new(
+        r"\]\((?:https?://[^/\s)]+)?/([^/\s)]+)/([^/\s)]+)/([\w-]+)(?:[?#][^)\s]*)?\)"
+    )
+    .unwrap();
+    /// 指向本地Markdown文件的相对链接
+    static ref LOCAL_LINK_REGEX: Regex =
+        Regex::new(r"\]\(<?([^)\s:>]+\.md)(?:#[^)\s>]*)?>?\)").unwrap();
+}
+
+/// 统计结果的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// 终端表格
+    Table,
+    Json,
+    /// 每个知识库一行
+    Csv,
+}
+
+/// 单篇文档的大小
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DocSize {
+    pub path: String,
+    pub words: usize,
+    pub chars: usize,
+}
+
+/// 长时间没有更新的文档
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StaleDoc {
+    pub path: String,
+    pub updated_at: String,
+}
+
+/// 文档中指向不存在的文档的链接
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BrokenLink {
+    pub path: String,
+    pub link: String,
+}
+
+/// 单个知识库的统计
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct BookStats {
+    pub name: String,
+    pub slug: String,
+    pub doc_count: usize,
+    /// 本地已导出的文档数量，字数等只统计这部分
+    pub exported_count: usize,
+    /// 目录深度 -> 文档数量，知识库下第一级为1
+    pub depth: BTreeMap<usize, usize>,
+    /// 字数，中日韩文字每字算一个，其余按单词计
+    pub words: usize,
+    /// 字符数，不含空白
+    pub chars: usize,
+    pub images: usize,
+    /// 字数最多的文档
+    pub largest: Vec<DocSize>,
+    pub stale: Vec<StaleDoc>,
+    pub broken_links: Vec<BrokenLink>,
+}
+
+/// 统计时的条件
+pub struct StatsOptions {
+    /// 早于该日期(YYYY-MM-DD)更新的文档视为过期
+    pub stale_before: String,
+    /// 最大文档的数量
+    pub top: usize,
+    /// 导出配置中的路径模板，配置了文档模板时按模板查找导出的文档
+    pub templates: OutputTemplates,
+}
+
+pub struct Stats;
+
+impl Stats {
+    /// 统计启动程序，只读取本地缓存和导出的文档，无需登录
+    /// # Arguments
+    /// * books - 知识库名称或slug，为空时统计所有缓存的知识库
+    /// * format - 输出格式
+    /// * stale_days - 超过这么多天没有更新的文档视为过期
+    /// * top - 列出字数最多的文档数量
+    pub fn start(
+        books: Vec<String>,
+        format: StatsFormat,
+        stale_days: i64,
+        top: usize,
+    ) -> Result<(), &'static str> {
+        let books_info = tools::get_cache_books_info().map_err(|_| t!("books-cache-missing"))?;
+
+        let targets: Vec<&BookItem> = if books.is_empty() {
+            books_info.iter().collect()
+        } else {
+            let mut targets = vec![];
+            for book in &books {
+                match books_info
+                    .iter()
+                    .find(|item| &item.name == book || &item.slug == book)
+                {
+                    Some(item) => targets.push(item),
+                    None => return Err(t!("book-not-found")),
+                }
+            }
+            targets
+        };
+
+        // 模板有误时导出也不会进行，按默认的目录结构查找
+        // 模板中的 {date} 是导出当天的日期，只能按今天查找
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let templates = tools::get_user_config()
+            .ok()
+            .and_then(|config| OutputTemplates::compile(&config.path_template, &today).ok())
+            .unwrap_or_default();
+        let options = StatsOptions {
+            stale_before: (Local::now() - Duration::days(stale_days))
+                .format("%Y-%m-%d")
+                .to_string(),
+            top,
+            templates,
+        };
+        let namespaces = Self::namespaces(&books_info);
+        let stats: Vec<BookStats> = targets
+            .into_iter()
+            .map(|book| {
+                Self::book_stats(
+                    book,
+                    &GLOBAL_CONFIG.target_output_dir,
+                    &namespaces,
+                    &options,
+                )
+            })
+            .collect();
+
+        match format {
+            StatsFormat::Table => println!("{}", Self::render_table(&stats, stale_days)),
+            StatsFormat::Json => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+            StatsFormat::Csv => print!("{}", Self::render_csv(&stats)),
+        }
+        Ok(())
+    }
+
+    /// 缓存的知识库 用户/slug -> 文档slug，用于判断链接是否失效
+    pub fn namespaces(books_info: &[BookItem]) -> HashMap<String, HashSet<String>> {
+        books_info
+            .iter()
+            .map(|book| {
+                (
+                    format!("{}/{}", book.user_login, book.slug),
+                    book.docs.iter().map(|doc| doc.url.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// 统计单个知识库
+    /// # Arguments
+    /// * book - 缓存的知识库
+    /// * root - 文档输出目录
+    /// * namespaces - 所有缓存的知识库及其文档
+    /// * options - 统计条件
+    pub fn book_stats(
+        book: &BookItem,
+        root: &str,
+        namespaces: &HashMap<String, HashSet<String>>,
+        options: &StatsOptions,
+    ) -> BookStats {
+        let mut nodes: Vec<TreeNone> = vec![];
+        Browse::flatten_nodes(&Scheduler::build_book_tree(book), &mut nodes);
+
+        let mut stats = BookStats {
+            name: book.name.to_string(),
+            slug: book.slug.to_string(),
+            ..Default::default()
+        };
+        let mut sizes: Vec<DocSize> = vec![];
+        let f = File::new();
+
+        for node in nodes.iter().filter(|node| node.node_type == "DOC") {
+            stats.doc_count += 1;
+            *stats
+                .depth
+                .entry(node.full_path.matches('/').count())
+                .or_insert(0) += 1;
+
+            // ISO 8601格式的日期可以直接按字符串比较，旧缓存中没有更新时间的不计入
+            if let Some(date) = node.updated_at.get(..10) {
+                if date < options.stale_before.as_str() {
+                    stats.stale.push(StaleDoc {
+                        path: node.full_path.to_string(),
+                        updated_at: node.updated_at.to_string(),
+                    });
+                }
+            }
+
+            let file = match Self::find_exported(root, node, &options.templates) {
+                Some(file) => file,
+                None => continue,
+            };
+            let content = match f.read(&file) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            stats.exported_count += 1;
+
+            let (words, chars) = Self::count_text(&content);
+            stats.words += words;
+            stats.chars += chars;
+            stats.images += IMAGE_REGEX.find_iter(&content).count();
+            sizes.push(DocSize {
+                path: node.full_path.to_string(),
+                words,
+                chars,
+            });
+            for link in Self::find_broken_links(&content, &file, namespaces) {
+                stats.broken_links.push(BrokenLink {
+                    path: node.full_path.to_string(),
+                    link,
+                });
+            }
+        }
+
+        sizes.sort_by(|a, b| b.words.cmp(&a.words).then(a.path.cmp(&b.path)));
+        sizes.truncate(options.top);
+        stats.largest = sizes;
+        stats
+            .stale
+            .sort_by(|a, b| a.updated_at.cmp(&b.updated_at).then(a.path.cmp(&b.path)));
+        stats
+    }
+
+    /// 统计字数和字符数，不计图片、链接地址和HTML标签
+    /// 中日韩文字每字算一个字，其余连续的字母数字算一个字
+    pub fn count_text(content: &str) -> (usize, usize) {
+        let text = LINK_URL_REGEX.replace_all(content, "$1");
+        let text = HTML_TAG_REGEX.replace_all(&text, " ");
+
+        let mut words = 0;
+        let mut chars = 0;
+        let mut in_word = false;
+        for c in text.chars() {
+            if c.is_whitespace() {
+                in_word = false;
+                continue;
+            }
+            chars += 1;
+            if is_cjk(c) {
+                words += 1;
+                in_word = false;
+            } else if c.is_alphanumeric() {
+                if !in_word {
+                    words += 1;
+                }
+                in_word = true;
+            } else {
+                in_word = false;
+            }
+        }
+        (words, chars)
+    }
+
+    /// 文档中失效的链接：指向缓存中的知识库但文档已不存在，或指向不存在的本地文件
+    /// # Arguments
+    /// * content - 文档内容
+    /// * file - 文档的本地路径，用于解析相对链接
+    /// * namespaces - 所有缓存的知识库及其文档
+    pub fn find_broken_links(
+        content: &str,
+        file: &str,
+        namespaces: &HashMap<String, HashSet<String>>,
+    ) -> Vec<String> {
+        let mut links: Vec<String> = vec![];
+
+        // 不在缓存中的知识库无法判断，不计入
+        for caps in YUQUE_LINK_REGEX.captures_iter(content) {
+            let namespace = format!("{}/{}", &caps[1], &caps[2]);
+            if let Some(docs) = namespaces.get(&namespace) {
+                if !docs.contains(&caps[3]) {
+                    links.push(Self::link_target(&caps[0]));
+                }
+            }
+        }
+
+        let dir = Path::new(file).parent().unwrap_or(Path::new(""));
+        for caps in LOCAL_LINK_REGEX.captures_iter(content) {
+            let target = caps[1].to_string();
+            let decoded = percent_decode_str(&target).decode_utf8_lossy();
+            if !dir.join(decoded.as_ref()).exists() && !dir.join(&target).exists() {
+                links.push(target);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        links.retain(|link| seen.insert(link.to_string()));
+        links
+    }
+
+    /// 文档导出后的本地文件，优先按文档模板查找，兼容各种layout和Logseq
+    fn find_exported(root: &str, node: &TreeNone, templates: &OutputTemplates) -> Option<String> {
+        templates
+            .doc_path(node)
+            .into_iter()
+            .chain([
+                format!("{}.md", node.full_path),
+                format!("{}/index.md", node.full_path),
+                format!("{}/README.md", node.full_path),
+                Vault::logseq_page_file(&node.full_path),
+            ])
+            .map(|path| format!("{}/{}", root, path))
+            .find(|path| Path::new(path).is_file())
+    }
+
+    /// ](地址) 中的地址
+    fn link_target(link: &str) -> String {
+        link.trim_start_matches("](")
+            .trim_end_matches(')')
+            .to_string()
+    }
+
+    /// 终端表格，先是各知识库的汇总，再是每个知识库的详细信息
+    pub fn render_table(stats: &[BookStats], stale_days: i64) -> String {
+        let mut rows: Vec<Vec<String>> = vec![[
+            "知识库",
+            "文档",
+            "已导出",
+            "字数",
+            "字符",
+            "图片",
+            "过期",
+            "失效链接",
+        ]
+        .map(|cell| cell.to_string())
+        .to_vec()];
+        let mut total = BookStats {
+            name: "合计".to_string(),
+            ..Default::default()
+        };
+        for item in stats {
+            total.doc_count += item.doc_count;
+            total.exported_count += item.exported_count;
+            total.words += item.words;
+            total.chars += item.chars;
+            total.images += item.images;
+            rows.push(Self::summary_row(item));
+        }
+        let mut total_row = Self::summary_row(&total);
+        total_row[6] = stats
+            .iter()
+            .map(|item| item.stale.len())
+            .sum::<usize>()
+            .to_string();
+        total_row[7] = stats
+            .iter()
+            .map(|item| item.broken_links.len())
+            .sum::<usize>()
+            .to_string();
+        rows.push(total_row);
+
+        let mut lines: Vec<String> = vec!["知识库统计：".to_string()];
+        lines.extend(
+            tools::format_table(&rows)
+                .into_iter()
+                .map(|line| format!("  {}", line)),
+        );
+
+        for item in stats {
+            lines.push(String::new());
+            lines.push(format!("📚 {}", item.name));
+            let depth = item
+                .depth
+                .iter()
+                .map(|(depth, count)| format!("{}级 {} 篇", depth, count))
+                .collect::<Vec<String>>()
+                .join("，");
+            lines.push(format!("  层级分布：{}", depth));
+            if !item.largest.is_empty() {
+                lines.push("  字数最多：".to_string());
+                for doc in &item.largest {
+                    lines.push(format!("    {}  {} 字", doc.path, doc.words));
+                }
+            }
+            if !item.stale.is_empty() {
+                lines.push(format!("  超过 {} 天未更新：", stale_days));
+                for doc in &item.stale {
+                    lines.push(format!(
+                        "    {}  {}",
+                        doc.path,
+                        doc.updated_at.get(..10).unwrap_or(&doc.updated_at)
+                    ));
+                }
+            }
+            if !item.broken_links.is_empty() {
+                lines.push("  失效链接：".to_string());
+                for link in &item.broken_links {
+                    lines.push(format!("    {} -> {}", link.path, link.link));
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn summary_row(item: &BookStats) -> Vec<String> {
+        vec![
+            item.name.to_string(),
+            item.doc_count.to_string(),
+            item.exported_count.to_string(),
+            item.words.to_string(),
+            item.chars.to_string(),
+            item.images.to_string(),
+            item.stale.len().to_string(),
+            item.broken_links.len().to_string(),
+        ]
+    }
+
+    /// CSV，每个知识库一行，层级分布格式为 深度:数量，以分号分隔
+    pub fn render_csv(stats: &[BookStats]) -> String {
+        let mut csv = "name,slug,doc_count,exported_count,words,chars,images,stale_count,broken_link_count,depth\n".to_string();
+        for item in stats {
+            let depth = item
+                .depth
+                .iter()
+                .map(|(depth, count)| format!("{}:{}", depth, count))
+                .collect::<Vec<String>>()
+                .join(";");
+            let row = [
+                item.name.to_string(),
+                item.slug.to_string(),
+                item.doc_count.to_string(),
+                item.exported_count.to_string(),
+                item.words.to_string(),
+                item.chars.to_string(),
+                item.images.to_string(),
+                item.stale.len().to_string(),
+                item.broken_links.len().to_string(),
+                depth,
+            ];
+            csv.push_str(
+                &row.iter()
+                    .map(|cell| Self::csv_escape(cell))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn csv_escape(cell: &str) -> String {
+        if cell.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::{
+        constants::schema::{cache_book::DocItem, PathTemplates},
+        fixtures::{book_item, doc_item},
+    };
+    use std::fs;
+
+    fn doc(title: &str, uuid: &str, parent_uuid: &str, url: &str, updated_at: &str) -> DocItem {
+        DocItem {
+            node_type: if url.is_empty() { "TITLE" } else { "DOC" }.to_string(),
+            url: url.to_string(),
+            updated_at: updated_at.to_string(),
+            ..doc_item(title, uuid, parent_uuid)
+        }
+    }
+
+    fn book() -> BookItem {
+        book_item(
+            "Rust笔记",
+            "rust-book",
+            vec![
+                doc("基础", "a", "", "", ""),
+                doc("所有权", "b", "a", "ownership", "2023-09-01T08:00:00.000Z"),
+                doc("生命周期", "c", "a", "lifetime", "2021-01-01T08:00:00.000Z"),
+                doc("随笔", "d", "", "essay", ""),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_count_text() {
+        assert_eq!(Stats::count_text("Rust 所有权"), (4, 7));
+        assert_eq!(
+            Stats::count_text("# 标题\n\n![图片](https://cdn/a.png) see [docs](https://x.y/z)"),
+            (6, 12)
+        );
+        assert_eq!(Stats::count_text("<p>hello-world</p>"), (2, 11));
+    }
+
+    #[test]
+    fn test_book_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("Rust笔记/基础")).unwrap();
+        fs::write(
+            dir.path().join("Rust笔记/基础/所有权.md"),
+            "所有权规则\n\n![](a.png)\n[生命周期](/vannvan/rust-book/lifetime) [已删除](https://www.yuque.com/vannvan/rust-book/removed) [外部](/other/book/doc) [本地](./不存在.md) [本地](./生命周期.md)",
+        )
+        .unwrap();
+        fs::write(dir.path().join("Rust笔记/基础/生命周期.md"), "lifetime 'a").unwrap();
+
+        let books_info = vec![book()];
+        let options = StatsOptions {
+            stale_before: "2022-01-01".to_string(),
+            top: 1,
+            templates: OutputTemplates::default(),
+        };
+        let stats = Stats::book_stats(
+            &books_info[0],
+            root,
+            &Stats::namespaces(&books_info),
+            &options,
+        );
+
+        assert_eq!(stats.doc_count, 3);
+        assert_eq!(stats.exported_count, 2);
+        assert_eq!(stats.depth, BTreeMap::from([(1, 1), (2, 2)]));
+        assert_eq!(stats.images, 1);
+        assert_eq!(stats.largest.len(), 1);
+        assert_eq!(stats.largest[0].path, "Rust笔记/基础/所有权");
+        assert_eq!(
+            stats.stale,
+            vec![StaleDoc {
+                path: "Rust笔记/基础/生命周期".to_string(),
+                updated_at: "2021-01-01T08:00:00.000Z".to_string(),
+            }]
+        );
+        let links: Vec<&str> = stats
+            .broken_links
+            .iter()
+            .map(|link| link.link.as_str())
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://www.yuque.com/vannvan/rust-book/removed",
+                "./不存在.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_book_stats_with_doc_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("Rust笔记")).unwrap();
+        fs::write(dir.path().join("Rust笔记/ownership.md"), "所有权").unwrap();
+
+        let books_info = vec![book()];
+        let path_template = PathTemplates {
+            doc: "{book}/{slug}.{ext}".to_string(),
+            ..Default::default()
+        };
+        let options = StatsOptions {
+            stale_before: "2022-01-01".to_string(),
+            top: 1,
+            templates: OutputTemplates::compile(&path_template, "2023-09-01").unwrap(),
+        };
+        let stats = Stats::book_stats(
+            &books_info[0],
+            root,
+            &Stats::namespaces(&books_info),
+            &options,
+        );
+        assert_eq!(stats.exported_count, 1);
+    }
+
+    #[test]
+    fn test_find_broken_links() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("生命周期.md"), "").unwrap();
+        let file = dir.path().join("所有权.md");
+
+        // 百分号编码的本地链接按解码后的路径查找，不相邻的重复链接也只记一次
+        let links = Stats::find_broken_links(
+            "[a](./%E7%94%9F%E5%91%BD%E5%91%A8%E6%9C%9F.md) [b](./a+b.md) [c](./x.md) [d](./a+b.md)",
+            &file.to_string_lossy(),
+            &HashMap::new(),
+        );
+        assert_eq!(links, vec!["./a+b.md", "./x.md"]);
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let stats = vec![BookStats {
+            name: "a,b".to_string(),
+            slug: "ab".to_string(),
+            doc_count: 2,
+            depth: BTreeMap::from([(1, 1), (2, 1)]),
+            ..Default::default()
+        }];
+        assert_eq!(
+            Stats::render_csv(&stats).lines().nth(1).unwrap(),
+            "\"a,b\",ab,2,0,0,0,0,0,0,1:1;2:1"
+        );
+    }
+}
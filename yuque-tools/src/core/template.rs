@@ -0,0 +1,408 @@
+/*
+ * Description: 输出路径模板，按文档/附件/资源的字段生成保存路径，如 `{book_slug}/{path}/{title}.{ext}`
+ * Created: 2026-10-19 02:06:58
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::collections::HashMap;
+
+use crate::{
+    libs::{
+        constants::schema::{PathTemplates, TreeNone},
+        tools::sanitize_file_name,
+    },
+    t,
+};
+
+/// 模板用途，决定可用的变量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    /// 文档，相对于文档输出目录
+    Doc,
+    /// Obsidian附件，相对于文档输出目录
+    Asset,
+    /// grd资源，相对于资源输出目录
+    Resource,
+}
+
+impl TemplateKind {
+    /// 可用的变量
+    fn variables(&self) -> &'static [&'static str] {
+        match self {
+            TemplateKind::Doc => &[
+                "space",
+                "book",
+                "book_slug",
+                "path",
+                "title",
+                "slug",
+                "uuid",
+                "author",
+                "updated",
+                "date",
+                "ext",
+            ],
+            TemplateKind::Asset => &[
+                "space",
+                "book",
+                "book_slug",
+                "path",
+                "title",
+                "name",
+                "date",
+            ],
+            TemplateKind::Resource => &["folder", "file", "ext", "date"],
+        }
+    }
+
+    /// 至少要包含其中一个变量，否则同一知识库/目录下的文件必然重名
+    fn unique_variables(&self) -> &'static [&'static str] {
+        match self {
+            TemplateKind::Doc => &["title", "slug", "uuid"],
+            TemplateKind::Asset => &["name"],
+            TemplateKind::Resource => &["file"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Var(String),
+}
+
+/// 编译后的路径模板
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathTemplate {
+    parts: Vec<Part>,
+}
+
+impl PathTemplate {
+    /// 校验并编译模板
+    /// # Arguments
+    /// * source - 模板，变量写作 `{name}`
+    /// * kind - 模板用途
+    pub fn compile(source: &str, kind: TemplateKind) -> Result<Self, String> {
+        let invalid = |reason: &str| t!("template-invalid", template = source, reason = reason);
+
+        if source.starts_with('/') || source.split('/').any(|part| part == "..") {
+            return Err(invalid(t!("template-outside")));
+        }
+
+        let mut parts = vec![];
+        let mut rest = source;
+        while let Some(start) = rest.find(['{', '}']) {
+            if rest[start..].starts_with('}') {
+                return Err(invalid(t!("template-unclosed")));
+            }
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => return Err(invalid(t!("template-unclosed"))),
+            };
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            let name = &rest[start + 1..end];
+            if !kind.variables().contains(&name) {
+                return Err(invalid(&t!(
+                    "template-unknown-variable",
+                    name = name,
+                    variables = kind.variables().join(", ")
+                )));
+            }
+            parts.push(Part::Var(name.to_string()));
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+
+        let has_unique = parts.iter().any(|part| match part {
+            Part::Var(name) => kind.unique_variables().contains(&name.as_str()),
+            _ => false,
+        });
+        if !has_unique {
+            return Err(invalid(&t!(
+                "template-not-unique",
+                variables = kind.unique_variables().join(" / ")
+            )));
+        }
+
+        Ok(PathTemplate { parts })
+    }
+
+    /// 生成路径，空的层级会被去掉
+    /// # Arguments
+    /// * vars - 变量的值，`path`/`folder`可以包含多级，其余变量中的 `/` 等特殊字符会被去掉
+    pub fn render(&self, vars: &HashMap<&str, String>) -> String {
+        let rendered: String = self
+            .parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.to_string(),
+                Part::Var(name) => {
+                    let value = vars.get(name.as_str()).map(|v| v.as_str()).unwrap_or("");
+                    match name.as_str() {
+                        "path" | "folder" => value.to_string(),
+                        _ => sanitize_file_name(value, ""),
+                    }
+                }
+            })
+            .collect();
+
+        rendered
+            .split('/')
+            .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+            .collect::<Vec<&str>>()
+            .join("/")
+    }
+}
+
+/// 一次导出用到的所有模板，没有配置的项为None，使用默认的目录结构
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputTemplates {
+    doc: Option<PathTemplate>,
+    /// 知识库名称或slug -> 文档模板
+    books: HashMap<String, PathTemplate>,
+    asset: Option<PathTemplate>,
+    resource: Option<PathTemplate>,
+    /// 本次导出的日期，对应 `{date}`
+    date: String,
+}
+
+impl OutputTemplates {
+    /// 校验并编译配置中的模板
+    /// # Arguments
+    /// * config - 模板配置
+    /// * date - 本次导出的日期，YYYY-MM-DD
+    pub fn compile(config: &PathTemplates, date: &str) -> Result<Self, String> {
+        let compile = |source: &str, kind| -> Result<Option<PathTemplate>, String> {
+            if source.is_empty() {
+                Ok(None)
+            } else {
+                PathTemplate::compile(source, kind).map(Some)
+            }
+        };
+
+        let mut books = HashMap::new();
+        for (book, source) in &config.books {
+            books.insert(
+                book.to_string(),
+                PathTemplate::compile(source, TemplateKind::Doc)?,
+            );
+        }
+
+        Ok(OutputTemplates {
+            doc: compile(&config.doc, TemplateKind::Doc)?,
+            books,
+            asset: compile(&config.asset, TemplateKind::Asset)?,
+            resource: compile(&config.resource, TemplateKind::Resource)?,
+            date: date.to_string(),
+        })
+    }
+
+    /// 是否配置了文档模板
+    pub fn has_doc_template(&self) -> bool {
+        self.doc.is_some() || !self.books.is_empty()
+    }
+
+    /// 是否配置了附件模板
+    pub fn has_asset_template(&self) -> bool {
+        self.asset.is_some()
+    }
+
+    /// 是否配置了资源模板
+    pub fn has_resource_template(&self) -> bool {
+        self.resource.is_some()
+    }
+
+    /// 文档相对于输出目录的保存路径，知识库单独配置的模板优先
+    pub fn doc_path(&self, item: &TreeNone) -> Option<String> {
+        let book = Self::book_name(item);
+        let template = self
+            .books
+            .get(book)
+            .or_else(|| self.books.get(&item.p_slug))
+            .or(self.doc.as_ref())?;
+
+        let mut vars = self.node_vars(item);
+        vars.insert("slug", Self::or_uuid(&item.url, item));
+        vars.insert("uuid", item.uuid.to_string());
+        vars.insert("author", item.author.to_string());
+        vars.insert(
+            "updated",
+            item.updated_at.chars().take(10).collect::<String>(),
+        );
+        vars.insert("ext", "md".to_string());
+        Some(template.render(&vars))
+    }
+
+    /// 文档中的附件相对于输出目录的保存路径
+    /// # Arguments
+    /// * item - 附件所在的文档
+    /// * name - 附件文件名
+    pub fn asset_path(&self, item: &TreeNone, name: &str) -> Option<String> {
+        let template = self.asset.as_ref()?;
+        let mut vars = self.node_vars(item);
+        vars.insert("name", name.to_string());
+        Some(template.render(&vars))
+    }
+
+    /// 资源相对于资源输出目录的保存路径
+    /// # Arguments
+    /// * folder - 资源所在的目录，第一级为资源库名称
+    /// * file - 不带扩展名的文件名
+    /// * ext - 扩展名
+    pub fn resource_path(&self, folder: &str, file: &str, ext: &str) -> Option<String> {
+        let template = self.resource.as_ref()?;
+        let vars = HashMap::from([
+            ("folder", folder.to_string()),
+            ("file", file.to_string()),
+            ("ext", ext.to_string()),
+            ("date", self.date.to_string()),
+        ]);
+        Some(template.render(&vars))
+    }
+
+    /// 文档和附件共用的变量
+    fn node_vars(&self, item: &TreeNone) -> HashMap<&'static str, String> {
+        let segments: Vec<&str> = item.full_path.split('/').collect();
+        let path = if segments.len() > 2 {
+            segments[1..segments.len() - 1].join("/")
+        } else {
+            "".to_string()
+        };
+        HashMap::from([
+            ("space", item.user.to_string()),
+            ("book", Self::book_name(item).to_string()),
+            ("book_slug", item.p_slug.to_string()),
+            ("path", path),
+            (
+                "title",
+                segments.last().copied().unwrap_or_default().to_string(),
+            ),
+            ("date", self.date.to_string()),
+        ])
+    }
+
+    fn book_name(item: &TreeNone) -> &str {
+        item.full_path.split('/').next().unwrap_or_default()
+    }
+
+    /// TITLE分组没有slug，使用uuid
+    fn or_uuid(value: &str, item: &TreeNone) -> String {
+        if value.is_empty() {
+            item.uuid.to_string()
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+/// 从文件所在目录到另一个文件的相对路径，路径都相对于同一个根目录
+/// # Arguments
+/// * from_file - 引用方文件
+/// * to_file - 被引用的文件
+pub fn relative_path(from_file: &str, to_file: &str) -> String {
+    let from: Vec<&str> = from_file.split('/').collect();
+    let from_dir = &from[..from.len() - 1];
+    let to: Vec<&str> = to_file.split('/').collect();
+
+    let common = from_dir
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts: Vec<&str> = vec![".."; from_dir.len() - common];
+    parts.extend(&to[common..]);
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::fixtures::tree_node;
+
+    fn doc(full_path: &str, url: &str) -> TreeNone {
+        TreeNone {
+            uuid: format!("uuid-{}", url),
+            title: "".to_string(),
+            p_slug: "rust".to_string(),
+            user: "vannvan".to_string(),
+            url: url.to_string(),
+            updated_at: "2023-09-01T08:00:00.000Z".to_string(),
+            author: "vannvan".to_string(),
+            ..tree_node(full_path)
+        }
+    }
+
+    #[test]
+    fn test_compile() {
+        assert!(PathTemplate::compile("{book}/{path}/{title}.{ext}", TemplateKind::Doc).is_ok());
+        assert!(PathTemplate::compile("{folder}/{file}.{ext}", TemplateKind::Resource).is_ok());
+        // 未知变量、未闭合、超出输出目录
+        assert!(PathTemplate::compile("{book}/{name}.md", TemplateKind::Doc).is_err());
+        assert!(PathTemplate::compile("{book}/{title.md", TemplateKind::Doc).is_err());
+        assert!(PathTemplate::compile("{book}/title}.md", TemplateKind::Doc).is_err());
+        assert!(PathTemplate::compile("../{title}.md", TemplateKind::Doc).is_err());
+        assert!(PathTemplate::compile("/tmp/{title}.md", TemplateKind::Doc).is_err());
+        // 必然重名
+        assert!(PathTemplate::compile("{book}/{path}.md", TemplateKind::Doc).is_err());
+        assert!(PathTemplate::compile("{folder}.{ext}", TemplateKind::Resource).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let config = PathTemplates {
+            doc: "{space}/{book_slug}/{path}/{title}.{ext}".to_string(),
+            books: HashMap::from([(
+                "Rust 笔记".to_string(),
+                "{date}/{book}/{updated}-{slug}.{ext}".to_string(),
+            )]),
+            asset: "assets/{book_slug}/{name}".to_string(),
+            resource: "{date}/{folder}/{file}.{ext}".to_string(),
+        };
+        let templates = OutputTemplates::compile(&config, "2026-10-21").unwrap();
+        assert!(templates.has_doc_template());
+
+        let nested = doc("Rust 笔记/基础/所有权", "c");
+        assert_eq!(
+            templates.doc_path(&nested).unwrap(),
+            "2026-10-21/Rust 笔记/2023-09-01-c.md"
+        );
+
+        let mut other = doc("Go/入门", "d");
+        other.p_slug = "go".to_string();
+        // 没有上级目录时 {path} 为空，不会留下空的层级
+        assert_eq!(templates.doc_path(&other).unwrap(), "vannvan/go/入门.md");
+        assert_eq!(
+            templates.asset_path(&other, "a.png").unwrap(),
+            "assets/go/a.png"
+        );
+        assert_eq!(
+            templates.resource_path("资源库/图片", "a", "png").unwrap(),
+            "2026-10-21/资源库/图片/a.png"
+        );
+
+        let empty = OutputTemplates::compile(&PathTemplates::default(), "2026-10-21").unwrap();
+        assert!(!empty.has_doc_template());
+        assert_eq!(empty.doc_path(&nested), None);
+        assert_eq!(empty.resource_path("资源库", "a", "png"), None);
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path("book/分组/README.md", "book/分组/父文档/README.md"),
+            "父文档/README.md"
+        );
+        assert_eq!(
+            relative_path("rust/分组.md", "2023/rust/文档.md"),
+            "../2023/rust/文档.md"
+        );
+        assert_eq!(relative_path("index.md", "a/b.md"), "a/b.md");
+    }
+}
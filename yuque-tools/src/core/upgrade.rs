@@ -0,0 +1,384 @@
+/*
+ * Description: 自我更新，从版本发布信息中获取当前平台的安装包，校验后原子替换正在运行的程序
+ * Created: 2026-10-19 01:27:58
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    env, fs,
+    io::{Cursor, Read},
+    path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use semver::Version;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    libs::{constants::GLOBAL_CONFIG, log::Log, request::Request, tools::sha256_hex},
+    t,
+};
+
+/// 程序文件名
+#[cfg(windows)]
+const BIN_NAME: &str = "ytool.exe";
+#[cfg(not(windows))]
+const BIN_NAME: &str = "ytool";
+
+/// 版本发布信息，与GitHub release接口的格式一致
+#[derive(Deserialize, Debug)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+pub struct Upgrade;
+
+impl Upgrade {
+    /// 更新启动程序
+    /// # Arguments
+    /// * check - 只检查并报告是否有新版本，不做替换
+    pub async fn start(check: bool) -> Result<(), &'static str> {
+        let exe = env::current_exe()
+            .and_then(|exe| exe.canonicalize())
+            .map_err(|_| t!("upgrade-exe-not-found"))?;
+        Self::run(
+            &GLOBAL_CONFIG.release_feed,
+            env!("CARGO_PKG_VERSION"),
+            &exe,
+            check,
+        )
+        .await
+    }
+
+    /// 检查并更新
+    /// # Arguments
+    /// * feed - 版本发布信息地址
+    /// * current - 当前版本
+    /// * exe - 要替换的程序文件
+    /// * check - 只检查不替换
+    pub async fn run(
+        feed: &str,
+        current: &str,
+        exe: &Path,
+        check: bool,
+    ) -> Result<(), &'static str> {
+        Log::info(t!("upgrade-checking"));
+        let content = Request::fetch_public(feed).await.map_err(|err| {
+            debug!("{}", err);
+            t!("upgrade-feed-failed")
+        })?;
+        let release: Release = serde_json::from_slice(&content).map_err(|err| {
+            debug!("{}", err);
+            t!("upgrade-feed-parse-failed")
+        })?;
+
+        let latest = Self::parse_version(&release.tag_name).ok_or(t!("upgrade-version-invalid"))?;
+        let current = Self::parse_version(current).ok_or(t!("upgrade-version-invalid"))?;
+        if latest <= current {
+            Log::success(&t!("upgrade-up-to-date", version = current));
+            return Ok(());
+        }
+
+        Log::info(&t!("upgrade-found", latest = latest, current = current));
+        if !release.body.trim().is_empty() {
+            println!("{}", release.body.trim());
+        }
+
+        let prefix = Self::platform_asset_prefix();
+        let asset = Self::find_asset(&release, &prefix).ok_or(t!("upgrade-no-asset"))?;
+        if check {
+            Log::info(&t!("upgrade-asset", url = asset.browser_download_url));
+            return Ok(());
+        }
+
+        // 没有校验文件时不更新，避免替换成下载不完整或损坏的程序
+        // 校验文件和安装包来自同一个release，只能发现传输中的损坏，不能防止release本身被篡改
+        let checksum_asset =
+            Self::find_checksum(&release, &asset.name).ok_or(t!("upgrade-no-checksum"))?;
+        let checksum_content = Request::fetch_public(&checksum_asset.browser_download_url)
+            .await
+            .map_err(|_| t!("upgrade-checksum-download-failed"))?;
+        let expected =
+            Self::parse_checksum(&String::from_utf8_lossy(&checksum_content), &asset.name)
+                .ok_or(t!("upgrade-checksum-missing"))?;
+
+        Log::info(&t!("upgrade-downloading", name = asset.name));
+        let archive = Request::fetch_public(&asset.browser_download_url)
+            .await
+            .map_err(|err| {
+                debug!("{}", err);
+                t!("upgrade-download-failed")
+            })?;
+        if sha256_hex(&archive) != expected {
+            return Err(t!("upgrade-checksum-mismatch"));
+        }
+
+        let binary = Self::extract_binary(&archive, &asset.name).map_err(|err| {
+            debug!("{}", err);
+            t!("upgrade-extract-failed")
+        })?;
+        Self::replace_exe(exe, &binary).map_err(|err| {
+            debug!("{}", err);
+            t!("upgrade-replace-failed")
+        })?;
+
+        Log::success(&t!("upgrade-done", version = latest));
+        Ok(())
+    }
+
+    /// 从标签中解析版本号，兼容 v1.0.0、ytool-v1.0.0 等写法
+    pub fn parse_version(tag: &str) -> Option<Version> {
+        let version = tag.trim_start_matches(|c: char| !c.is_ascii_digit());
+        Version::parse(version).ok()
+    }
+
+    /// 当前平台安装包的名称前缀，如 ytool-macOS-aarch64，和发布流程中的命名一致
+    pub fn platform_asset_prefix() -> String {
+        let os = match env::consts::OS {
+            "macos" => "macOS",
+            "windows" => "Windows",
+            "linux" => "Linux",
+            "freebsd" => "FreeBSD",
+            other => other,
+        };
+        format!("ytool-{}-{}", os, env::consts::ARCH)
+    }
+
+    fn find_asset<'a>(release: &'a Release, prefix: &str) -> Option<&'a ReleaseAsset> {
+        release.assets.iter().find(|asset| {
+            asset.name.starts_with(prefix)
+                && (asset.name.ends_with(".tar.gz") || asset.name.ends_with(".zip"))
+        })
+    }
+
+    /// 安装包的校验文件，单独的 xxx.sha256 或汇总的 SHA256SUMS
+    fn find_checksum<'a>(release: &'a Release, asset_name: &str) -> Option<&'a ReleaseAsset> {
+        let single = format!("{}.sha256", asset_name);
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == single)
+            .or_else(|| {
+                release
+                    .assets
+                    .iter()
+                    .find(|asset| asset.name == "SHA256SUMS")
+            })
+    }
+
+    /// 从 sha256sum 格式的内容中取出该文件的校验值，只有一行且没有文件名时直接使用
+    pub fn parse_checksum(content: &str, asset_name: &str) -> Option<String> {
+        let lines: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        for line in &lines {
+            let mut parts = line.split_whitespace();
+            let (hash, name) = (parts.next()?, parts.next());
+            match name {
+                Some(name) if name.trim_start_matches('*') == asset_name => {
+                    return Some(hash.to_lowercase())
+                }
+                None if lines.len() == 1 => return Some(hash.to_lowercase()),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// 从安装包(tar.gz/zip)中取出程序
+    pub fn extract_binary(archive: &[u8], asset_name: &str) -> Result<Vec<u8>, String> {
+        let mut binary = vec![];
+        if asset_name.ends_with(".zip") {
+            let mut zip = zip::ZipArchive::new(Cursor::new(archive)).map_err(|e| e.to_string())?;
+            for i in 0..zip.len() {
+                let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+                if Self::is_binary_entry(Path::new(entry.name())) {
+                    entry.read_to_end(&mut binary).map_err(|e| e.to_string())?;
+                    return Ok(binary);
+                }
+            }
+        } else {
+            let mut tar = tar::Archive::new(GzDecoder::new(archive));
+            for entry in tar.entries().map_err(|e| e.to_string())? {
+                let mut entry = entry.map_err(|e| e.to_string())?;
+                let path = entry.path().map_err(|e| e.to_string())?.to_path_buf();
+                if Self::is_binary_entry(&path) {
+                    entry.read_to_end(&mut binary).map_err(|e| e.to_string())?;
+                    return Ok(binary);
+                }
+            }
+        }
+        Err(format!("安装包中没有 {}", BIN_NAME))
+    }
+
+    fn is_binary_entry(path: &Path) -> bool {
+        path.file_name().is_some_and(|name| name == BIN_NAME)
+    }
+
+    /// 原子替换程序：先写入同目录下的临时文件，再重命名覆盖
+    /// Windows不能覆盖正在运行的程序，但可以重命名，所以先把旧程序改名
+    pub fn replace_exe(exe: &Path, binary: &[u8]) -> std::io::Result<()> {
+        let dir = exe.parent().unwrap_or(Path::new("."));
+        let file_name = exe
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or(BIN_NAME.to_string());
+        let temp: PathBuf = dir.join(format!(".{}.new", file_name));
+        fs::write(&temp, binary)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(exe)
+                .map(|meta| meta.permissions().mode())
+                .unwrap_or(0o755);
+            fs::set_permissions(&temp, fs::Permissions::from_mode(mode | 0o111))?;
+        }
+        #[cfg(windows)]
+        let old = {
+            let old = dir.join(format!("{}.old", file_name));
+            let _ = fs::remove_file(&old);
+            fs::rename(exe, &old)?;
+            old
+        };
+
+        if let Err(err) = fs::rename(&temp, exe) {
+            let _ = fs::remove_file(&temp);
+            // 新程序没有放到位，把旧程序改回原名，不能留下缺失的程序
+            #[cfg(windows)]
+            let _ = fs::rename(&old, exe);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn tar_gz(binary: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(binary.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, BIN_NAME, binary).unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// 模拟的发布服务，checksum为空时使用正确的校验值
+    async fn release_server(archive: &[u8], checksum: Option<&str>) -> MockServer {
+        let server = MockServer::start().await;
+        let asset = format!("{}.tar.gz", Upgrade::platform_asset_prefix());
+        let checksum = checksum
+            .map(|checksum| checksum.to_string())
+            .unwrap_or(sha256_hex(archive));
+
+        Mock::given(method("GET"))
+            .and(path("/latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tag_name": "v9.0.0",
+                "body": "修复若干问题",
+                "assets": [
+                    { "name": asset, "browser_download_url": format!("{}/{}", server.uri(), asset) },
+                    { "name": "SHA256SUMS", "browser_download_url": format!("{}/SHA256SUMS", server.uri()) }
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/{}", asset)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(archive.to_vec()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/SHA256SUMS"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                "{}  ytool-other.zip\n{}  {}\n",
+                "0".repeat(64),
+                checksum,
+                asset
+            )))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            Upgrade::parse_version("v1.0.5"),
+            Some(Version::new(1, 0, 5))
+        );
+        assert_eq!(
+            Upgrade::parse_version("ytool-v1.2.0"),
+            Some(Version::new(1, 2, 0))
+        );
+        assert!(Upgrade::parse_version("latest").is_none());
+        assert!(Upgrade::parse_version("v1.1.0").unwrap() > Version::new(1, 0, 4));
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let sums = "abc123  ytool-macOS-x86_64.tar.gz\nDEF456 *ytool-Windows-x86_64.zip\n";
+        assert_eq!(
+            Upgrade::parse_checksum(sums, "ytool-Windows-x86_64.zip"),
+            Some("def456".to_string())
+        );
+        assert!(Upgrade::parse_checksum(sums, "ytool-Linux-x86_64.tar.gz").is_none());
+        assert_eq!(
+            Upgrade::parse_checksum("abc123\n", "ytool-macOS-x86_64.tar.gz"),
+            Some("abc123".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrade() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join(BIN_NAME);
+        fs::write(&exe, "old").unwrap();
+        let archive = tar_gz(b"new");
+
+        // 只检查时不替换
+        let server = release_server(&archive, None).await;
+        let feed = format!("{}/latest", server.uri());
+        Upgrade::run(&feed, "1.0.4", &exe, true).await.unwrap();
+        assert_eq!(fs::read(&exe).unwrap(), b"old");
+
+        // 已是最新版本
+        Upgrade::run(&feed, "9.0.0", &exe, false).await.unwrap();
+        assert_eq!(fs::read(&exe).unwrap(), b"old");
+
+        Upgrade::run(&feed, "1.0.4", &exe, false).await.unwrap();
+        assert_eq!(fs::read(&exe).unwrap(), b"new");
+        assert!(!dir.path().join(format!(".{}.new", BIN_NAME)).exists());
+
+        // 校验值不符时不替换
+        fs::write(&exe, "old").unwrap();
+        let server = release_server(&archive, Some(&"f".repeat(64))).await;
+        let feed = format!("{}/latest", server.uri());
+        assert!(Upgrade::run(&feed, "1.0.4", &exe, false).await.is_err());
+        assert_eq!(fs::read(&exe).unwrap(), b"old");
+    }
+}
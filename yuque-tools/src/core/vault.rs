@@ -0,0 +1,534 @@
+/*
+ * Description: 导出为Obsidian笔记库或Logseq图谱，文档间链接转为wikilink，附件保存到本地
+ * Created: 2026-10-19 00:41:37
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use regex::{Captures, Regex};
+
+use crate::{
+    core::{archive::ExportArchive, template::OutputTemplates},
+    libs::{
+        cipher::{Cipher, ENCRYPTED_EXT},
+        constants::schema::{ExportTarget, TreeNone},
+        file::File,
+        log::Log,
+        request::Request,
+        tools,
+    },
+    t,
+};
+
+/// Obsidian默认的附件目录
+const OBSIDIAN_ATTACHMENT_DIR: &str = "attachments";
+/// Logseq的页面目录
+const LOGSEQ_PAGES_DIR: &str = "pages";
+/// Logseq的附件目录
+const LOGSEQ_ASSETS_DIR: &str = "assets";
+
+/// 一次导出中所有文档共用的信息
+#[derive(Debug, Clone)]
+pub struct VaultContext {
+    pub target: ExportTarget,
+    /// 文档输出目录，即笔记库根目录
+    pub output_dir: String,
+    /// 附件目录，相对于输出目录
+    pub attachment_dir: String,
+    /// 文档key -> wikilink目标
+    pub links: HashMap<String, String>,
+    /// 语雀域名，用于记录文档原地址
+    pub host: String,
+    /// 输出路径模板
+    pub templates: OutputTemplates,
+    /// 导出为归档时，文档和附件写入归档而不是输出目录
+    pub archive: Option<Arc<Mutex<ExportArchive>>>,
+    /// 逐个文件加密时的加密方式，文件名加上 .age，导出为归档时由归档整体加密
+    pub cipher: Option<Cipher>,
+}
+
+pub struct Vault;
+
+impl Vault {
+    /// 文档在链接表中的key，文档为 知识库slug/文档slug，分组为uuid
+    pub fn doc_key(node: &TreeNone) -> String {
+        if node.node_type == "DOC" {
+            format!("{}/{}", node.p_slug, node.url)
+        } else {
+            format!("title:{}", node.uuid)
+        }
+    }
+
+    /// Logseq的页面文件，层级用 ___ 连接，对应Logseq的命名空间
+    pub fn logseq_page_file(full_path: &str) -> String {
+        format!("{}/{}.md", LOGSEQ_PAGES_DIR, full_path.replace('/', "___"))
+    }
+
+    /// 构造导出上下文
+    /// # Arguments
+    /// * target - 导出目标
+    /// * output_dir - 文档输出目录
+    /// * attachment_dir - 配置的Obsidian附件目录
+    /// * docs - 会出现在笔记库中的文档及其相对于输出目录的保存路径
+    /// * host - 语雀域名
+    /// * templates - 输出路径模板，附件模板只对Obsidian生效
+    pub fn build_context(
+        target: ExportTarget,
+        output_dir: &str,
+        attachment_dir: &str,
+        docs: Vec<(&TreeNone, String)>,
+        host: &str,
+        templates: OutputTemplates,
+    ) -> VaultContext {
+        let attachment_dir = match target {
+            ExportTarget::Logseq => LOGSEQ_ASSETS_DIR.to_string(),
+            _ if attachment_dir.is_empty() => OBSIDIAN_ATTACHMENT_DIR.to_string(),
+            _ => attachment_dir.trim_matches('/').to_string(),
+        };
+
+        let mut links = HashMap::new();
+        match target {
+            ExportTarget::Markdown => {}
+            // Logseq按页面名称引用
+            ExportTarget::Logseq => {
+                for (node, _) in &docs {
+                    links.insert(Self::doc_key(node), node.full_path.to_string());
+                }
+            }
+            // Obsidian文件名唯一时只用文件名，否则用完整路径
+            ExportTarget::Obsidian => {
+                let paths: Vec<(String, String)> = docs
+                    .iter()
+                    .map(|(node, path)| {
+                        (
+                            Self::doc_key(node),
+                            path.trim_end_matches(".md").to_string(),
+                        )
+                    })
+                    .collect();
+                let mut name_count: HashMap<&str, usize> = HashMap::new();
+                for (_, path) in &paths {
+                    *name_count.entry(Self::base_name(path)).or_insert(0) += 1;
+                }
+                for (key, path) in &paths {
+                    let name = Self::base_name(path);
+                    let link = if name_count[name] == 1 { name } else { path };
+                    links.insert(key.to_string(), link.to_string());
+                }
+            }
+        }
+
+        VaultContext {
+            target,
+            output_dir: output_dir.to_string(),
+            attachment_dir,
+            links,
+            host: host.to_string(),
+            templates,
+            archive: None,
+            cipher: None,
+        }
+    }
+
+    /// 转换文档内容：头部信息、文档间链接和高亮块
+    pub fn convert(content: &str, item: &TreeNone, ctx: &VaultContext) -> String {
+        let body = Self::convert_links(content, ctx);
+        let body = Self::convert_callouts(&body, ctx.target);
+        format!("{}{}", Self::front_matter(item, ctx), body)
+    }
+
+    /// 分组的索引页，列出下一级文档
+    pub fn group_index(item: &TreeNone, ctx: &VaultContext) -> String {
+        let mut content = Self::front_matter(item, ctx);
+        for child in &item.children {
+            if let Some(link) = ctx.links.get(&Self::doc_key(child)) {
+                content.push_str(&format!("- [[{}]]\n", link));
+            }
+        }
+        content
+    }
+
+    /// Obsidian为YAML front matter，Logseq为页面属性
+    /// 标题作为别名，知识库名称作为标签
+    fn front_matter(item: &TreeNone, ctx: &VaultContext) -> String {
+        let book_name = item.full_path.split('/').next().unwrap_or_default();
+        let tag = Self::to_tag(book_name);
+        let yuque_url = if item.node_type == "DOC" {
+            format!("{}/{}/{}/{}", ctx.host, item.user, item.p_slug, item.url)
+        } else {
+            "".to_string()
+        };
+
+        match ctx.target {
+            ExportTarget::Markdown => "".to_string(),
+            ExportTarget::Obsidian => {
+                let mut lines = vec![
+                    "---".to_string(),
+                    "aliases:".to_string(),
+                    format!("  - {}", Self::yaml_string(&item.title)),
+                    "tags:".to_string(),
+                    format!("  - {}", Self::yaml_string(&tag)),
+                ];
+                if !yuque_url.is_empty() {
+                    lines.push(format!("yuque: {}", Self::yaml_string(&yuque_url)));
+                }
+                if !item.updated_at.is_empty() {
+                    lines.push(format!("updated: {}", Self::yaml_string(&item.updated_at)));
+                }
+                lines.push("---".to_string());
+                format!("{}\n\n", lines.join("\n"))
+            }
+            ExportTarget::Logseq => {
+                let mut lines = vec![format!("title:: {}", item.full_path)];
+                // 页面名称是完整路径，原标题和文件名不同时才加别名，避免同名文档的别名冲突
+                if Self::base_name(&item.full_path) != item.title {
+                    lines.push(format!("alias:: {}", item.title));
+                }
+                lines.push(format!("tags:: {}", tag));
+                if !yuque_url.is_empty() {
+                    lines.push(format!("yuque:: {}", yuque_url));
+                }
+                format!("{}\n\n", lines.join("\n"))
+            }
+        }
+    }
+
+    /// 指向导出范围内文档的链接改为wikilink，其余链接保持不变
+    pub fn convert_links(content: &str, ctx: &VaultContext) -> String {
+        let link = Regex::new(
+            r"\[([^\]]*)\]\((?:https?://[^/\s)]+)?/[^/\s)]+/([^/\s)]+)/([\w-]+)(?:[?#][^)\s]*)?\)",
+        )
+        .unwrap();
+
+        link.replace_all(content, |caps: &Captures| {
+            let key = format!("{}/{}", &caps[2], &caps[3]);
+            let text = &caps[1];
+            match ctx.links.get(&key) {
+                Some(target) if text.is_empty() || text == Self::base_name(target) => {
+                    format!("[[{}]]", target)
+                }
+                Some(target) => match ctx.target {
+                    ExportTarget::Logseq => format!("[{}]([[{}]])", text, target),
+                    _ => format!("[[{}|{}]]", target, text),
+                },
+                None => caps[0].to_string(),
+            }
+        })
+        .to_string()
+    }
+
+    /// 语雀的高亮块(:::tips ... :::)转为Obsidian callout或Logseq的提示块
+    pub fn convert_callouts(content: &str, target: ExportTarget) -> String {
+        let start = Regex::new(r"^:::(\w+)\s*$").unwrap();
+        let mut lines: Vec<String> = vec![];
+        let mut in_code = false;
+        let mut callout: Option<String> = None;
+
+        for line in content.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+            }
+
+            if !in_code && callout.is_none() {
+                if let Some(caps) = start.captures(line) {
+                    let kind = caps[1].to_string();
+                    match target {
+                        ExportTarget::Logseq => {
+                            lines.push(format!("#+BEGIN_{}", Self::logseq_callout(&kind)))
+                        }
+                        _ => lines.push(format!("> [!{}]", Self::obsidian_callout(&kind))),
+                    }
+                    callout = Some(kind);
+                    continue;
+                }
+            }
+
+            if let Some(kind) = &callout {
+                if !in_code && line.trim() == ":::" {
+                    if target == ExportTarget::Logseq {
+                        lines.push(format!("#+END_{}", Self::logseq_callout(kind)));
+                    }
+                    callout = None;
+                    continue;
+                }
+                match target {
+                    ExportTarget::Logseq => lines.push(line.to_string()),
+                    _ if line.is_empty() => lines.push(">".to_string()),
+                    _ => lines.push(format!("> {}", line)),
+                }
+                continue;
+            }
+
+            lines.push(line.to_string());
+        }
+
+        let mut result = lines.join("\n");
+        if content.ends_with('\n') {
+            result.push('\n');
+        }
+        result
+    }
+
+    /// 下载文档中的图片到附件目录，引用改为本地附件，下载失败的保留原地址
+    /// 配置了附件模板时按模板保存，Obsidian中以相对于笔记库的完整路径引用
+    pub async fn save_attachments(content: &str, item: &TreeNone, ctx: &VaultContext) -> String {
+        let f = File::new();
+        let mut names: HashMap<String, String> = HashMap::new();
+        let book = item.full_path.split('/').next().unwrap_or_default();
+
+        for url in Self::find_attachments(content) {
+            let name = Self::attachment_name(&url);
+            // 相对于输出目录的保存路径
+            let (relative_path, name) = match ctx.templates.asset_path(item, &name) {
+                Some(path) if ctx.target == ExportTarget::Obsidian => (path.to_string(), path),
+                _ => (format!("{}/{}", ctx.attachment_dir, name), name),
+            };
+            let file_path = format!("{}/{}", ctx.output_dir, relative_path);
+            let is_saved = match &ctx.archive {
+                Some(archive) => archive.lock().unwrap().contains(book, &relative_path),
+                None if ctx.cipher.is_some() => {
+                    f.exists(&format!("{}{}", file_path, ENCRYPTED_EXT))
+                }
+                None => f.exists(&file_path),
+            };
+            if !is_saved {
+                let download_url = url.split('#').next().unwrap_or(&url);
+                match Request::fetch_bytes(download_url).await {
+                    Ok(bytes) => {
+                        let saved = match &ctx.archive {
+                            Some(archive) => archive
+                                .lock()
+                                .unwrap()
+                                .add(book, &relative_path, &bytes, "", "")
+                                .is_ok(),
+                            None => {
+                                if let Some(dir) = std::path::Path::new(&file_path).parent() {
+                                    let _ = f.mkdir(&dir.to_string_lossy());
+                                }
+                                match &ctx.cipher {
+                                    Some(cipher) => cipher.write_file(&file_path, &bytes).is_ok(),
+                                    None => std::fs::write(&file_path, bytes).is_ok(),
+                                }
+                            }
+                        };
+                        if !saved {
+                            Log::warn(&t!("vault-attachment-save-failed", file = file_path));
+                            continue;
+                        }
+                    }
+                    Err(_) => {
+                        Log::warn(&t!("vault-attachment-download-failed", url = url));
+                        continue;
+                    }
+                }
+            }
+            names.insert(url, name);
+        }
+
+        Self::replace_attachments(content, &names, ctx)
+    }
+
+    /// 文档中的远程图片地址
+    pub fn find_attachments(content: &str) -> Vec<String> {
+        let image = Regex::new(r"!\[[^\]]*\]\((https?://[^)\s]+)[^)]*\)").unwrap();
+        let mut urls: Vec<String> = vec![];
+        for caps in image.captures_iter(content) {
+            let url = caps[1].to_string();
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// 附件文件名，取地址中的文件名部分
+    pub fn attachment_name(url: &str) -> String {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        // [ ] # ^ 在wikilink中有特殊含义
+        let name = tools::sanitize_file_name(path.rsplit('/').next().unwrap_or(path), "")
+            .replace(['[', ']', '#', '^'], "");
+        // 不同地址的图片可能同名，文件名后加上地址的短哈希
+        let hash = &tools::sha256_hex(path.as_bytes())[..8];
+        match name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => format!("{}-{}.{}", stem, hash, ext),
+            _ => format!("{}-{}", name, hash),
+        }
+    }
+
+    /// 图片引用改为本地附件，Obsidian为 ![[附件]]，Logseq为相对路径
+    fn replace_attachments(
+        content: &str,
+        names: &HashMap<String, String>,
+        ctx: &VaultContext,
+    ) -> String {
+        let image = Regex::new(r"!\[([^\]]*)\]\((https?://[^)\s]+)[^)]*\)").unwrap();
+        image
+            .replace_all(content, |caps: &Captures| match names.get(&caps[2]) {
+                Some(name) => match ctx.target {
+                    ExportTarget::Logseq => {
+                        format!("![{}](../{}/{})", &caps[1], ctx.attachment_dir, name)
+                    }
+                    _ => format!("![[{}]]", name),
+                },
+                None => caps[0].to_string(),
+            })
+            .to_string()
+    }
+
+    fn obsidian_callout(kind: &str) -> &'static str {
+        match kind {
+            "tips" => "tip",
+            "info" => "info",
+            "warning" => "warning",
+            "danger" => "danger",
+            "success" => "success",
+            _ => "note",
+        }
+    }
+
+    fn logseq_callout(kind: &str) -> &'static str {
+        match kind {
+            "tips" | "success" => "TIP",
+            "warning" => "WARNING",
+            "danger" => "CAUTION",
+            _ => "NOTE",
+        }
+    }
+
+    fn base_name(path: &str) -> &str {
+        path.rsplit('/').next().unwrap_or(path)
+    }
+
+    /// 知识库名称转为标签，标签中不能有空格和标点
+    fn to_tag(name: &str) -> String {
+        name.chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect::<String>()
+            .trim_matches('-')
+            .to_string()
+    }
+
+    fn yaml_string(text: &str) -> String {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::fixtures::tree_node;
+
+    fn node(title: &str, full_path: &str, node_type: &str, url: &str) -> TreeNone {
+        TreeNone {
+            uuid: format!("uuid-{}", url),
+            node_type: node_type.to_string(),
+            title: title.to_string(),
+            p_slug: "book".to_string(),
+            user: "vannvan".to_string(),
+            url: url.to_string(),
+            updated_at: "2023-09-01T08:00:00.000Z".to_string(),
+            ..tree_node(full_path)
+        }
+    }
+
+    fn context(target: ExportTarget) -> VaultContext {
+        let a = node("入门", "Rust 笔记/基础/入门", "DOC", "a");
+        let b = node("入门", "Rust 笔记/进阶/入门", "DOC", "b");
+        let c = node("所有权?", "Rust 笔记/基础/所有权", "DOC", "c");
+        Vault::build_context(
+            target,
+            "./docs",
+            "",
+            vec![
+                (&a, "Rust 笔记/基础/入门.md".to_string()),
+                (&b, "Rust 笔记/进阶/入门.md".to_string()),
+                (&c, "Rust 笔记/基础/所有权.md".to_string()),
+            ],
+            "https://www.yuque.com",
+            OutputTemplates::default(),
+        )
+    }
+
+    #[test]
+    fn test_convert_links() {
+        let content = "[所有权](https://www.yuque.com/vannvan/book/c#sec) [看这里](/vannvan/book/a) [外部](https://www.yuque.com/other/x/y)";
+
+        let ctx = context(ExportTarget::Obsidian);
+        assert_eq!(ctx.attachment_dir, "attachments");
+        assert_eq!(
+            Vault::convert_links(content, &ctx),
+            "[[所有权]] [[Rust 笔记/基础/入门|看这里]] [外部](https://www.yuque.com/other/x/y)"
+        );
+
+        let ctx = context(ExportTarget::Logseq);
+        assert_eq!(
+            Vault::convert_links(content, &ctx),
+            "[[Rust 笔记/基础/所有权]] [看这里]([[Rust 笔记/基础/入门]]) [外部](https://www.yuque.com/other/x/y)"
+        );
+    }
+
+    #[test]
+    fn test_convert_callouts() {
+        let content = ":::tips\n第一行\n\n第二行\n:::\n```\n:::info\n```\n";
+        assert_eq!(
+            Vault::convert_callouts(content, ExportTarget::Obsidian),
+            "> [!tip]\n> 第一行\n>\n> 第二行\n```\n:::info\n```\n"
+        );
+        assert_eq!(
+            Vault::convert_callouts(":::danger\n注意\n:::", ExportTarget::Logseq),
+            "#+BEGIN_CAUTION\n注意\n#+END_CAUTION"
+        );
+    }
+
+    #[test]
+    fn test_front_matter_and_attachments() {
+        let item = node("所有权?", "Rust 笔记/基础/所有权", "DOC", "c");
+        let content = "![图](https://cdn.nlark.com/yuque/0/2023/png/1/168-a.png#averageHue=%23f00)";
+
+        let ctx = context(ExportTarget::Obsidian);
+        let mut names = HashMap::new();
+        for url in Vault::find_attachments(content) {
+            names.insert(url.to_string(), Vault::attachment_name(&url));
+        }
+        let body = Vault::replace_attachments(content, &names, &ctx);
+        assert_eq!(body, "![[168-a-1de23e09.png]]");
+        assert_eq!(
+            Vault::convert(&body, &item, &ctx),
+            "---\naliases:\n  - \"所有权?\"\ntags:\n  - \"Rust-笔记\"\nyuque: \"https://www.yuque.com/vannvan/book/c\"\nupdated: \"2023-09-01T08:00:00.000Z\"\n---\n\n![[168-a-1de23e09.png]]"
+        );
+
+        let ctx = context(ExportTarget::Logseq);
+        assert_eq!(
+            Vault::replace_attachments(content, &names, &ctx),
+            "![图](../assets/168-a-1de23e09.png)"
+        );
+        assert!(Vault::convert("", &item, &ctx)
+            .starts_with("title:: Rust 笔记/基础/所有权\nalias:: 所有权?\ntags:: Rust-笔记\n"));
+        assert_eq!(
+            Vault::logseq_page_file(&item.full_path),
+            "pages/Rust 笔记___基础___所有权.md"
+        );
+    }
+
+    #[test]
+    fn test_attachment_name_unique() {
+        let a = Vault::attachment_name("https://cdn.nlark.com/yuque/0/2023/png/1/168-a.png");
+        let b = Vault::attachment_name("https://cdn.nlark.com/yuque/0/2023/png/2/168-a.png?x=1");
+        assert_eq!(a, "168-a-1de23e09.png");
+        assert_eq!(b, "168-a-ead229a4.png");
+        assert_eq!(Vault::attachment_name("https://a.com/img"), "img-6a5c3bab");
+    }
+}
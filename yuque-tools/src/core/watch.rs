@@ -0,0 +1,358 @@
+/*
+ * Description: 常驻同步，定时检查知识库的变化，只下载有变化的文档
+ * Created: 2026-10-19 00:46:35
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use chrono::{Local, SecondsFormat};
+use regex::Regex;
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use super::{push::Push, scheduler::Scheduler, yuque::YuqueApi};
+use crate::{
+    libs::{
+        constants::{
+            schema::{cache_book::BookItem, PullOptions},
+            GLOBAL_CONFIG,
+        },
+        file::File,
+        log::Log,
+        tools,
+    },
+    t,
+};
+
+/// 两次检查之间知识库的变化，记录文档标题
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct WatchChanges {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+    /// 需要重新下载的文档，key与导出计划一致
+    #[serde(skip)]
+    pub keys: HashSet<String>,
+}
+
+/// 写入状态文件的运行状态
+#[derive(Debug, Serialize)]
+struct WatchStatus {
+    pid: u32,
+    /// running/syncing/stopped
+    state: String,
+    interval_secs: u64,
+    started_at: String,
+    last_check_at: String,
+    next_check_at: String,
+    rounds: u64,
+    last_changes: WatchChanges,
+    last_error: String,
+}
+
+impl WatchStatus {
+    fn save(&self) {
+        let f = File::new();
+        if let Some(parent_dir) = Path::new(&GLOBAL_CONFIG.watch_status_file).parent() {
+            let _ = f.mkdir(&parent_dir.to_string_lossy());
+        }
+        if f.write(
+            &GLOBAL_CONFIG.watch_status_file,
+            serde_json::to_string_pretty(self).unwrap(),
+        )
+        .is_err()
+        {
+            Log::warn(t!("watch-status-write-failed"));
+        }
+    }
+}
+
+pub struct Watch;
+
+impl Watch {
+    /// 启动常驻同步
+    /// # Arguments
+    /// * interval - 检查间隔，如 30s、10m、1h
+    pub async fn start(interval: &str) -> Result<(), &'static str> {
+        let interval = Self::parse_interval(interval)?;
+        match tools::get_user_config() {
+            Ok(config) if !config.toc_range.is_empty() => {}
+            _ => return Err(t!("watch-config-missing")),
+        }
+
+        // 收到退出信号时不打断正在进行的同步，本轮结束后退出
+        let stopping = Arc::new(AtomicBool::new(false));
+        let notify = Arc::new(Notify::new());
+        {
+            let stopping = stopping.clone();
+            let notify = notify.clone();
+            tokio::spawn(async move {
+                Self::wait_shutdown_signal().await;
+                Log::info(t!("watch-stopping"));
+                stopping.store(true, Ordering::SeqCst);
+                notify.notify_one();
+            });
+        }
+
+        let now = Self::now();
+        let mut status = WatchStatus {
+            pid: process::id(),
+            state: "running".to_string(),
+            interval_secs: interval.as_secs(),
+            started_at: now.to_string(),
+            last_check_at: "".to_string(),
+            next_check_at: now,
+            rounds: 0,
+            last_changes: WatchChanges::default(),
+            last_error: "".to_string(),
+        };
+        Log::info(&t!(
+            "watch-start",
+            secs = interval.as_secs(),
+            file = GLOBAL_CONFIG.watch_status_file
+        ));
+
+        while !stopping.load(Ordering::SeqCst) {
+            status.state = "syncing".to_string();
+            status.save();
+
+            match Self::sync_once().await {
+                Ok(changes) => {
+                    status.last_changes = changes;
+                    status.last_error = "".to_string();
+                }
+                Err(err) => {
+                    Log::error(err);
+                    status.last_error = err.to_string();
+                }
+            }
+            status.rounds += 1;
+            status.last_check_at = Self::now();
+            status.next_check_at = (Local::now()
+                + chrono::Duration::from_std(interval).unwrap_or(chrono::Duration::zero()))
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+            status.state = "running".to_string();
+            status.save();
+
+            if stopping.load(Ordering::SeqCst) {
+                break;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = notify.notified() => {}
+            }
+        }
+
+        status.state = "stopped".to_string();
+        status.next_check_at = "".to_string();
+        status.save();
+        Log::success(t!("watch-stopped"));
+        Ok(())
+    }
+
+    /// 检查一次，刷新知识库信息后下载有变化的文档
+    async fn sync_once() -> Result<WatchChanges, &'static str> {
+        // 登录状态按local_expire过期，过期后重新登录
+        Push::ensure_login().await?;
+
+        let old_books = tools::get_cache_books_info().unwrap_or_default();
+        // 只重新获取更新时间有变化的知识库
+        YuqueApi::update_user_bookstacks()
+            .await
+            .map_err(|_| t!("watch-fetch-failed"))?;
+        let new_books = tools::get_cache_books_info().map_err(|_| t!("books-read-failed"))?;
+
+        let changes = Self::diff_books(&old_books, &new_books);
+        Log::info(&t!(
+            "watch-changes",
+            time = Self::now(),
+            added = changes.added.len(),
+            updated = changes.updated.len(),
+            deleted = changes.deleted.len()
+        ));
+        for title in &changes.deleted {
+            Log::warn(&t!("watch-doc-deleted", title = title));
+        }
+
+        // 有变化的文档重新下载，其余文档本地已存在时跳过，本地缺失的也会补上
+        // 导出失败只记入本轮状态，不退出常驻进程
+        Scheduler::handle_inquiry(PullOptions {
+            changed_docs: Some(changes.keys.clone()),
+            ..Default::default()
+        })
+        .await?;
+        Ok(changes)
+    }
+
+    /// 对比前后两次的知识库信息
+    /// 新出现的文档为新增，更新时间、标题或位置变化的为更新
+    pub fn diff_books(old_books: &Vec<BookItem>, new_books: &Vec<BookItem>) -> WatchChanges {
+        let snapshot = |books: &Vec<BookItem>| {
+            let mut docs = HashMap::new();
+            for book in books {
+                for doc in &book.docs {
+                    let key = if doc.node_type == "DOC" {
+                        format!("{}/{}", book.slug, doc.url)
+                    } else {
+                        format!("title:{}", doc.uuid)
+                    };
+                    let version = format!(
+                        "{}|{}|{}|{}",
+                        doc.updated_at, doc.title, doc.parent_uuid, doc.visible
+                    );
+                    docs.insert(key, (doc.title.to_string(), version));
+                }
+            }
+            docs
+        };
+        let old_docs = snapshot(old_books);
+        let new_docs = snapshot(new_books);
+
+        let mut changes = WatchChanges::default();
+        for (key, (title, version)) in &new_docs {
+            match old_docs.get(key) {
+                None => changes.added.push(title.to_string()),
+                Some((_, old_version)) if old_version != version => {
+                    changes.updated.push(title.to_string())
+                }
+                _ => continue,
+            }
+            changes.keys.insert(key.to_string());
+        }
+        for (key, (title, _)) in &old_docs {
+            if !new_docs.contains_key(key) {
+                changes.deleted.push(title.to_string());
+            }
+        }
+
+        changes.added.sort();
+        changes.updated.sort();
+        changes.deleted.sort();
+        changes
+    }
+
+    /// 解析检查间隔，支持s/m/h/d单位，不带单位时为秒
+    pub fn parse_interval(interval: &str) -> Result<Duration, &'static str> {
+        let regex = Regex::new(r"^(\d+)([smhd]?)$").unwrap();
+        let caps = regex
+            .captures(interval.trim())
+            .ok_or(t!("watch-interval-invalid"))?;
+        let value: u64 = caps[1]
+            .parse()
+            .map_err(|_| t!("watch-interval-too-large"))?;
+        let unit = match &caps[2] {
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86400,
+            _ => 1,
+        };
+        if value == 0 {
+            return Err(t!("watch-interval-zero"));
+        }
+        value
+            .checked_mul(unit)
+            .map(Duration::from_secs)
+            .ok_or(t!("watch-interval-too-large"))
+    }
+
+    fn now() -> String {
+        Local::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// 等待Ctrl+C或SIGTERM
+    async fn wait_shutdown_signal() {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::terminate()) {
+                Ok(mut terminate) => {
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => {}
+                        _ = terminate.recv() => {}
+                    }
+                }
+                Err(_) => {
+                    let _ = tokio::signal::ctrl_c().await;
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::{
+        constants::schema::cache_book::DocItem,
+        fixtures::{book_item, doc_item},
+    };
+
+    fn doc(title: &str, url: &str, updated_at: &str) -> DocItem {
+        DocItem {
+            updated_at: updated_at.to_string(),
+            ..doc_item(title, url, "")
+        }
+    }
+
+    fn book(docs: Vec<DocItem>) -> Vec<BookItem> {
+        vec![book_item("book", "slug", docs)]
+    }
+
+    #[test]
+    fn test_diff_books() {
+        let old_books = book(vec![
+            doc("不变", "a", "2023-09-01T00:00:00.000Z"),
+            doc("修改", "b", "2023-09-01T00:00:00.000Z"),
+            doc("删除", "c", "2023-09-01T00:00:00.000Z"),
+        ]);
+        let new_books = book(vec![
+            doc("不变", "a", "2023-09-01T00:00:00.000Z"),
+            doc("修改", "b", "2023-09-02T00:00:00.000Z"),
+            doc("新增", "d", "2023-09-02T00:00:00.000Z"),
+        ]);
+
+        let changes = Watch::diff_books(&old_books, &new_books);
+        assert_eq!(changes.added, vec!["新增"]);
+        assert_eq!(changes.updated, vec!["修改"]);
+        assert_eq!(changes.deleted, vec!["删除"]);
+        assert_eq!(
+            changes.keys,
+            HashSet::from(["slug/b".to_string(), "slug/d".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(Watch::parse_interval("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(Watch::parse_interval("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(Watch::parse_interval("1h"), Ok(Duration::from_secs(3600)));
+        assert!(Watch::parse_interval("0s").is_err());
+        assert!(Watch::parse_interval("10 min").is_err());
+        // 换算成秒后溢出
+        assert_eq!(
+            Watch::parse_interval("999999999999999999d"),
+            Err(t!("watch-interval-too-large"))
+        );
+        assert_eq!(
+            Watch::parse_interval("99999999999999999999"),
+            Err(t!("watch-interval-too-large"))
+        );
+    }
+}
@@ -0,0 +1,319 @@
+/*
+ * Description: 接收语雀的webhook，文档发布/更新后只重新导出该文档
+ * Created: 2026-10-19 00:47:27
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{push::Push, scheduler::Scheduler, watch::Watch, yuque::YuqueApi};
+use crate::{
+    libs::{constants::schema::cache_book::BookItem, log::Log, tools},
+    t,
+};
+
+/// 需要重新导出的事件类型
+const EXPORT_ACTIONS: [&str; 2] = ["publish", "update"];
+
+/// 一次文档变更
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookEvent {
+    /// publish/update/delete/comment_create等
+    pub action: String,
+    pub book_slug: String,
+    pub doc_slug: String,
+    pub title: String,
+}
+
+impl WebhookEvent {
+    /// 和导出计划一致的文档key
+    pub fn doc_key(&self) -> String {
+        format!("{}/{}", self.book_slug, self.doc_slug)
+    }
+}
+
+/// 合并短时间内的多次编辑，同一文档在安静一段时间后才导出
+#[derive(Debug, Default)]
+pub struct Debouncer {
+    pending: HashMap<String, (Instant, WebhookEvent)>,
+}
+
+impl Debouncer {
+    /// 记录事件，同一文档只保留最后一次
+    pub fn push(&mut self, event: WebhookEvent, now: Instant) {
+        self.pending.insert(event.doc_key(), (now, event));
+    }
+
+    /// 取出最后一次事件距今超过window的文档
+    pub fn take_ready(&mut self, now: Instant, window: Duration) -> Vec<WebhookEvent> {
+        let ready: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, (time, _))| now.duration_since(*time) >= window)
+            .map(|(key, _)| key.to_string())
+            .collect();
+        ready
+            .iter()
+            .filter_map(|key| self.pending.remove(key).map(|(_, event)| event))
+            .collect()
+    }
+}
+
+struct WebhookState {
+    token: Option<String>,
+    debouncer: Arc<Mutex<Debouncer>>,
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    #[serde(default)]
+    token: String,
+}
+
+pub struct Webhook;
+
+impl Webhook {
+    /// 启动webhook服务
+    /// # Arguments
+    /// * listen - 监听地址，如 127.0.0.1:9000
+    /// * token - 校验用的token，设置后webhook地址需带上 ?token=xxx
+    /// * debounce - 同一文档在这段时间内没有新的编辑才导出，如 5s
+    pub async fn start(
+        listen: &str,
+        token: Option<String>,
+        debounce: &str,
+    ) -> Result<(), &'static str> {
+        let window = Watch::parse_interval(debounce)?;
+        match tools::get_user_config() {
+            Ok(config) if !config.toc_range.is_empty() => {}
+            _ => return Err(t!("webhook-config-missing")),
+        }
+        Push::ensure_login().await?;
+
+        let debouncer = Arc::new(Mutex::new(Debouncer::default()));
+        tokio::spawn(Self::export_loop(debouncer.clone(), window));
+
+        let state = web::Data::new(WebhookState { token, debouncer });
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(state.clone())
+                .route("/", web::post().to(Self::receive))
+                .route("/webhook", web::post().to(Self::receive))
+        })
+        .bind(listen)
+        .map_err(|_| t!("webhook-listen-failed"))?;
+
+        Log::success(&t!("webhook-started", listen = listen));
+        server.run().await.map_err(|_| t!("webhook-exited"))
+    }
+
+    /// 接收webhook，校验后放入待导出列表
+    async fn receive(
+        state: web::Data<WebhookState>,
+        query: web::Query<TokenQuery>,
+        body: web::Bytes,
+    ) -> HttpResponse {
+        if let Some(token) = &state.token {
+            if &query.token != token {
+                return HttpResponse::Unauthorized().body("invalid token");
+            }
+        }
+
+        let payload: Value = match serde_json::from_slice(&body) {
+            Ok(payload) => payload,
+            Err(_) => return HttpResponse::BadRequest().body("invalid json"),
+        };
+        let event = match Self::parse_payload(&payload) {
+            Ok(event) => event,
+            Err(err) => return HttpResponse::BadRequest().body(err),
+        };
+
+        if !EXPORT_ACTIONS.contains(&event.action.as_str()) {
+            return HttpResponse::Ok().body("ignored");
+        }
+
+        Log::info(&t!(
+            "webhook-received",
+            title = event.title,
+            action = event.action
+        ));
+        if let Ok(mut debouncer) = state.debouncer.lock() {
+            debouncer.push(event, Instant::now());
+        }
+        HttpResponse::Accepted().body("accepted")
+    }
+
+    /// 解析webhook内容
+    /// 知识库slug取 data.book.slug，没有时从 data.path(用户/知识库/文档) 中取
+    pub fn parse_payload(payload: &Value) -> Result<WebhookEvent, &'static str> {
+        let data = payload.get("data").ok_or("missing data")?;
+        let action = data["webhook_subject_type"]
+            .as_str()
+            .or(data["action_type"].as_str())
+            .ok_or("missing webhook_subject_type")?;
+
+        let path_segments: Vec<&str> = data["path"]
+            .as_str()
+            .unwrap_or_default()
+            .trim_matches('/')
+            .split('/')
+            .collect();
+        let doc_slug = data["slug"]
+            .as_str()
+            .or(path_segments.get(2).copied())
+            .filter(|slug| !slug.is_empty())
+            .ok_or("missing doc slug")?;
+        let book_slug = data["book"]["slug"]
+            .as_str()
+            .or(path_segments.get(1).copied())
+            .filter(|slug| !slug.is_empty())
+            .ok_or("missing book slug")?;
+
+        Ok(WebhookEvent {
+            action: action.to_string(),
+            book_slug: book_slug.to_string(),
+            doc_slug: doc_slug.to_string(),
+            title: data["title"].as_str().unwrap_or(doc_slug).to_string(),
+        })
+    }
+
+    /// 定时取出安静下来的文档并导出
+    async fn export_loop(debouncer: Arc<Mutex<Debouncer>>, window: Duration) {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let events = match debouncer.lock() {
+                Ok(mut debouncer) => debouncer.take_ready(Instant::now(), window),
+                Err(_) => continue,
+            };
+            if events.is_empty() {
+                continue;
+            }
+            Self::export_docs(events).await;
+        }
+    }
+
+    /// 在缓存的目录中找到文档后重新导出，新文档或标题变化时先刷新目录
+    async fn export_docs(events: Vec<WebhookEvent>) {
+        let books = tools::get_cache_books_info().unwrap_or_default();
+        let is_stale = events
+            .iter()
+            .any(|event| Self::find_cached_title(&books, event) != Some(event.title.as_str()));
+        if is_stale && YuqueApi::get_user_bookstacks().await.is_err() {
+            Log::error(t!("webhook-refresh-failed"));
+            return;
+        }
+
+        let books = tools::get_cache_books_info().unwrap_or_default();
+        let toc_range = match tools::get_user_config() {
+            Ok(config) => tools::get_top_level_toc_from_toc_range(&config.toc_range),
+            Err(_) => vec![],
+        };
+        let mut changed_docs = HashSet::new();
+        for event in &events {
+            let book = books.iter().find(|book| book.slug == event.book_slug);
+            match book {
+                Some(book) if toc_range.contains(&book.name) => {
+                    changed_docs.insert(event.doc_key());
+                }
+                Some(_) => Log::warn(&t!("webhook-out-of-range", title = event.title)),
+                None => Log::warn(&t!("webhook-book-missing", title = event.title)),
+            }
+        }
+        if changed_docs.is_empty() {
+            return;
+        }
+
+        match Scheduler::export_docs(&changed_docs).await {
+            Ok(count) => Log::success(&t!("webhook-exported", count = count)),
+            Err(err) => Log::error(err),
+        }
+    }
+
+    fn find_cached_title<'a>(books: &'a [BookItem], event: &WebhookEvent) -> Option<&'a str> {
+        books
+            .iter()
+            .find(|book| book.slug == event.book_slug)
+            .and_then(|book| {
+                book.docs
+                    .iter()
+                    .find(|doc| doc.node_type == "DOC" && doc.url == event.doc_slug)
+            })
+            .map(|doc| doc.title.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_payload() {
+        let payload = json!({
+            "data": {
+                "webhook_subject_type": "update",
+                "slug": "fogcsik8cxgvnodw",
+                "title": "二级子文档",
+                "book": { "slug": "dd67e4" }
+            }
+        });
+        assert_eq!(
+            Webhook::parse_payload(&payload),
+            Ok(WebhookEvent {
+                action: "update".to_string(),
+                book_slug: "dd67e4".to_string(),
+                doc_slug: "fogcsik8cxgvnodw".to_string(),
+                title: "二级子文档".to_string(),
+            })
+        );
+
+        let payload = json!({
+            "data": { "action_type": "publish", "path": "vannvan/dd67e4/abc" }
+        });
+        let event = Webhook::parse_payload(&payload).unwrap();
+        assert_eq!(event.doc_key(), "dd67e4/abc");
+        assert_eq!(event.title, "abc");
+
+        assert!(Webhook::parse_payload(&json!({ "data": { "action_type": "update" } })).is_err());
+        assert!(Webhook::parse_payload(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_debouncer() {
+        let event = |doc_slug: &str| WebhookEvent {
+            action: "update".to_string(),
+            book_slug: "book".to_string(),
+            doc_slug: doc_slug.to_string(),
+            title: doc_slug.to_string(),
+        };
+        let window = Duration::from_secs(5);
+        let start = Instant::now();
+        let mut debouncer = Debouncer::default();
+
+        debouncer.push(event("a"), start);
+        debouncer.push(event("b"), start);
+        // 连续编辑会推迟导出
+        debouncer.push(event("a"), start + Duration::from_secs(3));
+
+        let ready = debouncer.take_ready(start + Duration::from_secs(5), window);
+        assert_eq!(ready, vec![event("b")]);
+        assert!(debouncer
+            .take_ready(start + Duration::from_secs(6), window)
+            .is_empty());
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_secs(8), window),
+            vec![event("a")]
+        );
+    }
+}
@@ -15,14 +15,18 @@ use spinoff::{spinners, Color, Spinner};
 
 use std::{collections::HashMap, process};
 
-use crate::libs::{
-    constants::{schema::ResourceItem, GLOBAL_CONFIG},
-    encrypt::encrypt_password,
-    file::File,
-    log::Log,
-    request::Request,
-    tools::{gen_timestamp, get_cache_user_info, is_personal},
+use crate::{
+    libs::{
+        constants::{schema::ResourceItem, GLOBAL_CONFIG},
+        encrypt::encrypt_password,
+        file::File,
+        log::Log,
+        request::Request,
+        tools::{gen_timestamp, get_cache_user_info, is_personal},
+    },
+    t,
 };
+use tracing::{debug, Instrument};
 use url::form_urlencoded::parse;
 
 extern crate flexbuffers;
@@ -65,10 +69,8 @@ impl YuqueApi {
                 match f.write(&GLOBAL_CONFIG.user_info_file, user_info.to_string()) {
                     Ok(_) => (),
                     Err(err) => {
-                        if cfg!(debug_assertions) {
-                            println!("写入用户信息失败信息：{}", err)
-                        }
-                        Log::error("缓存目录创建失败");
+                        debug!("写入用户信息失败信息：{}", err);
+                        Log::error(t!("cache-dir-create-failed"));
                         process::exit(1)
                     }
                 }
@@ -83,11 +85,28 @@ impl YuqueApi {
 
     /// 获取个人知识库/团队知识库列表数据
     pub async fn get_user_bookstacks() -> Result<Value, bool> {
+        Self::fetch_user_bookstacks(&Value::Null).await
+    }
+
+    /// 按缓存更新知识库列表数据，知识库的更新时间没变时沿用缓存中的目录和文档，只重新获取有变化的知识库
+    pub async fn update_user_bookstacks() -> Result<Value, bool> {
+        let cached = File::new()
+            .read(&GLOBAL_CONFIG.books_info_file)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            .map(|cache| cache["books_info"].clone())
+            .unwrap_or_default();
+        Self::fetch_user_bookstacks(&cached).await
+    }
+
+    /// 获取知识库列表数据并写入缓存
+    /// # Arguments
+    /// * cached - 缓存中的知识库信息，为空时全部重新获取
+    async fn fetch_user_bookstacks(cached: &Value) -> Result<Value, bool> {
         let is_personal = is_personal();
-        Log::info("开始获取知识库");
+        Log::info(t!("yuque-books-fetching"));
         // loading开始
-        let mut spinner =
-            Spinner::new(spinners::Dots, "正在获取知识库数据，请稍后...", Color::Blue);
+        let mut spinner = Spinner::new(spinners::Dots, t!("yuque-books-loading"), Color::Blue);
 
         let target_api = if is_personal {
             &GLOBAL_CONFIG.yuque_book_stacks
@@ -95,9 +114,7 @@ impl YuqueApi {
             &GLOBAL_CONFIG.yuque_space_books_info
         };
 
-        if cfg!(debug_assertions) {
-            println!("获取知识库地址：{}", target_api);
-        }
+        debug!("获取知识库地址：{}", target_api);
 
         if let Ok(resp) = Request::get(&target_api).await {
             if resp.get("data").is_some() {
@@ -105,7 +122,7 @@ impl YuqueApi {
                 let f = File::new();
 
                 let filtered_books_data = if is_personal {
-                    let docs = Self::gen_books_data_for_cache(&data_wrap).await;
+                    let docs = Self::gen_books_data_for_cache(data_wrap, cached).await;
                     docs
                 } else {
                     let mut temp_books_data: Vec<Value> = vec![];
@@ -113,9 +130,11 @@ impl YuqueApi {
                     let books_info = json!({ "books": data_wrap });
                     temp_books_data.push(books_info);
 
-                    let docs =
-                        Self::gen_books_data_for_cache(&serde_json::Value::Array(temp_books_data))
-                            .await;
+                    let docs = Self::gen_books_data_for_cache(
+                        &serde_json::Value::Array(temp_books_data),
+                        cached,
+                    )
+                    .await;
                     docs
                 };
 
@@ -123,7 +142,7 @@ impl YuqueApi {
 
                 let mut all_docs_len: usize = 0;
 
-                if let Ok(collab_books) = Self::get_collab_books().await {
+                if let Ok(collab_books) = Self::get_collab_books(cached).await {
                     let collab_books_array = collab_books.to_owned();
                     for book in collab_books_array.as_array().unwrap() {
                         merged_books_data.push(book.clone());
@@ -144,7 +163,7 @@ impl YuqueApi {
                 // 写入知识库信息文件
                 match f.write(&GLOBAL_CONFIG.books_info_file, books_info.to_string()) {
                     Err(_) => {
-                        Log::error("文件创建失败");
+                        Log::error(t!("file-create-failed"));
                         process::exit(1)
                     }
                     Ok(_) => {
@@ -155,24 +174,24 @@ impl YuqueApi {
                 }
                 // println!("{:?}", serde_json::to_string(&books).unwrap())
             } else {
-                if cfg!(debug_assertions) {
-                    println!("获取知识库响应信息：{:?}", resp.to_owned());
-                }
-                let mut error_info = String::from("获取知识库失败: ");
-                error_info.push_str(resp.get("message").unwrap().to_string().as_str());
+                debug!("获取知识库响应信息：{:?}", resp.to_owned());
+                let error_info = t!(
+                    "yuque-books-fetch-failed-reason",
+                    reason = resp.get("message").unwrap().to_string()
+                );
                 spinner.stop();
                 Log::error(&error_info);
                 Err(false)
             }
         } else {
             spinner.stop();
-            Log::error("获取知识库失败");
+            Log::error(t!("yuque-books-fetch-failed"));
             Err(false)
         }
     }
 
     /// 获取协作知识库数据
-    pub async fn get_collab_books() -> Result<Value, bool> {
+    pub async fn get_collab_books(cached: &Value) -> Result<Value, bool> {
         if let Ok(resp) = Request::get(&GLOBAL_CONFIG.yuque_collab_books_info).await {
             if resp.get("data").is_some() {
                 let data_wrap = resp.get("data").unwrap();
@@ -181,9 +200,11 @@ impl YuqueApi {
                 let books_info = json!({ "books": data_wrap });
                 temp_books_data.push(books_info);
 
-                let docs =
-                    Self::gen_books_data_for_cache(&serde_json::Value::Array(temp_books_data))
-                        .await;
+                let docs = Self::gen_books_data_for_cache(
+                    &serde_json::Value::Array(temp_books_data),
+                    cached,
+                )
+                .await;
 
                 // println!("协作知识库：{:?}", docs);
                 Ok(docs)
@@ -196,7 +217,10 @@ impl YuqueApi {
     }
 
     /// 生成适配缓存结构的知识库数据
-    pub async fn gen_books_data_for_cache(book_info: &Value) -> Value {
+    /// # Arguments
+    /// * book_info - 知识库列表
+    /// * cached - 缓存中的知识库信息，更新时间没变的知识库沿用其中的目录和文档
+    pub async fn gen_books_data_for_cache(book_info: &Value, cached: &Value) -> Value {
         let mut target_books_data = vec![];
 
         let current_login = get_cache_user_info().unwrap().login.to_string();
@@ -204,14 +228,12 @@ impl YuqueApi {
         for item in book_info.as_array().unwrap() {
             for sub_item in item.to_owned().get("books").unwrap().as_array().unwrap() {
                 let current_book_user_login = sub_item.get("user").unwrap().get("login").unwrap();
-                if cfg!(debug_assertions) {
-                    println!(
-                        "当前登录用户 {}, 当前知识库用户 {},{}",
-                        current_login.to_string(),
-                        current_book_user_login,
-                        current_login.to_string() == current_book_user_login.to_owned()
-                    );
-                }
+                debug!(
+                    "当前登录用户 {}, 当前知识库用户 {},{}",
+                    current_login,
+                    current_book_user_login,
+                    current_login == *current_book_user_login
+                );
 
                 // 知识库所属
                 let book_type = if current_login == current_book_user_login.to_owned() {
@@ -227,7 +249,9 @@ impl YuqueApi {
                   "book_id": sub_item.get("id"),
                   "user_login": sub_item.get("user").unwrap().get("login"),
                   "user_name": sub_item.get("user").unwrap().get("name"),
-                  "book_type": book_type
+                  "book_type": book_type,
+                  "updated_at": sub_item.get("updated_at"),
+                  "content_updated_at": sub_item.get("content_updated_at")
                 });
 
                 target_books_data.push(book_info)
@@ -237,10 +261,25 @@ impl YuqueApi {
         for item in &mut target_books_data {
             let user_login = item["user_login"].as_str().unwrap_or_default();
             let slug = item["slug"].as_str().unwrap_or_default();
+            if let Some(docs) = Self::cached_book_docs(cached, item) {
+                debug!("知识库{}没有变化，沿用缓存中的目录", slug);
+                item["docs"] = docs;
+                continue;
+            }
             let url = format!("/{}/{}", user_login, slug);
-            let toc = Self::get_book_docs_info(&url).await;
-
-            if let Ok(book_toc) = toc {
+            let span = tracing::info_span!("book", slug = %slug);
+            let toc = Self::get_book_docs_info(&url)
+                .instrument(span.clone())
+                .await;
+
+            if let Ok(mut book_toc) = toc {
+                // 目录中没有更新时间和作者，用于导出过滤
+                if let Ok(docs_meta) = Self::get_book_docs_meta(&item["book_id"])
+                    .instrument(span)
+                    .await
+                {
+                    Self::merge_docs_meta(&mut book_toc, &docs_meta);
+                }
                 item["docs"] = book_toc;
             }
         }
@@ -255,6 +294,83 @@ impl YuqueApi {
         serde_json::Value::Array(filtered_books_data)
     }
 
+    /// 缓存中同一知识库的目录，知识库的更新时间和内容更新时间都没变时才沿用
+    /// # Arguments
+    /// * cached - 缓存中的知识库信息
+    /// * book - 刚获取的知识库信息
+    fn cached_book_docs(cached: &Value, book: &Value) -> Option<Value> {
+        let is_same = |item: &Value, key: &str| item[key] == book[key];
+        if book["updated_at"].as_str().unwrap_or_default().is_empty()
+            && book["content_updated_at"]
+                .as_str()
+                .unwrap_or_default()
+                .is_empty()
+        {
+            return None;
+        }
+        cached
+            .as_array()?
+            .iter()
+            .find(|item| {
+                ["user_login", "slug", "updated_at", "content_updated_at"]
+                    .iter()
+                    .all(|key| is_same(item, key))
+            })
+            .map(|item| item["docs"].clone())
+            .filter(|docs| docs.is_array())
+    }
+
+    /// 获取知识库下文档的更新时间和作者
+    async fn get_book_docs_meta(book_id: &Value) -> Result<Value, bool> {
+        let book_id = match book_id.as_u64() {
+            Some(id) => id.to_string(),
+            None => return Err(false),
+        };
+        let url = GLOBAL_CONFIG.yuque_book_docs.replace("{book_id}", &book_id);
+
+        match Request::get(&url).await {
+            Ok(resp) => match resp.get("data") {
+                Some(data) => Ok(data.clone()),
+                None => Err(false),
+            },
+            Err(_err) => Err(false),
+        }
+    }
+
+    /// 按文档slug把更新时间和作者合并到目录中
+    /// # Arguments
+    /// * toc - 知识库目录
+    /// * docs_meta - 文档列表接口返回的数据
+    fn merge_docs_meta(toc: &mut Value, docs_meta: &Value) {
+        let mut meta_map: HashMap<&str, (&str, &str, &str)> = HashMap::new();
+        if let Some(docs) = docs_meta.as_array() {
+            for doc in docs {
+                let slug = doc["slug"].as_str().unwrap_or_default();
+                let updated_at = doc["content_updated_at"]
+                    .as_str()
+                    .or(doc["updated_at"].as_str())
+                    .unwrap_or_default();
+                let author = doc["user"]["login"]
+                    .as_str()
+                    .or(doc["last_editor"]["login"].as_str())
+                    .unwrap_or_default();
+                let last_editor = doc["last_editor"]["login"].as_str().unwrap_or(author);
+                meta_map.insert(slug, (updated_at, author, last_editor));
+            }
+        }
+
+        if let Some(items) = toc.as_array_mut() {
+            for item in items {
+                let url = item["url"].as_str().unwrap_or_default().to_string();
+                if let Some((updated_at, author, last_editor)) = meta_map.get(url.as_str()) {
+                    item["updated_at"] = json!(updated_at);
+                    item["author"] = json!(author);
+                    item["last_editor"] = json!(last_editor);
+                }
+            }
+        }
+    }
+
     /// 爬取知识库下文档数据
     pub async fn get_book_docs_info(repo: &str) -> Result<Value, Null> {
         if let Ok(resp) = Self::crawl_book_toc_info(repo).await {
@@ -369,8 +485,6 @@ impl YuqueApi {
             format!("/api/resources?book_id={}&offset={}", id, current_offset)
         };
 
-        let f = File::new();
-
         if let Ok(resp) = Request::get(&url).await {
             if resp.get("data").is_some() {
                 let list = resp.get("data");
@@ -420,11 +534,9 @@ impl YuqueApi {
                         //     resource_item.get("id")
                         // );
 
-                        let file_name_string = format!(
-                            "{}.{}",
-                            resource_item.get("filename").unwrap().as_str().unwrap(),
-                            resource_item.get("ext").unwrap().as_str().unwrap()
-                        );
+                        let file_name = resource_item.get("filename").unwrap().as_str().unwrap();
+                        let ext = resource_item.get("ext").unwrap().as_str().unwrap();
+                        let file_name_string = format!("{}.{}", file_name, ext);
 
                         // 本地目录路径
                         let local_folder_string = &format!(
@@ -453,12 +565,11 @@ impl YuqueApi {
                             // link: file_name_string,
                             target_save_full_path_name: local_file_full_name.to_string(),
                             source_id: resource_item.get("id").unwrap().to_string(),
+                            folder: folder_name.to_string(),
+                            file_name: file_name.to_string(),
+                            ext: ext.to_string(),
                         });
-                        // 创建多级文件夹
-                        if let Err(_) = f.mkdir(local_folder_string) {
-                            Log::error(&format!("{} 资源目录创建失败", local_folder_string));
-                            process::exit(1)
-                        }
+                        // 目录在下载时按需创建
                     }
                 }
 
@@ -504,6 +615,36 @@ impl YuqueApi {
 #[allow(non_snake_case)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_cached_book_docs() {
+        let cached = json!([{
+            "user_login": "mock-user",
+            "slug": "rust-book",
+            "updated_at": "2023-09-01T08:00:00.000Z",
+            "content_updated_at": "2023-09-01T08:00:00.000Z",
+            "docs": [{ "type": "DOC", "title": "所有权" }]
+        }]);
+        let mut book = json!({
+            "user_login": "mock-user",
+            "slug": "rust-book",
+            "updated_at": "2023-09-01T08:00:00.000Z",
+            "content_updated_at": "2023-09-01T08:00:00.000Z"
+        });
+        assert_eq!(
+            YuqueApi::cached_book_docs(&cached, &book),
+            Some(json!([{ "type": "DOC", "title": "所有权" }]))
+        );
+
+        // 内容有更新时重新获取
+        book["content_updated_at"] = json!("2023-09-02T08:00:00.000Z");
+        assert_eq!(YuqueApi::cached_book_docs(&cached, &book), None);
+        // 没有更新时间时无法判断，重新获取
+        book["updated_at"] = Value::Null;
+        book["content_updated_at"] = Value::Null;
+        assert_eq!(YuqueApi::cached_book_docs(&cached, &book), None);
+        assert_eq!(YuqueApi::cached_book_docs(&Value::Null, &book), None);
+    }
     #[tokio::test]
     async fn test_crawl_success() {
         let ss = YuqueApi::crawl_book_toc_info("/vannvan/dd67e4").await;
@@ -536,6 +677,26 @@ mod tests {
         }
     }
     #[test]
+    fn test_merge_docs_meta() {
+        let mut toc = json!([
+            { "type": "TITLE", "title": "分组", "url": "" },
+            { "type": "DOC", "title": "文档", "url": "fogcsik8cxgvnodw" }
+        ]);
+        let docs_meta = json!([{
+            "slug": "fogcsik8cxgvnodw",
+            "content_updated_at": "2023-09-01T08:00:00.000Z",
+            "updated_at": "2023-09-02T08:00:00.000Z",
+            "user": { "login": "vannvan" },
+            "last_editor": { "login": "editor" }
+        }]);
+
+        YuqueApi::merge_docs_meta(&mut toc, &docs_meta);
+        assert!(toc[0]["updated_at"].is_null());
+        assert_eq!(toc[1]["updated_at"], "2023-09-01T08:00:00.000Z");
+        assert_eq!(toc[1]["author"], "vannvan");
+        assert_eq!(toc[1]["last_editor"], "editor");
+    }
+    #[test]
     fn reg_toc_info() {
         let input = r#"window.appData = JSON.parse(decodeURIComponent("%7B%22me%22%3A%7B%22PERMISSION%22%3A"))"#;
 
@@ -0,0 +1,354 @@
+/*
+ * Description: 导出内容加密，基于age，支持接收方公钥(X25519)和口令两种方式
+ * Created: 2026-10-19 02:43:19
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    iter,
+    str::FromStr,
+};
+
+use age::{
+    scrypt,
+    secrecy::{ExposeSecret, SecretString},
+    stream::StreamWriter,
+    x25519, Decryptor, Encryptor, Identity, Recipient,
+};
+use inquire::{Password, PasswordDisplayMode};
+
+use crate::t;
+
+use super::constants::schema::{EncryptConfig, EncryptOptions};
+
+/// 加密文件的扩展名
+pub const ENCRYPTED_EXT: &str = ".age";
+
+/// 默认的口令环境变量
+pub const PASSPHRASE_ENV: &str = "YTOOL_PASSPHRASE";
+
+/// 加密方式
+#[derive(Clone)]
+pub enum Cipher {
+    /// 接收方公钥，任一对应的私钥都能解密
+    Recipients(Vec<x25519::Recipient>),
+    /// 口令
+    Passphrase(SecretString),
+}
+
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cipher::Recipients(recipients) => f
+                .debug_tuple("Recipients")
+                .field(&recipients.iter().map(|r| r.to_string()).collect::<Vec<_>>())
+                .finish(),
+            Cipher::Passphrase(_) => f.write_str("Passphrase(***)"),
+        }
+    }
+}
+
+impl Cipher {
+    /// 按接收方公钥加密
+    /// # Arguments
+    /// * keys - age1 开头的公钥
+    pub fn from_recipients(keys: &[String]) -> Result<Self, &'static str> {
+        let recipients = keys
+            .iter()
+            .map(|key| x25519::Recipient::from_str(key.trim()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| t!("encrypt-recipient-invalid"))?;
+        if recipients.is_empty() {
+            return Err(t!("encrypt-recipient-invalid"));
+        }
+        Ok(Cipher::Recipients(recipients))
+    }
+
+    /// 按口令加密
+    pub fn from_passphrase(passphrase: &str) -> Result<Self, &'static str> {
+        if passphrase.is_empty() {
+            return Err(t!("encrypt-passphrase-empty"));
+        }
+        Ok(Cipher::Passphrase(SecretString::from(
+            passphrase.to_string(),
+        )))
+    }
+
+    /// 按命令行参数和配置档案确定加密方式，不需要加密时返回None
+    /// 命令行中的公钥优先，其次是配置档案中的公钥，都没有时使用口令
+    /// # Arguments
+    /// * options - 命令行中的加密参数
+    /// * config - 配置档案中的密钥配置
+    pub fn resolve(
+        options: Option<&EncryptOptions>,
+        config: Option<&EncryptConfig>,
+    ) -> Result<Option<Self>, &'static str> {
+        let always = config.is_some_and(|config| config.always);
+        let options = match options {
+            Some(options) => options.clone(),
+            None if always => EncryptOptions::default(),
+            None => return Ok(None),
+        };
+
+        let recipients = if options.recipients.is_empty() {
+            config
+                .map(|config| config.recipients.clone())
+                .unwrap_or_default()
+        } else {
+            options.recipients
+        };
+        if !options.passphrase && !recipients.is_empty() {
+            return Self::from_recipients(&recipients).map(Some);
+        }
+
+        let env_name = config.map(|config| config.passphrase_env.as_str());
+        let passphrase = read_passphrase(env_name, true)?;
+        Self::from_passphrase(&passphrase).map(Some)
+    }
+
+    /// 是否为口令加密
+    pub fn is_passphrase(&self) -> bool {
+        matches!(self, Cipher::Passphrase(_))
+    }
+
+    fn encryptor(&self) -> Result<Encryptor, &'static str> {
+        match self {
+            Cipher::Recipients(recipients) => {
+                Encryptor::with_recipients(recipients.iter().map(|r| r as &dyn Recipient))
+                    .map_err(|_| t!("encrypt-failed"))
+            }
+            Cipher::Passphrase(passphrase) => {
+                Ok(Encryptor::with_user_passphrase(passphrase.clone()))
+            }
+        }
+    }
+
+    /// 加密整段内容
+    pub fn encrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let mut encrypted = vec![];
+        let mut writer = self.wrap_output(&mut encrypted)?;
+        writer.write_all(bytes).map_err(|_| t!("encrypt-failed"))?;
+        writer.finish().map_err(|_| t!("encrypt-failed"))?;
+        Ok(encrypted)
+    }
+
+    /// 写入加密后的文件，文件名加上 .age
+    /// # Arguments
+    /// * path - 原文件路径
+    /// * bytes - 明文内容
+    pub fn write_file(&self, path: &str, bytes: &[u8]) -> Result<(), &'static str> {
+        let encrypted = self.encrypt(bytes)?;
+        fs::write(format!("{}{}", path, ENCRYPTED_EXT), encrypted).map_err(|_| t!("encrypt-failed"))
+    }
+
+    /// 把已有的明文文件替换为加密文件
+    pub fn encrypt_file(&self, path: &str) -> Result<(), &'static str> {
+        let bytes = fs::read(path).map_err(|_| t!("encrypt-failed"))?;
+        self.write_file(path, &bytes)?;
+        fs::remove_file(path).map_err(|_| t!("encrypt-failed"))
+    }
+
+    /// 包装输出，写入的内容会被加密，结束时需要调用finish
+    pub fn wrap_output<W: Write>(&self, output: W) -> Result<StreamWriter<W>, &'static str> {
+        self.encryptor()?
+            .wrap_output(output)
+            .map_err(|_| t!("encrypt-failed"))
+    }
+}
+
+/// 解密用的密钥，私钥和口令按需使用
+#[derive(Default)]
+pub struct Keyring {
+    /// 私钥
+    pub identities: Vec<x25519::Identity>,
+    /// 口令所在的环境变量
+    pub passphrase_env: Option<String>,
+    /// 第一次遇到口令加密的文件时读取，之后复用
+    passphrase: Option<SecretString>,
+}
+
+impl Keyring {
+    /// # Arguments
+    /// * identities - 私钥
+    /// * passphrase_env - 口令所在的环境变量，为空时使用 YTOOL_PASSPHRASE
+    pub fn new(identities: Vec<x25519::Identity>, passphrase_env: Option<String>) -> Self {
+        Keyring {
+            identities,
+            passphrase_env,
+            passphrase: None,
+        }
+    }
+
+    /// 解密，input为age格式的内容，明文写入output
+    pub fn decrypt<R: Read, W: Write>(
+        &mut self,
+        input: R,
+        mut output: W,
+    ) -> Result<u64, &'static str> {
+        let decryptor = Decryptor::new(input).map_err(|_| t!("decrypt-not-encrypted"))?;
+        let mut reader = if decryptor.is_scrypt() {
+            if self.passphrase.is_none() {
+                let passphrase = read_passphrase(self.passphrase_env.as_deref(), false)?;
+                self.passphrase = Some(SecretString::from(passphrase));
+            }
+            let identity = scrypt::Identity::new(self.passphrase.clone().unwrap());
+            decryptor.decrypt(iter::once(&identity as &dyn Identity))
+        } else {
+            if self.identities.is_empty() {
+                return Err(t!("decrypt-identity-missing"));
+            }
+            decryptor.decrypt(self.identities.iter().map(|id| id as &dyn Identity))
+        }
+        .map_err(|_| t!("decrypt-failed"))?;
+
+        let size = io::copy(&mut reader, &mut output).map_err(|_| t!("decrypt-failed"))?;
+        output.flush().map_err(|_| t!("decrypt-failed"))?;
+        Ok(size)
+    }
+}
+
+/// 读取私钥文件，忽略空行和 # 开头的注释，兼容 age-keygen 生成的文件
+pub fn read_identities(identity_file: &str) -> Result<Vec<x25519::Identity>, &'static str> {
+    let content = fs::read_to_string(identity_file).map_err(|_| t!("decrypt-identity-invalid"))?;
+    let identities = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(x25519::Identity::from_str)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| t!("decrypt-identity-invalid"))?;
+    if identities.is_empty() {
+        return Err(t!("decrypt-identity-invalid"));
+    }
+    Ok(identities)
+}
+
+/// 生成新的密钥对，返回 (私钥文件内容, 公钥)
+pub fn generate_identity() -> (String, String) {
+    let identity = x25519::Identity::generate();
+    let recipient = identity.to_public().to_string();
+    let content = format!(
+        "# created: {}\n# public key: {}\n{}\n",
+        chrono::Local::now().to_rfc3339(),
+        recipient,
+        identity.to_string().expose_secret()
+    );
+    (content, recipient)
+}
+
+/// 读取口令，优先使用环境变量，未设置时交互询问
+/// # Arguments
+/// * env_name - 环境变量名，为空时使用 YTOOL_PASSPHRASE
+/// * confirm - 是否需要再次输入确认，加密时需要
+fn read_passphrase(env_name: Option<&str>, confirm: bool) -> Result<String, &'static str> {
+    let env_name = match env_name {
+        Some(name) if !name.is_empty() => name,
+        _ => PASSPHRASE_ENV,
+    };
+    if let Ok(passphrase) = env::var(env_name) {
+        if !passphrase.is_empty() {
+            return Ok(passphrase);
+        }
+    }
+
+    let prompt = Password::new(t!("encrypt-passphrase-prompt"))
+        .with_display_mode(PasswordDisplayMode::Masked);
+    let prompt = if confirm {
+        prompt.with_custom_confirmation_message(t!("encrypt-passphrase-confirm"))
+    } else {
+        prompt.without_confirmation()
+    };
+    prompt.prompt().map_err(|_| t!("encrypt-passphrase-empty"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipient_round_trip() {
+        let identity = x25519::Identity::generate();
+        let cipher = Cipher::from_recipients(&[identity.to_public().to_string()]).unwrap();
+        assert!(!cipher.is_passphrase());
+        assert!(!format!("{:?}", cipher).contains("AGE-SECRET-KEY"));
+
+        let encrypted = cipher.encrypt("# 文档".as_bytes()).unwrap();
+        assert_ne!(encrypted, "# 文档".as_bytes());
+
+        let mut plain = vec![];
+        let mut keyring = Keyring::new(vec![identity], None);
+        keyring.decrypt(&encrypted[..], &mut plain).unwrap();
+        assert_eq!(plain, "# 文档".as_bytes());
+
+        // 其他私钥无法解密
+        let mut keyring = Keyring::new(vec![x25519::Identity::generate()], None);
+        assert!(keyring.decrypt(&encrypted[..], &mut vec![]).is_err());
+        // 没有私钥
+        assert!(Keyring::default()
+            .decrypt(&encrypted[..], &mut vec![])
+            .is_err());
+        // 不是age格式
+        assert!(Keyring::default()
+            .decrypt(&b"# doc"[..], &mut vec![])
+            .is_err());
+
+        assert!(Cipher::from_recipients(&["age1invalid".to_string()]).is_err());
+        assert!(Cipher::from_recipients(&[]).is_err());
+    }
+
+    #[test]
+    fn test_passphrase_round_trip() {
+        let cipher = Cipher::from_passphrase("correct horse").unwrap();
+        assert!(cipher.is_passphrase());
+        assert!(Cipher::from_passphrase("").is_err());
+
+        let encrypted = cipher.encrypt(b"secret").unwrap();
+        let env_name = "YTOOL_TEST_PASSPHRASE";
+        env::set_var(env_name, "correct horse");
+        let mut plain = vec![];
+        let mut keyring = Keyring::new(vec![], Some(env_name.to_string()));
+        keyring.decrypt(&encrypted[..], &mut plain).unwrap();
+        assert_eq!(plain, b"secret");
+    }
+
+    #[test]
+    fn test_resolve_and_identities() {
+        assert!(Cipher::resolve(None, None).unwrap().is_none());
+
+        let (content, recipient) = generate_identity();
+        let dir = tempfile::tempdir().unwrap();
+        let identity_file = dir.path().join("key.txt").to_string_lossy().to_string();
+        fs::write(&identity_file, &content).unwrap();
+        let identities = read_identities(&identity_file).unwrap();
+        assert_eq!(identities[0].to_public().to_string(), recipient);
+
+        fs::write(&identity_file, "# empty\n").unwrap();
+        assert!(read_identities(&identity_file).is_err());
+
+        // 配置档案要求每次都加密时，使用档案中的公钥
+        let config = EncryptConfig {
+            always: true,
+            recipients: vec![recipient.to_string()],
+            ..Default::default()
+        };
+        let cipher = Cipher::resolve(None, Some(&config)).unwrap().unwrap();
+        assert!(format!("{:?}", cipher).contains(&recipient));
+
+        // 命令行中的公钥优先
+        let other = x25519::Identity::generate().to_public().to_string();
+        let options = EncryptOptions {
+            recipients: vec![other.to_string()],
+            passphrase: false,
+        };
+        let cipher = Cipher::resolve(Some(&options), Some(&config))
+            .unwrap()
+            .unwrap();
+        assert!(format!("{:?}", cipher).contains(&other));
+        assert!(!format!("{:?}", cipher).contains(&recipient));
+    }
+}
@@ -9,10 +9,19 @@
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::json;
+
+use super::{schema::Profile, ACTIVE_PROFILE};
+use crate::libs::profile;
+use std::env;
 // use std::fs;
 
 // const CONFIG_FILE_PATH: &str = "conf/config.json";
 
+/// 覆盖语雀地址的环境变量
+pub const YUQUE_HOST_ENV: &str = "YTOOL_YUQUE_HOST";
+/// 覆盖版本发布信息地址的环境变量，测试时可指向本地文件服务
+pub const RELEASE_FEED_ENV: &str = "YTOOL_RELEASE_FEED";
+
 fn parse_json<T: DeserializeOwned>(schema: &str) -> Option<T> {
     match serde_json::from_str(schema) {
         Ok(parsed) => Some(parsed),
@@ -42,6 +51,14 @@ pub struct GlobalConfig {
     pub group_resource_base_info: String,
     /// 导出md文件
     pub yuque_export_markdown: String,
+    /// 知识库文档(创建/更新)
+    pub yuque_repo_docs: String,
+    /// 知识库目录
+    pub yuque_repo_toc: String,
+    /// 上传图片
+    pub yuque_upload_image: String,
+    /// 知识库文档列表，带更新时间和作者
+    pub yuque_book_docs: String,
     /// meta目录
     pub meta_dir: String,
     /// 用户的CLI配置
@@ -56,10 +73,23 @@ pub struct GlobalConfig {
     pub user_info_file: String,
     /// 知识库信息
     pub books_info_file: String,
+    /// 导出文档的全文索引
+    pub search_index_file: String,
+    /// 推送记录，本地路径与语雀文档的对应关系
+    pub push_map_file: String,
+    /// watch的运行状态
+    pub watch_status_file: String,
     /// 过期时间,1天
     pub local_expire: u128,
     /// 下载频率,500
     pub duration: usize,
+    /// 配置档案文件
+    pub profiles_file: String,
+    /// 版本发布信息，GitHub release格式
+    pub release_feed: String,
+    /// 当前使用的配置档案，通过 --profile 选择
+    #[serde(default)]
+    pub profile: Option<Profile>,
 }
 #[derive(Serialize, Deserialize, Debug)]
 struct Conf {
@@ -81,6 +111,10 @@ pub fn load_conf() -> GlobalConfig {
         "user_agent":"Mozilla/5.0 (iPhone; CPU iPhone OS 16_6_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/20G81 YuqueMobileApp/1.0.2 (AppBuild/650 Device/Phone Locale/zh-cn Theme/light YuqueType/public)",
         "yuque_books_info": "",
         "yuque_export_markdown": "",
+        "yuque_repo_docs": "/api/v2/repos/{namespace}/docs",
+        "yuque_repo_toc": "/api/v2/repos/{namespace}/toc",
+        "yuque_upload_image": "/api/upload/attach?attachable_type=Doc&attachable_id={doc_id}&type=image",
+        "yuque_book_docs": "/api/docs?book_id={book_id}",
         "meta_dir": ".meta",
         "target_output_dir": "./docs",
         "target_resource_dir":"./resources",
@@ -88,6 +122,11 @@ pub fn load_conf() -> GlobalConfig {
         "cookies_file": ".meta/cookies.json",
         "user_info_file": ".meta/user_info.json",
         "books_info_file": ".meta/books_info.json",
+        "search_index_file": ".meta/search_index.json",
+        "push_map_file": ".meta/push_map.json",
+        "watch_status_file": ".meta/watch_status.json",
+        "profiles_file": "yuque.profiles.json",
+        "release_feed": "https://api.github.com/repos/vannvan/rust-explore/releases/latest",
         "duration": 500,
         "local_expire": 86400000
     })
@@ -111,7 +150,30 @@ pub fn load_conf() -> GlobalConfig {
 
     // let parsed_json =
     //     parse_json::<GlobalConfig>(&serde_json::to_string(&conf).unwrap().clone()).unwrap();
-    let parsed_json = parse_json::<GlobalConfig>(&config_file.to_string()).unwrap();
+    let mut parsed_json = parse_json::<GlobalConfig>(&config_file.to_string()).unwrap();
+
+    // 可通过环境变量指定语雀地址，用于离线测试(mock服务)或私有部署
+    if let Ok(yuque_host) = env::var(YUQUE_HOST_ENV) {
+        let yuque_host = yuque_host.trim_end_matches('/');
+        if !yuque_host.is_empty() {
+            parsed_json.yuque_host = yuque_host.to_string();
+            parsed_json.yuque_referer = format!("{}/login", yuque_host);
+        }
+    }
+
+    if let Ok(release_feed) = env::var(RELEASE_FEED_ENV) {
+        if !release_feed.is_empty() {
+            parsed_json.release_feed = release_feed;
+        }
+    }
+
+    // 使用了配置档案时，缓存和输出目录都切换到档案自己的目录
+    if let Some(profile_name) = ACTIVE_PROFILE.get() {
+        let profiles = profile::read_profiles(&parsed_json.profiles_file);
+        if let Some(target_profile) = profiles.iter().find(|item| &item.name == profile_name) {
+            profile::apply_profile(&mut parsed_json, target_profile);
+        }
+    }
     parsed_json
     // conf
 }
@@ -7,10 +7,14 @@
  * Copyright (c) https://github.com/vannvan
  */
 use lazy_static::lazy_static;
+use std::sync::OnceLock;
 pub mod load_config;
 pub mod schema;
 use crate::libs::constants::load_config::{load_conf, GlobalConfig};
 
+/// 通过 --profile 选择的配置档案名称，必须在首次访问GLOBAL_CONFIG之前设置
+pub static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
 lazy_static! {
     pub static ref GLOBAL_CONFIG: GlobalConfig = load_conf();
 }
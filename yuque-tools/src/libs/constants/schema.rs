@@ -7,6 +7,9 @@
  * Copyright (c) https://github.com/vannvan
  */
 
+use std::collections::{HashMap, HashSet};
+
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
 fn default_as_true() -> bool {
@@ -46,6 +49,149 @@ pub struct UserCliConfig {
     #[serde(default)]
     /// 自定义输出目录
     pub output: String,
+    #[serde(default)]
+    /// 开放接口的Token，推送/复制知识库时通过 /api/v2 写入文档需要
+    pub token: String,
+    #[serde(default)]
+    /// 含子文档的文档/分组的输出方式
+    pub layout: DocLayout,
+    #[serde(default)]
+    /// 是否导出隐藏文档(目录中visible为0，包括未发布的草稿)
+    pub include_hidden: bool,
+    #[serde(default)]
+    /// 导出过滤条件
+    pub filter: DocFilter,
+    #[serde(default)]
+    /// 导出目标，普通Markdown或Obsidian/Logseq笔记库
+    pub target: ExportTarget,
+    #[serde(default)]
+    /// Obsidian附件目录，相对于输出目录，为空时使用 attachments
+    pub attachment_dir: String,
+    #[serde(default)]
+    /// 输出路径模板，为空的项使用默认的目录结构
+    pub path_template: PathTemplates,
+}
+
+/// pull命令的参数，命令行中填写了的项覆盖配置文件
+#[derive(Debug, Clone, Default)]
+pub struct PullOptions {
+    /// 只输出导出计划，不修改任何本地文件
+    pub dry_run: bool,
+    /// 过滤条件
+    pub filter: DocFilter,
+    /// 导出目标
+    pub target: Option<ExportTarget>,
+    /// 导出后提交到输出目录的git仓库
+    pub git: bool,
+    /// 每篇文档单独提交，作者为文档的最后编辑者
+    pub git_author: bool,
+    /// 有变化的文档(导出计划中的key)，总是重新下载，其余文档本地已存在时跳过
+    pub changed_docs: Option<HashSet<String>>,
+    /// 文档路径模板，覆盖配置文件中的 path_template.doc
+    pub path_template: Option<String>,
+    /// 导出为归档文件(.tar.zst/.zip)，不写入输出目录
+    pub archive: Option<String>,
+    /// 按知识库拆分归档
+    pub archive_split: bool,
+    /// 加密导出，为None时按配置档案决定
+    pub encrypt: Option<EncryptOptions>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// 命令行中的加密参数
+pub struct EncryptOptions {
+    /// 接收方公钥，为空时使用配置档案中的公钥
+    pub recipients: Vec<String>,
+    /// 使用口令加密
+    pub passphrase: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+/// 导出目标
+pub enum ExportTarget {
+    /// 普通Markdown，文档间为Markdown链接
+    #[default]
+    Markdown,
+    /// Obsidian笔记库，wikilink + front matter，附件放到附件目录
+    Obsidian,
+    /// Logseq图谱，页面放到pages，附件放到assets
+    Logseq,
+}
+
+/// 导出过滤条件，与GUI端共用
+pub use yuque_common::filter::DocFilter;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// 输出路径模板，变量写作 `{name}`，路径都是相对的
+pub struct PathTemplates {
+    #[serde(default)]
+    /// 文档路径，相对于输出目录，如 `{book}/{path}/{title}.{ext}`
+    pub doc: String,
+    #[serde(default)]
+    /// 按知识库(名称或slug)单独指定的文档路径，优先于doc
+    pub books: HashMap<String, String>,
+    #[serde(default)]
+    /// Obsidian附件路径，相对于输出目录，如 `attachments/{book_slug}/{name}`
+    pub asset: String,
+    #[serde(default)]
+    /// grd资源路径，相对于资源输出目录，如 `{folder}/{file}.{ext}`
+    pub resource: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// 账号/空间配置档案，通过 `ytool --profile <name>` 选择
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    /// 空间域名，个人知识库留空
+    pub host: String,
+    #[serde(default)]
+    /// 开放接口的Token，推送/复制知识库时使用
+    pub token: String,
+    #[serde(default)]
+    /// 缓存目录，为空时使用 .meta/profiles/{name}
+    pub meta_dir: String,
+    #[serde(default)]
+    /// 文档输出目录，为空时使用 ./docs/{name}
+    pub output: String,
+    #[serde(default)]
+    /// 导出加密的密钥配置
+    pub encrypt: EncryptConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// 导出加密的密钥配置，基于age
+pub struct EncryptConfig {
+    #[serde(default)]
+    /// 每次导出都加密，不需要 --encrypt
+    pub always: bool,
+    #[serde(default)]
+    /// 接收方公钥(age1...)，为空时使用口令加密
+    pub recipients: Vec<String>,
+    #[serde(default)]
+    /// 解密用的私钥文件(AGE-SECRET-KEY-...)，ytool decrypt 未指定 --identity 时使用
+    pub identity_file: String,
+    #[serde(default)]
+    /// 口令所在的环境变量，为空时使用 YTOOL_PASSPHRASE，未设置时交互询问
+    pub passphrase_env: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+/// 含子文档的文档（以及TITLE分组）的输出方式
+pub enum DocLayout {
+    /// 目录 + 同级同名文件，如 `a/` 和 `a.md`
+    #[default]
+    Sibling,
+    /// 目录下的 `index.md`，如 `a/index.md`
+    Index,
+    /// 目录下的 `README.md`，如 `a/README.md`
+    Readme,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -68,9 +214,21 @@ pub struct MutualAnswer {
     pub skip: bool,
     /// 是否保留换行标识
     pub line_break: bool,
+    /// 含子文档的文档输出方式
+    pub layout: DocLayout,
+    /// 是否导出隐藏/草稿文档
+    pub include_hidden: bool,
+    /// 导出过滤条件
+    pub filter: DocFilter,
+    /// 导出目标
+    pub target: ExportTarget,
+    /// Obsidian附件目录
+    pub attachment_dir: String,
+    /// 输出路径模板
+    pub path_template: PathTemplates,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 /// 树形节点
 pub struct TreeNone {
     pub parent_id: String,
@@ -90,12 +248,21 @@ pub struct TreeNone {
     pub user: String,
     /// 文档地址
     pub url: String,
+    #[serde(default)]
+    /// 文档最后更新时间，ISO 8601格式
+    pub updated_at: String,
+    #[serde(default)]
+    /// 文档作者login
+    pub author: String,
+    #[serde(default)]
+    /// 文档最后编辑者login
+    pub last_editor: String,
 }
 
 /// 知识库缓存信息
 pub mod cache_book {
     use serde::{Deserialize, Serialize};
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Debug, Default)]
     /// 文档项目
     pub struct DocItem {
         pub title: String,
@@ -109,6 +276,15 @@ pub mod cache_book {
         pub visible: u8,
         /// 这个是必须的，导出的时候需要
         pub url: String,
+        #[serde(default)]
+        /// 文档最后更新时间，ISO 8601格式，旧缓存中没有
+        pub updated_at: String,
+        #[serde(default)]
+        /// 文档作者login，旧缓存中没有
+        pub author: String,
+        #[serde(default)]
+        /// 文档最后编辑者login，旧缓存中没有
+        pub last_editor: String,
     }
     #[derive(Serialize, Deserialize, Debug)]
     /// 知识库项目
@@ -136,4 +312,40 @@ pub struct ResourceItem {
     pub target_save_full_path_name: String,
     /// 资源id
     pub source_id: String,
+    /// 所在目录，第一级为资源库名称，用于路径模板
+    #[serde(default)]
+    pub folder: String,
+    /// 不带扩展名的文件名
+    #[serde(default)]
+    pub file_name: String,
+    /// 扩展名
+    #[serde(default)]
+    pub ext: String,
+}
+
+/// 推送到语雀的节点
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PushedNode {
+    /// 目录节点uuid
+    pub uuid: String,
+    /// 文档id，分组为0
+    #[serde(default)]
+    pub doc_id: u64,
+    #[serde(rename = "type")]
+    /// DOC 或 TITLE
+    pub node_type: String,
+    /// 推送时本地文件的修改时间，没变化的不再更新
+    #[serde(default)]
+    pub modified: u64,
+}
+
+/// 单个知识库的推送记录
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PushMap {
+    /// 本地相对路径 -> 语雀节点
+    #[serde(default)]
+    pub nodes: HashMap<String, PushedNode>,
+    /// 本地图片相对路径 -> 语雀图片地址
+    #[serde(default)]
+    pub images: HashMap<String, String>,
 }
@@ -96,6 +96,47 @@ impl File {
 
         Ok(())
     }
+
+    /// 删除文件夹，但保留文件名为keep的文件及其所在目录，返回保留的数量
+    pub fn rmdir_keep(&self, d: &str, keep: &str) -> Result<usize, Error> {
+        let mut kept = 0;
+
+        for entry in fs::read_dir(d)? {
+            let sub_path = entry?.path();
+            if sub_path.is_dir() {
+                kept += self.rmdir_keep(&sub_path.to_string_lossy(), keep)?;
+            } else if sub_path.file_name().is_some_and(|name| name == keep) {
+                kept += 1;
+            } else {
+                fs::remove_file(&sub_path)?;
+            }
+        }
+
+        if kept == 0 {
+            fs::remove_dir_all(d)?;
+        }
+
+        Ok(kept)
+    }
+
+    /// 自下而上删除空文件夹(包括d本身)，返回删除的数量
+    pub fn remove_empty_dirs(&self, d: &str) -> Result<usize, Error> {
+        let mut removed = 0;
+
+        for entry in fs::read_dir(d)? {
+            let sub_path = entry?.path();
+            if sub_path.is_dir() {
+                removed += self.remove_empty_dirs(&sub_path.to_string_lossy())?;
+            }
+        }
+
+        if fs::read_dir(d)?.next().is_none() {
+            fs::remove_dir(d)?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
 }
 
 #[test]
@@ -133,6 +174,49 @@ fn touch_file() {
     }
 }
 
+#[test]
+fn remove_empty_dirs() {
+    let f = File::new();
+    let dir = "./dev/empty-dirs";
+
+    let _ = f.mkdir(&format!("{}/a/b/c", dir));
+    let _ = f.mkdir(&format!("{}/d", dir));
+    let _ = f.write(&format!("{}/d/doc.md", dir), "内容".to_string());
+
+    assert_eq!(f.remove_empty_dirs(dir).unwrap(), 3);
+    assert!(!f.exists(&format!("{}/a", dir)));
+    assert!(f.exists(&format!("{}/d/doc.md", dir)));
+
+    let _ = f.rmdir(dir);
+}
+
+#[test]
+fn rmdir_keep() {
+    let f = File::new();
+    let dir = tempfile::tempdir().unwrap();
+    let meta_dir = dir.path().join(".meta").to_string_lossy().to_string();
+
+    let _ = f.mkdir(&format!("{}/profiles/work", meta_dir));
+    let _ = f.mkdir(&format!("{}/profiles/home", meta_dir));
+    let _ = f.write(&format!("{}/cookies.json", meta_dir), "{}".to_string());
+    let _ = f.write(
+        &format!("{}/profiles/work/identity.txt", meta_dir),
+        "AGE-SECRET-KEY-1".to_string(),
+    );
+    let _ = f.write(
+        &format!("{}/profiles/home/books_info.json", meta_dir),
+        "[]".to_string(),
+    );
+
+    assert_eq!(f.rmdir_keep(&meta_dir, "identity.txt").unwrap(), 1);
+    assert!(f.exists(&format!("{}/profiles/work/identity.txt", meta_dir)));
+    assert!(!f.exists(&format!("{}/cookies.json", meta_dir)));
+    assert!(!f.exists(&format!("{}/profiles/home", meta_dir)));
+
+    assert_eq!(f.rmdir_keep(&meta_dir, "none").unwrap(), 0);
+    assert!(!f.exists(&meta_dir));
+}
+
 #[test]
 fn touch_file_and_mkdir() {
     let path = std::path::Path::new("./home/roger/foo/bar/baz.txt");
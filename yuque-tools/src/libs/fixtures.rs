@@ -0,0 +1,50 @@
+/*
+ * Description: 单元测试共用的文档和知识库数据
+ * Created: 2026-10-19 05:24:15
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use crate::libs::constants::schema::{
+    cache_book::{BookItem, DocItem},
+    TreeNone,
+};
+
+/// 可见的文档节点，uuid和标题取路径最后一段，其余字段按需覆盖
+pub fn tree_node(full_path: &str) -> TreeNone {
+    let title = full_path.rsplit('/').next().unwrap_or_default();
+    TreeNone {
+        uuid: title.to_string(),
+        full_path: full_path.to_string(),
+        node_type: "DOC".to_string(),
+        title: title.to_string(),
+        visible: 1,
+        ..Default::default()
+    }
+}
+
+/// 缓存中可见的文档，url和uuid相同，其余字段按需覆盖
+pub fn doc_item(title: &str, uuid: &str, parent_uuid: &str) -> DocItem {
+    DocItem {
+        title: title.to_string(),
+        node_type: "DOC".to_string(),
+        uuid: uuid.to_string(),
+        parent_uuid: parent_uuid.to_string(),
+        visible: 1,
+        url: uuid.to_string(),
+        ..Default::default()
+    }
+}
+
+/// vannvan的知识库
+pub fn book_item(name: &str, slug: &str, docs: Vec<DocItem>) -> BookItem {
+    BookItem {
+        name: name.to_string(),
+        slug: slug.to_string(),
+        docs,
+        user_login: "vannvan".to_string(),
+        book_type: "owner".to_string(),
+    }
+}
@@ -0,0 +1,275 @@
+/*
+ * Description: 多语言文案，zh-CN/en-US两套，语言由 --lang 或 LANG 等环境变量决定
+ * Created: 2026-10-19 02:02:00
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{collections::HashMap, ffi::OsString, fmt::Display, sync::OnceLock};
+
+use clap::{Command, ValueEnum};
+use lazy_static::lazy_static;
+use yuque_common::i18n::{env_lang, format, is_english, parse, EN_US, ZH_CN};
+
+/// 命令行帮助的文案前缀，只在en-US中提供，zh-CN直接使用命令定义中的说明
+const CLI_PREFIX: &str = "cli";
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+lazy_static! {
+    static ref ZH_CN_MESSAGES: HashMap<&'static str, &'static str> = parse(ZH_CN);
+    static ref EN_US_MESSAGES: HashMap<&'static str, &'static str> = parse(EN_US);
+}
+
+/// 支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Locale {
+    #[value(name = "zh-CN")]
+    ZhCn,
+    #[value(name = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    /// 从 LANG 等环境变量的值识别语言，如 en_US.UTF-8，无法识别时为zh-CN
+    pub fn from_env_value(value: &str) -> Locale {
+        if is_english(value) {
+            Locale::EnUs
+        } else {
+            Locale::ZhCn
+        }
+    }
+
+    fn messages(&self) -> &'static HashMap<&'static str, &'static str> {
+        match self {
+            Locale::ZhCn => &ZH_CN_MESSAGES,
+            Locale::EnUs => &EN_US_MESSAGES,
+        }
+    }
+}
+
+/// 设置当前语言，需要在输出任何信息之前调用，未指定时从环境变量识别
+pub fn init(lang: Option<Locale>) {
+    let _ = LOCALE.set(lang.unwrap_or_else(detect));
+}
+
+/// 当前语言，未初始化时为zh-CN
+pub fn current() -> Locale {
+    LOCALE.get().copied().unwrap_or(Locale::ZhCn)
+}
+
+/// 按 LC_ALL、LC_MESSAGES、LANG 的顺序识别语言
+fn detect() -> Locale {
+    env_lang()
+        .map(|value| Locale::from_env_value(&value))
+        .unwrap_or(Locale::ZhCn)
+}
+
+/// 解析命令行之前找出 --lang，帮助信息在解析时就要输出
+/// # Arguments
+/// * args - 命令行参数
+pub fn lang_from_args(args: impl IntoIterator<Item = OsString>) -> Option<Locale> {
+    let args: Vec<String> = args
+        .into_iter()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect();
+    let value = args.iter().enumerate().find_map(|(index, arg)| {
+        if arg == "--lang" {
+            args.get(index + 1).cloned()
+        } else {
+            arg.strip_prefix("--lang=").map(|value| value.to_string())
+        }
+    })?;
+    Locale::from_str(&value, true).ok()
+}
+
+/// 当前语言的文案，缺失时依次使用zh-CN的文案和key本身
+pub fn t(key: &'static str) -> &'static str {
+    lookup(current(), key).unwrap_or(key)
+}
+
+/// 带变量的文案
+/// # Arguments
+/// * key - 文案key
+/// * args - 变量名和值，替换文案中的 `{ $name }`
+pub fn tf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    format(t(key), args)
+}
+
+/// 所有语言中的文案，用于识别按语言命名的文件，如导出报告
+pub fn all(key: &'static str) -> Vec<&'static str> {
+    Locale::value_variants()
+        .iter()
+        .filter_map(|locale| locale.messages().get(key).copied())
+        .collect()
+}
+
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    locale
+        .messages()
+        .get(key)
+        .or_else(|| ZH_CN_MESSAGES.get(key))
+        .copied()
+}
+
+/// 按当前语言替换命令行的说明和参数帮助
+pub fn localize(cmd: Command) -> Command {
+    match current() {
+        Locale::ZhCn => cmd,
+        locale => localize_command(locale, cmd, CLI_PREFIX),
+    }
+}
+
+/// 命令说明的key为 cli-子命令-about，参数为 cli-子命令-参数名
+fn localize_command(locale: Locale, cmd: Command, prefix: &str) -> Command {
+    let messages = locale.messages();
+    let about_key = format!("{}-about", prefix);
+    let cmd = match messages.get(about_key.as_str()) {
+        Some(about) => cmd.about(*about),
+        None => cmd,
+    };
+    cmd.mut_args(|arg| {
+        let key = arg_key(prefix, arg.get_id().as_str());
+        match messages.get(key.as_str()) {
+            Some(help) => arg.help(*help),
+            None => arg,
+        }
+    })
+    .mut_subcommands(|sub| {
+        let sub_prefix = format!("{}-{}", prefix, sub.get_name());
+        localize_command(locale, sub, &sub_prefix)
+    })
+}
+
+fn arg_key(prefix: &str, id: &str) -> String {
+    format!("{}-{}", prefix, id.replace('_', "-"))
+}
+
+/// 文案，带变量时替换为对应的值
+/// ```ignore
+/// Log::info(t!("push-start", dir = dir, book = namespace).as_str());
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::libs::i18n::t($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::libs::i18n::tf(
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use regex::Regex;
+
+    use super::*;
+    use crate::core::command::Cli;
+    use clap::CommandFactory;
+
+    /// 文案中的变量名
+    fn placeholders(message: &str) -> HashSet<String> {
+        Regex::new(r"\{ \$(\w+) \}")
+            .unwrap()
+            .captures_iter(message)
+            .map(|caps| caps[1].to_string())
+            .collect()
+    }
+
+    /// 命令定义中所有命令说明和参数帮助对应的key
+    fn cli_keys(cmd: &Command, prefix: &str, keys: &mut HashSet<String>) {
+        keys.insert(format!("{}-about", prefix));
+        for arg in cmd.get_arguments() {
+            keys.insert(arg_key(prefix, arg.get_id().as_str()));
+        }
+        for sub in cmd.get_subcommands() {
+            cli_keys(sub, &format!("{}-{}", prefix, sub.get_name()), keys);
+        }
+    }
+
+    #[test]
+    fn test_catalogue() {
+        let cli = format!("{}-", CLI_PREFIX);
+        let zh: HashSet<&str> = ZH_CN_MESSAGES.keys().copied().collect();
+        let en: HashSet<&str> = EN_US_MESSAGES
+            .keys()
+            .copied()
+            .filter(|key| !key.starts_with(&cli))
+            .collect();
+        assert_eq!(zh, en);
+
+        for key in zh {
+            assert_eq!(
+                placeholders(ZH_CN_MESSAGES[key]),
+                placeholders(EN_US_MESSAGES[key]),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_help() {
+        let mut expected = HashSet::new();
+        cli_keys(&Cli::command(), CLI_PREFIX, &mut expected);
+        let cli = format!("{}-", CLI_PREFIX);
+        let actual: HashSet<String> = EN_US_MESSAGES
+            .keys()
+            .filter(|key| key.starts_with(&cli))
+            .map(|key| key.to_string())
+            .collect();
+        assert_eq!(expected, actual);
+
+        let cmd = localize_command(Locale::EnUs, Cli::command(), CLI_PREFIX);
+        let pull = cmd.find_subcommand("pull").unwrap();
+        assert_eq!(
+            pull.get_about().unwrap().to_string(),
+            EN_US_MESSAGES["cli-pull-about"]
+        );
+        let dry_run = pull
+            .get_arguments()
+            .find(|arg| arg.get_id() == "dry_run")
+            .unwrap();
+        assert_eq!(
+            dry_run.get_help().unwrap().to_string(),
+            EN_US_MESSAGES["cli-pull-dry-run"]
+        );
+    }
+
+    #[test]
+    fn test_lang_from_args() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            lang_from_args(args(&["ytool", "--lang", "en-US", "ls"])),
+            Some(Locale::EnUs)
+        );
+        assert_eq!(
+            lang_from_args(args(&["ytool", "ls", "--lang=zh-cn"])),
+            Some(Locale::ZhCn)
+        );
+        assert_eq!(lang_from_args(args(&["ytool", "ls"])), None);
+        assert_eq!(lang_from_args(args(&["ytool", "--lang", "fr"])), None);
+
+        assert_eq!(Locale::from_env_value("en_US.UTF-8"), Locale::EnUs);
+        assert_eq!(Locale::from_env_value("zh_CN.UTF-8"), Locale::ZhCn);
+        assert_eq!(Locale::from_env_value("C"), Locale::ZhCn);
+    }
+
+    #[test]
+    fn test_tf() {
+        assert_eq!(t("book-not-found"), ZH_CN_MESSAGES["book-not-found"]);
+        assert_eq!(t("no-such-key"), "no-such-key");
+        assert_eq!(all("report-file"), vec!["导出报告.md", "export-report.md"]);
+        assert_eq!(
+            crate::t!("push-node-success", path = "book/a.md"),
+            "推送成功 book/a.md"
+        );
+    }
+}
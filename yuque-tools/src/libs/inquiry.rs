@@ -8,15 +8,19 @@
  */
 
 use std::process;
+use tracing::trace;
 use utf8_slice::{self, slice};
 
-use inquire::{Confirm, InquireError, MultiSelect, Password, PasswordDisplayMode, Text};
+use inquire::{Confirm, InquireError, MultiSelect, Password, PasswordDisplayMode, Select, Text};
 
 use super::{
-    constants::schema::{MutualAnswer, YuqueAccount},
+    constants::schema::{
+        DocFilter, DocLayout, ExportTarget, MutualAnswer, PathTemplates, YuqueAccount,
+    },
     log::Log,
     tools,
 };
+use crate::t;
 
 /// 询问用户导出知识库的选项
 pub fn ask_user_toc_options() -> MutualAnswer {
@@ -24,13 +28,17 @@ pub fn ask_user_toc_options() -> MutualAnswer {
         toc_range: vec![],
         skip: true,
         line_break: true,
+        layout: DocLayout::Sibling,
+        include_hidden: false,
+        filter: DocFilter::default(),
+        target: ExportTarget::Markdown,
+        attachment_dir: "".to_string(),
+        path_template: PathTemplates::default(),
     };
 
     match tools::get_cache_books_info() {
         Ok(books_info) => {
-            if cfg!(debug_assertions) {
-                // println!("知识库信息：{:?}", books_info);
-            }
+            trace!("知识库信息：{:?}", books_info);
 
             // 询问知识库
             let mut options: Vec<String> = vec![];
@@ -46,8 +54,8 @@ pub fn ask_user_toc_options() -> MutualAnswer {
 
             // 选择知识库
             let books_ans: Result<Vec<String>, InquireError> =
-                MultiSelect::new("请选择知识库", options)
-                    .with_help_message("空格选中/取消选中，⬆ ⬇ 键移动选择")
+                MultiSelect::new(t!("inquiry-select-books"), options)
+                    .with_help_message(t!("inquiry-select-books-help"))
                     .prompt();
             match books_ans {
                 Ok(choice) => {
@@ -56,34 +64,61 @@ pub fn ask_user_toc_options() -> MutualAnswer {
                         .map(|s| slice(s, 2, s.len()).to_string())
                         .collect()
                 }
-                Err(_) => panic!("未选择知识库，程序退出"),
+                Err(_) => panic!("{}", t!("inquiry-no-book-selected")),
             }
 
             // 确认是否跳过本地文件
-            let skip_ans = Confirm::new("是否跳过本地文件?")
-                .with_default(true)
-                .prompt();
+            let skip_ans = Confirm::new(t!("inquiry-skip")).with_default(true).prompt();
 
             match skip_ans {
                 Ok(true) => answer.skip = true,
                 Ok(false) => answer.skip = false,
-                Err(_) => panic!("选择出错，程序退出"),
+                Err(_) => panic!("{}", t!("inquiry-select-failed")),
             }
 
             // 确认是否保留语雀换行标识
-            let lb_ans = Confirm::new("是否保留语雀换行标识?")
+            let lb_ans = Confirm::new(t!("inquiry-line-break"))
                 .with_default(true)
-                .with_help_message("</br>在不同平台处理逻辑存在差异，可按需选择是否保留")
+                .with_help_message(t!("inquiry-line-break-help"))
                 .prompt();
 
             match lb_ans {
                 Ok(true) => answer.line_break = true,
                 Ok(false) => answer.line_break = false,
-                Err(_) => panic!("选择出错，程序退出"),
+                Err(_) => panic!("{}", t!("inquiry-select-failed")),
+            }
+
+            // 含子文档的文档输出方式
+            let layout_options = vec![
+                t!("inquiry-layout-sibling"),
+                t!("inquiry-layout-index"),
+                t!("inquiry-layout-readme"),
+            ];
+            let layout_ans = Select::new(t!("inquiry-layout"), layout_options)
+                .with_help_message(t!("inquiry-layout-help"))
+                .prompt();
+
+            match layout_ans {
+                Ok(choice) if choice.contains("index.md") => answer.layout = DocLayout::Index,
+                Ok(choice) if choice.contains("README.md") => answer.layout = DocLayout::Readme,
+                Ok(_) => answer.layout = DocLayout::Sibling,
+                Err(_) => panic!("{}", t!("inquiry-select-failed")),
+            }
+
+            // 确认是否导出隐藏文档
+            let hidden_ans = Confirm::new(t!("inquiry-hidden"))
+                .with_default(false)
+                .with_help_message(t!("inquiry-hidden-help"))
+                .prompt();
+
+            match hidden_ans {
+                Ok(true) => answer.include_hidden = true,
+                Ok(false) => answer.include_hidden = false,
+                Err(_) => panic!("{}", t!("inquiry-select-failed")),
             }
         }
         Err(_) => {
-            Log::error("知识库文件读取失败，程序退出");
+            Log::error(t!("inquiry-books-read-failed"));
             process::exit(1);
         }
     }
@@ -97,13 +132,13 @@ pub fn ask_user_account() -> YuqueAccount {
         password: "".to_string(),
     };
 
-    let username = Text::new("yuque username:").prompt();
+    let username = Text::new(t!("inquiry-username")).prompt();
     match username {
         Ok(username) => account.username = username,
-        Err(_) => panic!("username 填写出错，程序退出"),
+        Err(_) => panic!("{}", t!("inquiry-username-failed")),
     }
 
-    let password = Password::new("yuque password:")
+    let password = Password::new(t!("inquiry-password"))
         .without_confirmation()
         .with_display_mode(PasswordDisplayMode::Masked)
         .prompt();
@@ -111,7 +146,7 @@ pub fn ask_user_account() -> YuqueAccount {
     match password {
         Ok(password) => account.password = password,
         Err(_) => {
-            panic!("password 填写出错，程序退出");
+            panic!("{}", t!("inquiry-password-failed"));
         }
     }
     account
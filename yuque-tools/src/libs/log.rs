@@ -1,5 +1,5 @@
 /*
- * Description: 打印信息
+ * Description: 打印信息，基于tracing，支持 -v/-vv 调试信息、JSON日志文件和敏感信息脱敏
  * Created: 2023-08-30 09:24:54
  * Author: vannvan
  * Email : adoerww@gmail.com
@@ -7,53 +7,328 @@
  * Copyright (c) https://github.com/vannvan
  */
 
+use std::{
+    fmt::Debug,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    sync::OnceLock,
+};
+
+use lazy_static::lazy_static;
 use owo_colors::OwoColorize;
+use regex::Regex;
+use serde::Serialize;
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{
+    filter::{LevelFilter, Targets},
+    fmt::{
+        self as tracing_fmt, format::Writer, FmtContext, FormatEvent, FormatFields,
+        FormattedFields, MakeWriter,
+    },
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+    Layer,
+};
 
-#[allow(dead_code)]
-pub enum Action {
-    SUCCESS,
-    INFO,
-    ERROR,
-    WARN,
-}
-#[allow(dead_code)]
-pub fn dump_log(action: Action, str: String) {
-    const NAME: &str = "ytool->";
-    match action {
-        Action::SUCCESS => {
-            println!("{NAME} {}", str.green())
-        }
-        Action::INFO => {
-            println!("{NAME} {}", str.cyan())
-        }
-        Action::ERROR => {
-            println!("{NAME} {}", str.red())
-        }
-        Action::WARN => {
-            println!("{NAME} {}", str.yellow())
-        }
-    }
+use crate::{
+    libs::{file::File, progress},
+    t,
+};
+
+const NAME: &str = "ytool->";
+
+/// 本程序的日志target前缀，用于和依赖库的日志区分
+const TARGET: &str = "ytool";
+
+/// 通过 --dump-dir 指定的中间数据输出目录
+static DUMP_DIR: OnceLock<String> = OnceLock::new();
+
+lazy_static! {
+    /// 引号包裹的cookie整体脱敏
+    static ref COOKIE_REGEX: Regex =
+        Regex::new(r#"(?i)(cookies?\\?"?\s*[:=]\s*\\?")([^"\\]*)"#).unwrap();
+    /// 密码、token、会话等键值对脱敏
+    static ref SECRET_REGEX: Regex = Regex::new(
+        r#"(?i)(password|passwd|token|authorization|session|cookies?)(\\?"?\s*[:=]\s*\\?"?)([^"\\\s,;&})]+)"#
+    )
+    .unwrap();
 }
 
 pub struct Log;
 
-const NAME: &str = "ytool->";
 #[allow(dead_code)]
 impl Log {
     /// 成功消息
     pub fn success(str: &str) {
-        println!("{NAME} {}", str.green())
+        if tracing::dispatcher::has_been_set() {
+            tracing::info!(success = true, "{}", str)
+        } else {
+            println!("{NAME} {}", str.green())
+        }
     }
     /// 普通消息
     pub fn info(str: &str) {
-        println!("{NAME} {}", str.cyan())
+        if tracing::dispatcher::has_been_set() {
+            tracing::info!("{}", str)
+        } else {
+            println!("{NAME} {}", str.cyan())
+        }
     }
     /// 错误消息
     pub fn error(str: &str) {
-        println!("{NAME} {}", str.red())
+        if tracing::dispatcher::has_been_set() {
+            tracing::error!("{}", str)
+        } else {
+            println!("{NAME} {}", str.red())
+        }
     }
     /// 警告消息
     pub fn warn(str: &str) {
-        println!("{NAME} {}", str.yellow())
+        if tracing::dispatcher::has_been_set() {
+            tracing::warn!("{}", str)
+        } else {
+            println!("{NAME} {}", str.yellow())
+        }
+    }
+
+    /// 初始化日志，需要在输出任何信息之前调用
+    /// # Arguments
+    /// * verbose - 0 只输出提示信息，1 输出调试信息，2 及以上输出更详细的信息(含依赖库)
+    /// * log_file - 同时以JSON格式写入的日志文件
+    /// * dump_dir - 中间数据(目录树等)的输出目录
+    pub fn init(
+        verbose: u8,
+        log_file: Option<&str>,
+        dump_dir: Option<&str>,
+    ) -> Result<(), &'static str> {
+        if let Some(dir) = dump_dir {
+            let _ = DUMP_DIR.set(dir.trim_end_matches('/').to_string());
+        }
+
+        let console = tracing_fmt::layer()
+            .event_format(ConsoleFormat)
+            .with_writer(|| Redacted(Console))
+            .with_filter(Self::level_filter(verbose));
+
+        let file = match log_file {
+            Some(log_file) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(log_file)
+                    .map_err(|_| t!("log-file-create-failed"))?;
+                // 日志文件至少记录调试信息，便于事后排查
+                Some(
+                    tracing_fmt::layer()
+                        .json()
+                        .with_current_span(true)
+                        .with_span_list(true)
+                        .with_writer(LogFile(file))
+                        .with_filter(Self::level_filter(verbose.max(1))),
+                )
+            }
+            None => None,
+        };
+
+        tracing_subscriber::registry()
+            .with(console)
+            .with(file)
+            .try_init()
+            .map_err(|_| t!("log-init-failed"))
+    }
+
+    /// 输出中间数据，只有指定了 --dump-dir 时才写入
+    /// # Arguments
+    /// * name - 文件名，如 tree-doc.json
+    /// * value - 数据
+    pub fn dump<T: Serialize + ?Sized>(name: &str, value: &T) {
+        let dir = match DUMP_DIR.get() {
+            Some(dir) => dir,
+            None => return,
+        };
+        let f = File::new();
+        let target = format!("{}/{}", dir, name);
+        let content = match serde_json::to_string_pretty(value) {
+            Ok(content) => content,
+            Err(_) => return,
+        };
+        if f.mkdir(dir).is_err() || f.write(&target, content).is_err() {
+            Log::warn(&t!("log-dump-failed", file = target));
+        } else {
+            tracing::debug!(file = %target, "已输出中间数据");
+        }
+    }
+
+    /// 隐藏日志中的密码、cookie和token
+    pub fn redact(str: &str) -> String {
+        let str = COOKIE_REGEX.replace_all(str, "$1***");
+        SECRET_REGEX.replace_all(&str, "$1$2***").to_string()
+    }
+
+    fn level_filter(verbose: u8) -> Targets {
+        match verbose {
+            0 => Targets::new().with_target(TARGET, LevelFilter::INFO),
+            1 => Targets::new().with_target(TARGET, LevelFilter::DEBUG),
+            _ => Targets::new()
+                .with_target(TARGET, LevelFilter::TRACE)
+                .with_default(LevelFilter::DEBUG),
+        }
+    }
+}
+
+/// 终端输出格式，提示信息保持原来的样式，调试信息带上所在的知识库/文档
+struct ConsoleFormat;
+
+impl<S, N> FormatEvent<S, N> for ConsoleFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+
+        let level = *event.metadata().level();
+        let message = visitor.message;
+        match level {
+            Level::ERROR => return writeln!(writer, "{NAME} {}", message.red()),
+            Level::WARN => return writeln!(writer, "{NAME} {}", message.yellow()),
+            Level::INFO if visitor.success => {
+                return writeln!(writer, "{NAME} {}", message.green())
+            }
+            Level::INFO => return writeln!(writer, "{NAME} {}", message.cyan()),
+            _ => {}
+        }
+
+        write!(writer, "{} ", format!("{:>5}", level).dimmed())?;
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                write!(writer, "{}", span.name().bold())?;
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<FormattedFields<N>>() {
+                    if !fields.is_empty() {
+                        write!(writer, "{{{}}}", fields)?;
+                    }
+                }
+                write!(writer, ": ")?;
+            }
+        }
+        writeln!(writer, "{}{}", message, visitor.fields.dimmed())
+    }
+}
+
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+    success: bool,
+}
+
+impl Visit for EventVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "success" {
+            self.success = value;
+        } else {
+            self.fields
+                .push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+}
+
+/// 写入前脱敏，每条日志格式化完成后一次性写入，因此可以按条处理
+struct Redacted<W: Write>(W);
+
+impl<W: Write> Write for Redacted<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .write_all(Log::redact(&String::from_utf8_lossy(buf)).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// 终端输出，显示进度条时先隐藏进度条再输出
+struct Console;
+
+impl Write for Console {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        progress::suspend(|| io::stdout().write_all(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+/// JSON日志文件，以追加方式打开，每条日志一次写入
+struct LogFile(fs::File);
+
+impl<'a> MakeWriter<'a> for LogFile {
+    type Writer = Redacted<&'a fs::File>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        Redacted(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            Log::redact(r#"UserCliConfig { username: "vannvan", password: "123456", host: "" }"#),
+            r#"UserCliConfig { username: "vannvan", password: "***", host: "" }"#
+        );
+        assert_eq!(
+            Log::redact(r#"{"expire_time":1,"cookies":"_yuque_session=abc; yuque_ctoken=xyz"}"#),
+            r#"{"expire_time":1,"cookies":"***"}"#
+        );
+        // JSON日志中字符串里的引号会被转义
+        assert_eq!(
+            Log::redact(r#"{"message":"user_config: { password: \"123456\" }"}"#),
+            r#"{"message":"user_config: { password: \"***\" }"}"#
+        );
+        assert_eq!(
+            Log::redact("cookie: _yuque_session=abc; path=/"),
+            "cookie: ***; path=/"
+        );
+        assert_eq!(
+            Log::redact("GET-> https://www.yuque.com/api/docs?book_id=1&token=abc"),
+            "GET-> https://www.yuque.com/api/docs?book_id=1&token=***"
+        );
+        assert_eq!(Log::redact("获取知识库成功"), "获取知识库成功");
     }
 }
@@ -1,7 +1,14 @@
+pub mod cipher;
 pub mod constants;
 pub mod encrypt;
 pub mod file;
+#[cfg(test)]
+pub mod fixtures;
+pub mod i18n;
 pub mod inquiry;
 pub mod log;
+pub mod profile;
+pub mod progress;
 pub mod request;
+pub mod search_index;
 pub mod tools;
@@ -0,0 +1,244 @@
+/*
+ * Description: 多账号/多空间配置档案
+ * Created: 2026-10-19 00:16:06
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{fs, io::Error, path::Path};
+
+use crate::t;
+
+use super::{
+    cipher,
+    constants::{load_config::GlobalConfig, schema::Profile},
+};
+
+/// 读取所有配置档案，文件不存在时返回空列表
+pub fn read_profiles(profiles_file: &str) -> Vec<Profile> {
+    if !Path::new(profiles_file).exists() {
+        return vec![];
+    }
+    let data = fs::read_to_string(profiles_file).expect("配置档案文件读取失败");
+    serde_json::from_str(&data).expect("配置档案文件解析失败，请检查格式是否正确")
+}
+
+/// 写入所有配置档案
+pub fn write_profiles(profiles_file: &str, profiles: &Vec<Profile>) -> Result<(), Error> {
+    let json_string = serde_json::to_string_pretty(profiles).unwrap();
+    fs::write(profiles_file, json_string)
+}
+
+/// 新增配置档案，同名的会被覆盖，返回是否为覆盖
+pub fn add_profile(profiles_file: &str, profile: Profile) -> Result<bool, Error> {
+    let mut profiles = read_profiles(profiles_file);
+    let replaced = profiles.iter().any(|item| item.name == profile.name);
+
+    profiles.retain(|item| item.name != profile.name);
+    profiles.push(profile);
+    write_profiles(profiles_file, &profiles)?;
+
+    Ok(replaced)
+}
+
+/// 删除配置档案，返回是否存在该档案
+pub fn remove_profile(profiles_file: &str, name: &str) -> Result<bool, Error> {
+    let mut profiles = read_profiles(profiles_file);
+    let count = profiles.len();
+
+    profiles.retain(|item| item.name != name);
+    if profiles.len() == count {
+        return Ok(false);
+    }
+    write_profiles(profiles_file, &profiles)?;
+
+    Ok(true)
+}
+
+/// 为配置档案生成加密导出用的密钥，私钥写入文件，公钥追加到档案中
+/// 返回 (私钥文件, 公钥)
+/// # Arguments
+/// * config - 全局配置
+/// * name - 档案名称
+/// * identity_file - 私钥文件，为空时为配置档案文件旁的 yuque.keys/{name}.txt
+pub fn generate_profile_key(
+    config: &GlobalConfig,
+    name: &str,
+    identity_file: Option<String>,
+) -> Result<(String, String), String> {
+    let mut profiles = read_profiles(&config.profiles_file);
+    let target = profiles
+        .iter_mut()
+        .find(|item| item.name == name)
+        .ok_or_else(|| t!("profile-missing", name = name))?;
+
+    let identity_file = identity_file.unwrap_or_else(|| profile_key_file(config, name));
+    // 已有的私钥可能还要用来解密之前的导出，不覆盖
+    if Path::new(&identity_file).exists() {
+        return Err(t!("profile-keygen-exists", file = identity_file));
+    }
+    if let Some(dir) = Path::new(&identity_file).parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    let (content, recipient) = cipher::generate_identity();
+    write_private_file(&identity_file, &content)
+        .map_err(|_| t!("profile-keygen-failed").to_string())?;
+
+    target.encrypt.recipients.push(recipient.to_string());
+    target.encrypt.identity_file = identity_file.to_string();
+    write_profiles(&config.profiles_file, &profiles)
+        .map_err(|_| t!("profile-write-failed").to_string())?;
+
+    Ok((identity_file, recipient))
+}
+
+/// 旧版本默认保存在档案缓存目录中的私钥文件名，清除缓存时保留
+pub const LEGACY_IDENTITY_FILE: &str = "identity.txt";
+
+/// 配置档案默认的私钥文件，放在配置档案文件旁边
+/// 不能放在缓存目录中，否则 ytool clear 后之前的加密导出都无法解密
+pub fn profile_key_file(config: &GlobalConfig, name: &str) -> String {
+    let dir = Path::new(&config.profiles_file)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(|dir| format!("{}/", dir.to_string_lossy()))
+        .unwrap_or_default();
+    format!("{}yuque.keys/{}.txt", dir, name)
+}
+
+/// 写入只有当前用户可读的文件
+fn write_private_file(file_path: &str, content: &str) -> Result<(), Error> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(file_path)?;
+    std::io::Write::write_all(&mut file, content.as_bytes())
+}
+
+/// 配置档案的缓存目录
+pub fn profile_meta_dir(config: &GlobalConfig, profile: &Profile) -> String {
+    if profile.meta_dir.is_empty() {
+        format!("{}/profiles/{}", config.meta_dir, profile.name)
+    } else {
+        profile.meta_dir.to_string()
+    }
+}
+
+/// 将配置档案的缓存目录和输出目录应用到全局配置
+/// 注意这里在GLOBAL_CONFIG初始化过程中调用，不能访问GLOBAL_CONFIG
+pub fn apply_profile(config: &mut GlobalConfig, profile: &Profile) {
+    let meta_dir = profile_meta_dir(config, profile);
+
+    config.cookies_file = format!("{}/cookies.json", meta_dir);
+    config.user_info_file = format!("{}/user_info.json", meta_dir);
+    config.books_info_file = format!("{}/books_info.json", meta_dir);
+    config.search_index_file = format!("{}/search_index.json", meta_dir);
+    config.push_map_file = format!("{}/push_map.json", meta_dir);
+    config.watch_status_file = format!("{}/watch_status.json", meta_dir);
+    config.meta_dir = meta_dir;
+
+    config.target_output_dir = if profile.output.is_empty() {
+        format!("{}/{}", config.target_output_dir, profile.name)
+    } else {
+        profile.output.to_string()
+    };
+    config.target_resource_dir = format!("{}/{}", config.target_resource_dir, profile.name);
+    config.profile = Some(profile.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::constants::load_config::load_conf;
+
+    fn new_profile(name: &str) -> Profile {
+        Profile {
+            name: name.to_string(),
+            username: "".to_string(),
+            password: "".to_string(),
+            host: "https://team.yuque.com".to_string(),
+            token: "".to_string(),
+            meta_dir: "".to_string(),
+            output: "".to_string(),
+            encrypt: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove_profile() {
+        let profiles_file = "./dev/test.profiles.json";
+        let _ = fs::create_dir_all("./dev");
+        let _ = fs::remove_file(profiles_file);
+
+        assert!(!add_profile(profiles_file, new_profile("work")).unwrap());
+        assert!(!add_profile(profiles_file, new_profile("home")).unwrap());
+        assert!(add_profile(profiles_file, new_profile("work")).unwrap());
+        assert_eq!(read_profiles(profiles_file).len(), 2);
+
+        assert!(remove_profile(profiles_file, "work").unwrap());
+        assert!(!remove_profile(profiles_file, "work").unwrap());
+        assert_eq!(read_profiles(profiles_file)[0].name, "home");
+
+        let _ = fs::remove_file(profiles_file);
+    }
+
+    #[test]
+    fn test_apply_profile() {
+        let mut config = load_conf();
+        apply_profile(&mut config, &new_profile("work"));
+
+        assert_eq!(config.meta_dir, ".meta/profiles/work");
+        assert_eq!(config.cookies_file, ".meta/profiles/work/cookies.json");
+        assert_eq!(
+            config.books_info_file,
+            ".meta/profiles/work/books_info.json"
+        );
+        assert_eq!(config.target_output_dir, "./docs/work");
+        assert!(config.profile.is_some());
+    }
+
+    #[test]
+    fn test_generate_profile_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = load_conf();
+        config.profiles_file = dir
+            .path()
+            .join("profiles.json")
+            .to_string_lossy()
+            .to_string();
+        add_profile(&config.profiles_file, new_profile("work")).unwrap();
+
+        let identity_file = dir.path().join("key.txt").to_string_lossy().to_string();
+        let (file, recipient) =
+            generate_profile_key(&config, "work", Some(identity_file.to_string())).unwrap();
+        assert_eq!(file, identity_file);
+        assert!(recipient.starts_with("age1"));
+
+        let profile = &read_profiles(&config.profiles_file)[0];
+        assert_eq!(profile.encrypt.recipients, vec![recipient]);
+        assert_eq!(profile.encrypt.identity_file, identity_file);
+        assert_eq!(cipher::read_identities(&identity_file).unwrap().len(), 1);
+
+        // 不覆盖已有的私钥
+        assert!(generate_profile_key(&config, "work", Some(identity_file)).is_err());
+        assert!(generate_profile_key(&config, "home", None).is_err());
+
+        // 默认的私钥文件不在缓存目录中
+        add_profile(&config.profiles_file, new_profile("home")).unwrap();
+        let (file, _) = generate_profile_key(&config, "home", None).unwrap();
+        assert_eq!(
+            file,
+            format!("{}/yuque.keys/home.txt", dir.path().display())
+        );
+        let meta_dir = profile_meta_dir(&config, &new_profile("home"));
+        assert!(!Path::new(&file).starts_with(&meta_dir));
+        assert!(Path::new(&file).exists());
+    }
+}
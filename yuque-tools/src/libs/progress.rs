@@ -0,0 +1,344 @@
+/*
+ * Description: 统一的下载进度，按知识库分行显示，非终端环境下逐行输出
+ * Created: 2026-10-19 01:22:35
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    io::{self, IsTerminal},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use indicatif::{
+    HumanBytes, HumanDuration, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle,
+};
+use lazy_static::lazy_static;
+
+use super::{log::Log, tools};
+use crate::t;
+
+lazy_static! {
+    /// 正在显示的进度，输出日志时先隐藏进度条，避免和进度条错行
+    static ref ACTIVE: Mutex<Option<MultiProgress>> = Mutex::new(None);
+}
+
+/// 在进度条上方输出内容，没有进度条时直接执行
+pub fn suspend<F: FnOnce() -> R, R>(f: F) -> R {
+    let active = ACTIVE.lock().ok().and_then(|active| active.clone());
+    match active {
+        Some(multi) => multi.suspend(f),
+        None => f(),
+    }
+}
+
+/// 一个分组(知识库)的下载情况
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GroupStat {
+    pub name: String,
+    /// 需要下载的数量
+    pub total: u64,
+    pub success: u64,
+    pub failed: u64,
+    pub skipped: u64,
+    pub bytes: u64,
+}
+
+struct GroupState {
+    stat: GroupStat,
+    bar: ProgressBar,
+}
+
+pub struct Progress {
+    /// 下载内容的名称，如 文档、资源
+    label: String,
+    multi: MultiProgress,
+    total_bar: ProgressBar,
+    groups: Mutex<Vec<GroupState>>,
+    is_tty: bool,
+    started_at: Instant,
+}
+
+impl Progress {
+    /// 创建进度，每个分组一个进度条，最后一行为总进度
+    /// # Arguments
+    /// * label - 下载内容的名称，如 文档、资源
+    /// * groups - 分组名称及需要下载的数量，按显示顺序排列
+    pub fn new(label: &str, groups: Vec<(String, u64)>) -> Self {
+        let is_tty = io::stdout().is_terminal();
+        let multi = if is_tty {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        };
+
+        let group_style = ProgressStyle::with_template(
+            "{prefix:.bold} [{bar:30.cyan/blue}] {pos}/{len} {wide_msg}",
+        )
+        .unwrap()
+        .progress_chars("##-");
+        let groups = groups
+            .into_iter()
+            .map(|(name, total)| {
+                let bar = multi.add(ProgressBar::new(total));
+                bar.set_style(group_style.clone());
+                bar.set_prefix(name.to_string());
+                GroupState {
+                    stat: GroupStat {
+                        name,
+                        total,
+                        ..Default::default()
+                    },
+                    bar,
+                }
+            })
+            .collect::<Vec<GroupState>>();
+
+        let total = groups.iter().map(|group| group.stat.total).sum();
+        let total_bar = multi.add(ProgressBar::new(total));
+        total_bar.set_style(
+            ProgressStyle::with_template(&format!(
+                "[{{elapsed_precise}}] [{{bar:40.green/blue}}] {{pos}}/{{len}} {{msg}} {} {{eta}}",
+                t!("progress-eta")
+            ))
+            .unwrap()
+            .progress_chars("##-"),
+        );
+
+        let progress = Progress {
+            label: label.to_string(),
+            multi,
+            total_bar,
+            groups: Mutex::new(groups),
+            is_tty,
+            started_at: Instant::now(),
+        };
+        progress.refresh_total(0, 0);
+        if is_tty {
+            if let Ok(mut active) = ACTIVE.lock() {
+                *active = Some(progress.multi.clone());
+            }
+        }
+        progress
+    }
+
+    /// 开始下载，分组的进度条显示正在下载的内容
+    pub fn start(&self, group: &str, item: &str) {
+        if let Ok(groups) = self.groups.lock() {
+            if let Some(state) = groups.iter().find(|state| state.stat.name == group) {
+                state.bar.set_message(format!("⇣ {}", item));
+            }
+        }
+    }
+
+    /// 本地已存在等原因跳过的，只计入汇总
+    pub fn skip(&self, group: &str) {
+        self.update(group, |stat| stat.skipped += 1);
+    }
+
+    /// 下载结束
+    /// # Arguments
+    /// * group - 分组名称
+    /// * item - 下载的内容，非终端环境下输出
+    /// * bytes - 下载的字节数，失败时为空
+    pub fn finish(&self, group: &str, item: &str, bytes: Option<u64>) {
+        let (done, total) = self.update(group, |stat| match bytes {
+            Some(bytes) => {
+                stat.success += 1;
+                stat.bytes += bytes;
+            }
+            None => stat.failed += 1,
+        });
+
+        if !self.is_tty {
+            match bytes {
+                Some(bytes) => Log::info(&t!(
+                    "progress-done",
+                    done = done,
+                    total = total,
+                    item = item,
+                    size = HumanBytes(bytes)
+                )),
+                None => Log::error(&t!(
+                    "progress-failed",
+                    done = done,
+                    total = total,
+                    item = item
+                )),
+            }
+        }
+    }
+
+    /// 结束所有进度条并输出汇总表
+    pub fn finish_all(&self) -> Vec<GroupStat> {
+        if let Ok(mut active) = ACTIVE.lock() {
+            *active = None;
+        }
+        let stats = match self.groups.lock() {
+            Ok(groups) => {
+                for state in groups.iter() {
+                    state.bar.finish_and_clear();
+                }
+                groups.iter().map(|state| state.stat.clone()).collect()
+            }
+            Err(_) => vec![],
+        };
+        self.total_bar.finish_and_clear();
+        let _ = self.multi.clear();
+
+        println!(
+            "{}",
+            Self::summary_table(&self.label, &stats, self.started_at.elapsed())
+        );
+        stats
+    }
+
+    /// 汇总表，每个分组一行，最后一行为合计
+    pub fn summary_table(label: &str, stats: &[GroupStat], elapsed: Duration) -> String {
+        let mut total = GroupStat {
+            name: t!("progress-row-total").to_string(),
+            ..Default::default()
+        };
+        for stat in stats {
+            total.success += stat.success;
+            total.failed += stat.failed;
+            total.skipped += stat.skipped;
+            total.bytes += stat.bytes;
+        }
+
+        let mut rows: Vec<Vec<String>> = vec![[
+            "progress-col-group",
+            "progress-col-success",
+            "progress-col-failed",
+            "progress-col-skipped",
+            "progress-col-size",
+        ]
+        .map(|key| t!(key).to_string())
+        .to_vec()];
+        for stat in stats.iter().chain([&total]) {
+            rows.push(vec![
+                stat.name.to_string(),
+                stat.success.to_string(),
+                stat.failed.to_string(),
+                stat.skipped.to_string(),
+                HumanBytes(stat.bytes).to_string(),
+            ]);
+        }
+
+        let mut table = format!("{}\n", t!("progress-summary", label = label));
+        for line in tools::format_table(&rows) {
+            table.push_str(&format!("  {}\n", line));
+        }
+        table.push_str(&format!(
+            "  {}",
+            t!("progress-elapsed", time = HumanDuration(elapsed))
+        ));
+        table
+    }
+
+    /// 更新分组和总进度，返回已完成数量和总数
+    fn update<F: FnOnce(&mut GroupStat)>(&self, group: &str, f: F) -> (u64, u64) {
+        let mut groups = match self.groups.lock() {
+            Ok(groups) => groups,
+            Err(_) => return (0, 0),
+        };
+        let state = match groups.iter_mut().find(|state| state.stat.name == group) {
+            Some(state) => state,
+            None => return (0, 0),
+        };
+
+        let before = state.stat.success + state.stat.failed;
+        f(&mut state.stat);
+        if state.stat.success + state.stat.failed > before {
+            state.bar.inc(1);
+            self.total_bar.inc(1);
+            if state.bar.position() >= state.stat.total {
+                state.bar.set_message(t!("progress-group-done"));
+            }
+        }
+
+        let bytes = groups.iter().map(|state| state.stat.bytes).sum();
+        let failed = groups.iter().map(|state| state.stat.failed).sum();
+        self.refresh_total(bytes, failed);
+        (
+            self.total_bar.position(),
+            self.total_bar.length().unwrap_or_default(),
+        )
+    }
+
+    fn refresh_total(&self, bytes: u64, failed: u64) {
+        self.total_bar.set_message(t!(
+            "progress-total-status",
+            bytes = HumanBytes(bytes),
+            failed = failed
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_stats() {
+        let progress = Progress::new(
+            "文档",
+            vec![("Rust笔记".to_string(), 2), ("团队手册".to_string(), 1)],
+        );
+        progress.start("Rust笔记", "Rust笔记/所有权");
+        progress.finish("Rust笔记", "Rust笔记/所有权", Some(1024));
+        progress.finish("Rust笔记", "Rust笔记/生命周期", None);
+        progress.skip("团队手册");
+        progress.finish("团队手册", "团队手册/入职指南", Some(24));
+
+        let stats = progress.finish_all();
+        assert_eq!(
+            stats[0],
+            GroupStat {
+                name: "Rust笔记".to_string(),
+                total: 2,
+                success: 1,
+                failed: 1,
+                skipped: 0,
+                bytes: 1024,
+            }
+        );
+        assert_eq!(stats[1].skipped, 1);
+        assert_eq!(stats[1].success, 1);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let stats = vec![
+            GroupStat {
+                name: "Rust笔记".to_string(),
+                total: 3,
+                success: 2,
+                failed: 1,
+                skipped: 10,
+                bytes: 2048,
+            },
+            GroupStat {
+                name: "book".to_string(),
+                total: 1,
+                success: 1,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            Progress::summary_table("文档", &stats, Duration::from_secs(3)),
+            [
+                "文档下载汇总：",
+                "  分组      成功  失败  跳过      大小",
+                "  Rust笔记     2     1    10  2.00 KiB",
+                "  book         1     0     0       0 B",
+                "  合计         3     1    10  2.00 KiB",
+                "  耗时 3 seconds",
+            ]
+            .join("\n")
+        );
+    }
+}
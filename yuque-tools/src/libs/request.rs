@@ -9,19 +9,30 @@
 
 // use config::Config;
 use regex::Regex;
-use reqwest::{header::HeaderMap, Response};
+use reqwest::{
+    header::HeaderMap,
+    multipart::{Form, Part},
+    Method, Response,
+};
 
-use crate::libs::{
-    constants::GLOBAL_CONFIG,
-    file::File,
-    log::Log,
-    tools::{gen_timestamp, get_local_cookies, get_user_config},
+use crate::{
+    libs::{
+        constants::GLOBAL_CONFIG,
+        file::File,
+        log::Log,
+        tools::{gen_timestamp, get_local_cookies, get_user_config},
+    },
+    t,
 };
 use serde_json::{json, Value};
 use std::{fs::File as fsFile, io::Write};
+use tracing::debug;
 
 use std::{collections::HashMap, process};
 
+/// 语雀及其图片CDN的域名，只有请求这些域名时才带上登录信息
+const YUQUE_DOMAINS: [&str; 2] = ["yuque.com", "nlark.com"];
+
 #[allow(dead_code)]
 pub fn crawl() {
     //
@@ -33,7 +44,7 @@ pub struct Request {
 
 impl Request {
     // 获取匹配的host，如果是个人就用配置，如果是用户指定的就用指定的
-    fn get_match_host() -> String {
+    pub fn get_match_host() -> String {
         if let Ok(user_config) = get_user_config() {
             // user_config.host
             if user_config.host.is_empty() {
@@ -46,6 +57,13 @@ impl Request {
         }
     }
 
+    /// 开放接口的Token，使用了配置档案时取档案中的
+    pub fn auth_token() -> String {
+        get_user_config()
+            .map(|user_config| user_config.token)
+            .unwrap_or_default()
+    }
+
     fn request_header() -> HeaderMap {
         // 组装header
         let mut headers = HeaderMap::new();
@@ -58,9 +76,7 @@ impl Request {
     /// 返回JSON
     pub async fn get(url: &str) -> Result<HashMap<String, Value>, reqwest::Error> {
         let target_url = Self::get_match_host().clone() + &url;
-        if cfg!(debug_assertions) {
-            println!("GET-> {}", &target_url);
-        }
+        debug!("GET-> {}", &target_url);
         // let res = reqwest::get(&target_url);
         // Ok(res.json::<HashMap<String, String>>().await?)
         let client = reqwest::Client::new();
@@ -68,7 +84,7 @@ impl Request {
         let cookies = get_local_cookies();
 
         if cookies.is_empty() {
-            Log::error("cookies已过期，请清除缓存后重新执行程序");
+            Log::error(t!("request-cookies-expired"));
             process::exit(1)
         }
 
@@ -84,7 +100,7 @@ impl Request {
 
         let resp = res.json::<HashMap<String, Value>>().await?;
         if res_status != 200 {
-            Log::error(format!("接口请求失败：{}", url).as_str());
+            Log::error(&t!("request-failed", url = url));
             Log::error(format!("{:?}", resp).as_str());
         }
         Ok(resp)
@@ -93,9 +109,7 @@ impl Request {
     /// 返回响应文本
     pub async fn get_text(url: &str) -> Result<String, reqwest::Error> {
         let target_url = Self::get_match_host().clone() + &url;
-        if cfg!(debug_assertions) {
-            println!("GET-> {}", &target_url);
-        }
+        debug!("GET-> {}", &target_url);
         // let res = reqwest::get(&target_url);
         // Ok(res.json::<HashMap<String, String>>().await?)
         let client = reqwest::Client::new();
@@ -103,7 +117,7 @@ impl Request {
         let cookies = get_local_cookies();
 
         if cookies.is_empty() {
-            Log::error("cookies已过期，请清除缓存后重新执行程序");
+            Log::error(t!("request-cookies-expired"));
             process::exit(1)
         }
 
@@ -126,9 +140,7 @@ impl Request {
         let header = Self::request_header();
         let target_url = Self::get_match_host().clone() + &url;
         let login_reg = Regex::new("login");
-        if cfg!(debug_assertions) {
-            println!("POST-> {}", &target_url);
-        }
+        debug!("POST-> {}", &target_url);
 
         let res = client
             .post(target_url)
@@ -147,15 +159,132 @@ impl Request {
         let resp = res.json::<HashMap<String, Value>>().await?;
 
         if res_status != 200 {
-            Log::error(format!("接口请求失败：{}", url).as_str());
+            Log::error(&t!("request-failed", url = url));
             Log::error(format!("{:?}", resp).as_str());
         }
 
         Ok(resp)
     }
 
-    /// 下载文件
-    pub async fn download(url: &str, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// 携带cookies和开放接口的Token发送JSON请求，用于通过 /api/v2 创建/更新文档等写操作
+    pub async fn send_json(
+        method: Method,
+        url: &str,
+        body: &Value,
+    ) -> Result<HashMap<String, Value>, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let target_url = Self::get_match_host() + url;
+        debug!("{}-> {}", method, &target_url);
+
+        let res = client
+            .request(method, target_url)
+            .headers(Self::request_header())
+            .header("cookie", get_local_cookies())
+            .header("X-Auth-Token", Self::auth_token())
+            .header("x-requested-with", "XMLHttpRequest")
+            .json(body)
+            .send()
+            .await?;
+
+        let res_status = res.status().as_u16();
+        let resp = res.json::<HashMap<String, Value>>().await?;
+        if res_status != 200 {
+            Log::error(&t!("request-failed", url = url));
+            Log::error(format!("{:?}", resp).as_str());
+        }
+        Ok(resp)
+    }
+
+    /// 上传文件
+    pub async fn upload(
+        url: &str,
+        filename: &str,
+    ) -> Result<HashMap<String, Value>, Box<dyn std::error::Error>> {
+        let client = reqwest::Client::new();
+        let target_url = Self::get_match_host() + url;
+        debug!("UPLOAD-> {} {}", &target_url, filename);
+
+        let file_name = std::path::Path::new(filename)
+            .file_name()
+            .map_or("file".to_string(), |name| {
+                name.to_string_lossy().to_string()
+            });
+        let part = Part::bytes(std::fs::read(filename)?).file_name(file_name);
+
+        let res = client
+            .post(target_url)
+            .header("cookie", get_local_cookies())
+            .header("referer", GLOBAL_CONFIG.yuque_referer.to_string())
+            .header("x-requested-with", "XMLHttpRequest")
+            .multipart(Form::new().part("file", part))
+            .send()
+            .await?;
+
+        let res_status = res.status().as_u16();
+        let resp = res.json::<HashMap<String, Value>>().await?;
+        if res_status != 200 {
+            Log::error(&t!("request-upload-failed", file = filename));
+            Log::error(format!("{:?}", resp).as_str());
+        }
+        Ok(resp)
+    }
+
+    /// 获取二进制内容，如文档中的图片，url可以是完整地址
+    /// 第三方地址不携带cookie和referer
+    pub async fn fetch_bytes(url: &str) -> Result<Vec<u8>, reqwest::Error> {
+        let target_url = if url.starts_with("http") {
+            url.to_string()
+        } else {
+            Self::get_match_host() + url
+        };
+        debug!("GET-> {}", &target_url);
+
+        let mut req = reqwest::Client::new().get(&target_url);
+        if Self::is_yuque_url(&target_url) {
+            req = req
+                .header("cookie", get_local_cookies())
+                .header("referer", GLOBAL_CONFIG.yuque_referer.to_string());
+        }
+        let res = req.send().await?.error_for_status()?;
+        Ok(res.bytes().await?.to_vec())
+    }
+
+    /// 是否为语雀或其图片CDN的地址，包括配置的语雀域名和团队空间域名
+    pub fn is_yuque_url(url: &str) -> bool {
+        let target = match reqwest::Url::parse(url) {
+            Ok(target) => target,
+            Err(_) => return false,
+        };
+        let host = match target.host_str() {
+            Some(host) => host,
+            None => return false,
+        };
+        if YUQUE_DOMAINS
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+        {
+            return true;
+        }
+        [GLOBAL_CONFIG.yuque_host.clone(), Self::get_match_host()]
+            .iter()
+            .filter_map(|yuque_host| reqwest::Url::parse(yuque_host).ok())
+            .any(|yuque_host| yuque_host.origin() == target.origin())
+    }
+
+    /// 获取语雀以外的公开内容，如版本发布信息，不携带cookie
+    pub async fn fetch_public(url: &str) -> Result<Vec<u8>, reqwest::Error> {
+        debug!("GET-> {}", url);
+        let res = reqwest::Client::new()
+            .get(url)
+            .header("User-Agent", format!("ytool/{}", env!("CARGO_PKG_VERSION")))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(res.bytes().await?.to_vec())
+    }
+
+    /// 下载文件，返回下载的字节数
+    pub async fn download(url: &str, filename: &str) -> Result<u64, Box<dyn std::error::Error>> {
         let client = reqwest::Client::new();
         let cookies = get_local_cookies();
 
@@ -174,10 +303,12 @@ impl Request {
         // }
 
         let mut dest = fsFile::create(filename)?;
+        let mut bytes = 0;
         while let Some(chunk) = response.chunk().await? {
             dest.write_all(&chunk)?;
+            bytes += chunk.len() as u64;
         }
-        Ok(())
+        Ok(bytes)
     }
 
     fn save_cookies(res: &Response) {
@@ -202,12 +333,12 @@ impl Request {
         let f = File::new();
 
         if let Err(_) = f.mkdir(&GLOBAL_CONFIG.meta_dir) {
-            Log::error("缓存目录创建失败");
+            Log::error(t!("cache-dir-create-failed"));
             process::exit(1)
         }
 
         if let Err(_) = f.write(&GLOBAL_CONFIG.cookies_file, cookies_info.to_string()) {
-            Log::error("缓存暂存失败");
+            Log::error(t!("cache-write-failed"));
             process::exit(1);
         }
     }
@@ -218,6 +349,19 @@ impl Request {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_yuque_url() {
+        assert!(Request::is_yuque_url("https://cdn.nlark.com/yuque/0/a.png"));
+        assert!(Request::is_yuque_url("https://www.yuque.com/api/docs"));
+        assert!(Request::is_yuque_url("https://yuque.com/a"));
+        assert!(!Request::is_yuque_url("https://example.com/a.png"));
+        assert!(!Request::is_yuque_url(
+            "https://yuque.com.example.com/a.png"
+        ));
+        assert!(!Request::is_yuque_url("https://notyuque.com/a.png"));
+        assert!(!Request::is_yuque_url("a.png"));
+    }
+
     #[tokio::test]
     async fn test_download() {
         if let Ok(_res) = Request::download(
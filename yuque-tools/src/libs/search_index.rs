@@ -0,0 +1,488 @@
+/*
+ * Description: 导出文档的全文索引(倒排索引，中日韩文字按单字+双字切分)
+ * Created: 2026-10-19 00:20:54
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::Error,
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{file::File, tools};
+
+/// BM25参数
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+/// 标题命中时的额外得分
+const TITLE_BOOST: f64 = 2.0;
+/// 摘要长度(字符数)
+const SNIPPET_LEN: usize = 80;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// 已索引的文档
+pub struct IndexedDoc {
+    pub id: u32,
+    pub title: String,
+    /// 文件修改时间(毫秒)，用于增量更新
+    pub modified: u64,
+    /// 分词后的词数量
+    pub length: u32,
+    /// 文档包含的词，删除文档时用于清理倒排表
+    pub terms: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// 全文索引
+pub struct SearchIndex {
+    /// 建立索引的文档目录
+    pub root: String,
+    next_id: u32,
+    /// 相对路径 -> 文档
+    pub docs: HashMap<String, IndexedDoc>,
+    /// 词 -> [(文档id, 词频)]
+    postings: HashMap<String, Vec<(u32, u32)>>,
+}
+
+#[derive(Serialize, Debug)]
+/// 搜索结果
+pub struct GrepHit {
+    /// 相对文档目录的路径
+    pub path: String,
+    pub title: String,
+    pub snippet: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+/// 增量更新结果
+pub struct UpdateSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl SearchIndex {
+    /// 读取索引文件，不存在或解析失败时返回空索引
+    pub fn load(index_file: &str) -> Self {
+        match File::new().read(index_file) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// 保存索引文件
+    pub fn save(&self, index_file: &str) -> Result<(), Error> {
+        let f = File::new();
+        if let Some(parent_dir) = Path::new(index_file).parent() {
+            f.mkdir(&parent_dir.to_string_lossy())?;
+        }
+        f.write(index_file, serde_json::to_string(self).unwrap())
+    }
+
+    /// 读取、增量更新并保存索引
+    /// # Arguments
+    /// * index_file - 索引文件
+    /// * root - 文档目录
+    /// * rebuild - 是否丢弃旧索引重新建立
+    pub fn refresh(index_file: &str, root: &str, rebuild: bool) -> Result<UpdateSummary, Error> {
+        let mut index = if rebuild {
+            Self::default()
+        } else {
+            Self::load(index_file)
+        };
+        let summary = index.update(root)?;
+        index.save(index_file)?;
+        Ok(summary)
+    }
+
+    /// 按文件修改时间增量更新索引，目录变了会重新建立
+    pub fn update(&mut self, root: &str) -> Result<UpdateSummary, Error> {
+        if self.root != root {
+            *self = Self {
+                root: root.to_string(),
+                ..Self::default()
+            };
+        }
+
+        let mut summary = UpdateSummary::default();
+        let mut files: Vec<String> = vec![];
+        Self::collect_markdown_files(Path::new(root), root, &mut files)?;
+
+        let f = File::new();
+        for rel_path in &files {
+            let full_path = format!("{}/{}", root, rel_path);
+            let modified = Self::modified_millis(&full_path);
+
+            let is_new = match self.docs.get(rel_path) {
+                Some(doc) if doc.modified == modified => continue,
+                Some(_) => false,
+                None => true,
+            };
+
+            let content = f.read(&full_path)?;
+            self.remove_doc(rel_path);
+            self.add_doc(rel_path, &Self::doc_title(rel_path), &content, modified);
+
+            if is_new {
+                summary.added += 1;
+            } else {
+                summary.updated += 1;
+            }
+        }
+
+        // 已经不存在的文件
+        let exists: HashSet<&String> = files.iter().collect();
+        let removed: Vec<String> = self
+            .docs
+            .keys()
+            .filter(|path| !exists.contains(path))
+            .cloned()
+            .collect();
+        for path in &removed {
+            self.remove_doc(path);
+        }
+        summary.removed = removed.len();
+
+        Ok(summary)
+    }
+
+    /// 添加文档
+    pub fn add_doc(&mut self, path: &str, title: &str, content: &str, modified: u64) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        // 标题也参与索引
+        let tokens = tokenize(&format!("{}\n{}", title, content));
+        let mut freqs: HashMap<String, u32> = HashMap::new();
+        for token in &tokens {
+            *freqs.entry(token.to_string()).or_insert(0) += 1;
+        }
+
+        for (term, freq) in &freqs {
+            self.postings
+                .entry(term.to_string())
+                .or_insert(vec![])
+                .push((id, *freq));
+        }
+
+        self.docs.insert(
+            path.to_string(),
+            IndexedDoc {
+                id,
+                title: title.to_string(),
+                modified,
+                length: tokens.len() as u32,
+                terms: freqs.into_keys().collect(),
+            },
+        );
+    }
+
+    /// 删除文档
+    pub fn remove_doc(&mut self, path: &str) {
+        if let Some(doc) = self.docs.remove(path) {
+            for term in &doc.terms {
+                if let Some(list) = self.postings.get_mut(term) {
+                    list.retain(|(id, _)| *id != doc.id);
+                    if list.is_empty() {
+                        self.postings.remove(term);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 按BM25排序搜索，标题命中的排在前面，返回带摘要的结果
+    pub fn search(&self, query: &str, limit: usize) -> Vec<GrepHit> {
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() || self.docs.is_empty() {
+            return vec![];
+        }
+
+        let doc_count = self.docs.len() as f64;
+        let lengths: HashMap<u32, f64> = self
+            .docs
+            .values()
+            .map(|doc| (doc.id, doc.length as f64))
+            .collect();
+        let avg_length = lengths.values().sum::<f64>() / doc_count;
+
+        let mut scores: HashMap<u32, f64> = HashMap::new();
+        for term in &terms {
+            if let Some(list) = self.postings.get(term) {
+                let df = list.len() as f64;
+                let idf = (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln();
+                for (id, freq) in list {
+                    let length = *lengths.get(id).unwrap_or(&avg_length);
+                    let tf = *freq as f64;
+                    let score = idf * tf * (BM25_K1 + 1.0)
+                        / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length / avg_length.max(1.0)));
+                    *scores.entry(*id).or_insert(0.0) += score;
+                }
+            }
+        }
+
+        let query_lower = query.trim().to_lowercase();
+        let mut hits: Vec<GrepHit> = self
+            .docs
+            .iter()
+            .filter_map(|(path, doc)| {
+                scores.get(&doc.id).map(|score| {
+                    let boost = if doc.title.to_lowercase().contains(&query_lower) {
+                        TITLE_BOOST
+                    } else {
+                        0.0
+                    };
+                    GrepHit {
+                        path: path.to_string(),
+                        title: doc.title.to_string(),
+                        snippet: "".to_string(),
+                        score: score + boost,
+                    }
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap()
+                .then(a.path.cmp(&b.path))
+        });
+        hits.truncate(limit);
+
+        let f = File::new();
+        for hit in hits.iter_mut() {
+            if let Ok(content) = f.read(&format!("{}/{}", self.root, hit.path)) {
+                hit.snippet = make_snippet(&content, query, &terms);
+            }
+        }
+        hits
+    }
+
+    /// 文档标题，index.md/README.md取所在目录名
+    fn doc_title(rel_path: &str) -> String {
+        let path = Path::new(rel_path);
+        let stem = path
+            .file_stem()
+            .map_or("".to_string(), |s| s.to_string_lossy().to_string());
+        if stem == "index" || stem == "README" {
+            if let Some(dir_name) = path.parent().and_then(|p| p.file_name()) {
+                return dir_name.to_string_lossy().to_string();
+            }
+        }
+        stem
+    }
+
+    fn modified_millis(full_path: &str) -> u64 {
+        fs::metadata(full_path)
+            .and_then(|meta| meta.modified())
+            .map(|time| time.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    /// 递归收集目录下的md文件，返回相对路径
+    fn collect_markdown_files(
+        dir: &Path,
+        root: &str,
+        files: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        if !dir.exists() {
+            return Ok(());
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            if name.starts_with(".") {
+                continue;
+            }
+            if path.is_dir() {
+                Self::collect_markdown_files(&path, root, files)?;
+            } else if name.ends_with(".md") {
+                let rel_path = path
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace("\\", "/");
+                // 导出报告不参与索引
+                if !tools::is_report_file(&rel_path) {
+                    files.push(rel_path);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 是否是中日韩文字
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+}
+
+/// 分词：英文数字按单词切分并转小写，中日韩文字输出单字和相邻双字
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = vec![];
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = vec![];
+
+    fn flush_word(word: &mut String, tokens: &mut Vec<String>) {
+        if !word.is_empty() {
+            tokens.push(word.to_lowercase());
+            word.clear();
+        }
+    }
+
+    fn flush_cjk(cjk_run: &mut Vec<char>, tokens: &mut Vec<String>) {
+        for (i, c) in cjk_run.iter().enumerate() {
+            tokens.push(c.to_string());
+            if let Some(next) = cjk_run.get(i + 1) {
+                tokens.push(format!("{}{}", c, next));
+            }
+        }
+        cjk_run.clear();
+    }
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.push(c);
+        } else {
+            flush_word(&mut word, &mut tokens);
+            flush_cjk(&mut cjk_run, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    flush_cjk(&mut cjk_run, &mut tokens);
+
+    tokens
+}
+
+/// 生成摘要，用 minigrep 逐行匹配(忽略大小写)，
+/// 优先取包含完整查询的行，否则取包含任意词的行
+pub fn make_snippet(content: &str, query: &str, terms: &[String]) -> String {
+    let query = query.trim().to_lowercase();
+
+    let line = minigrep::search_case_insensitive(&query, content)
+        .into_iter()
+        .next()
+        .or_else(|| {
+            content.lines().find(|line| {
+                terms
+                    .iter()
+                    .any(|term| !minigrep::search_case_insensitive(term, line).is_empty())
+            })
+        })
+        .unwrap_or("")
+        .trim();
+
+    // 以命中位置为中心截取
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= SNIPPET_LEN {
+        return line.to_string();
+    }
+    let lower_line = line.to_lowercase();
+    let hit_at = lower_line
+        .find(&query)
+        .map_or(0, |byte_index| lower_line[..byte_index].chars().count());
+    let start = hit_at.saturating_sub(SNIPPET_LEN / 4);
+    let end = (start + SNIPPET_LEN).min(chars.len());
+    let start = end.saturating_sub(SNIPPET_LEN);
+
+    format!(
+        "{}{}{}",
+        if start > 0 { "..." } else { "" },
+        chars[start..end].iter().collect::<String>(),
+        if end < chars.len() { "..." } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Rust入门, hello"),
+            vec!["rust", "入", "入门", "门", "hello"]
+        );
+        assert!(tokenize("  ,. ").is_empty());
+    }
+
+    #[test]
+    fn test_add_search_remove() {
+        let mut index = SearchIndex::default();
+        index.add_doc("a/Rust入门.md", "Rust入门", "所有权和借用\nrust is fast", 1);
+        index.add_doc("b/随笔.md", "随笔", "今天学习了借用检查", 1);
+
+        let hits = index.search("借用", 10);
+        assert_eq!(hits.len(), 2);
+
+        let hits = index.search("rust", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "Rust入门");
+
+        index.remove_doc("a/Rust入门.md");
+        assert!(index.search("rust", 10).is_empty());
+        assert!(!index.postings.contains_key("所有"));
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let f = File::new();
+        let root = "./dev/index-docs";
+        let _ = f.rmdir(root);
+        let _ = f.mkdir(&format!("{}/book/分组", root));
+        let _ = f.write(&format!("{}/book/文档.md", root), "第一篇文档".to_string());
+        let _ = f.write(
+            &format!("{}/book/分组/index.md", root),
+            "分组索引".to_string(),
+        );
+        let _ = f.write(&format!("{}/导出报告.md", root), "# 导出报告".to_string());
+
+        let mut index = SearchIndex::default();
+        let summary = index.update(root).unwrap();
+        assert_eq!(summary.added, 2);
+        assert_eq!(index.docs["book/分组/index.md"].title, "分组");
+
+        // 没有变化时不重复索引
+        assert_eq!(index.update(root).unwrap(), UpdateSummary::default());
+
+        let _ = f.remove(&format!("{}/book/文档.md", root));
+        assert_eq!(index.update(root).unwrap().removed, 1);
+
+        let hits = index.search("分组", 10);
+        assert_eq!(hits[0].snippet, "分组索引");
+
+        let _ = f.rmdir(root);
+    }
+
+    #[test]
+    fn test_make_snippet() {
+        let content = "标题\n\n这里提到了 Rust 语言\n其他内容";
+        let terms = tokenize("rust");
+        assert_eq!(
+            make_snippet(content, "rust", &terms),
+            "这里提到了 Rust 语言"
+        );
+
+        let long_line = format!("{}关键字{}", "前".repeat(100), "后".repeat(100));
+        let snippet = make_snippet(&long_line, "关键字", &tokenize("关键字"));
+        assert!(snippet.starts_with("...") && snippet.ends_with("..."));
+        assert!(snippet.contains("关键字"));
+    }
+}
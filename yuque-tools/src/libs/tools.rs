@@ -5,13 +5,29 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
+use lazy_static::lazy_static;
+use openssl::sha::sha256;
+use regex::Regex;
+
 use super::{
     constants::schema::{
         cache_book, LocalCacheUserInfo, LocalCookiesInfo, UserCliConfig, YuqueLoginUserInfo,
     },
     constants::GLOBAL_CONFIG,
     file::File,
+    i18n,
 };
+
+lazy_static! {
+    /// 不能用于文件名的字符
+    static ref INVALID_FILE_NAME_CHARS: Regex = Regex::new(r#"[<>:"\/\\|?*\x00-\x1F]"#).unwrap();
+}
+
+/// 是否为导出报告，报告按语言命名，任一语言的都算
+pub fn is_report_file(name: &str) -> bool {
+    i18n::all("report-file").contains(&name)
+}
+
 /// 生成当前时间戳
 pub fn gen_timestamp() -> u128 {
     SystemTime::now()
@@ -36,12 +52,12 @@ pub fn get_local_cookies() -> String {
     }
 }
 
-/// 获取用户的CLI配置信息
+/// 获取用户的CLI配置信息，使用了配置档案时以档案中的账号和域名为准
 pub fn get_user_config() -> Result<UserCliConfig, &'static str> {
     // println!("获取本地用户信息");
     let user_cli_config = &GLOBAL_CONFIG.user_cli_config_file;
 
-    if Path::new(&user_cli_config).exists() {
+    let config = if Path::new(&user_cli_config).exists() {
         match fsFile::open(user_cli_config) {
             Ok(mut f) => {
                 let mut data = String::new();
@@ -54,6 +70,20 @@ pub fn get_user_config() -> Result<UserCliConfig, &'static str> {
         }
     } else {
         Err("配置文件不存在")
+    };
+
+    match &GLOBAL_CONFIG.profile {
+        Some(profile) => {
+            // 档案之外的导出选项仍然沿用配置文件，没有配置文件时使用默认值
+            let mut config = config.unwrap_or_else(|_| serde_json::from_str("{}").unwrap());
+            config.username = profile.username.to_string();
+            config.password = profile.password.to_string();
+            config.host = profile.host.to_string();
+            config.token = profile.token.to_string();
+            config.output = GLOBAL_CONFIG.target_output_dir.to_string();
+            Ok(config)
+        }
+        None => config,
     }
 }
 
@@ -127,3 +157,80 @@ pub fn is_personal() -> bool {
     }
     true
 }
+
+/// 终端中的显示宽度，中文等宽字符占两列
+pub fn display_width(str: &str) -> usize {
+    str.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+/// 按列对齐的文本表格，第一列左对齐，其余列右对齐，列之间空两格
+/// # Arguments
+/// * rows - 表格内容，第一行为表头
+pub fn format_table(rows: &[Vec<String>]) -> Vec<String> {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| display_width(cell))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(column, cell)| {
+                    let padding = " ".repeat(widths[column] - display_width(cell));
+                    if column == 0 {
+                        format!("{}{}", cell, padding)
+                    } else {
+                        format!("{}{}", padding, cell)
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// 替换名称中不能用于文件名的字符
+/// # Arguments
+/// * name - 文档标题、知识库名称等
+/// * replacement - 替换成的内容，为空时直接去掉
+pub fn sanitize_file_name(name: &str, replacement: &str) -> String {
+    INVALID_FILE_NAME_CHARS
+        .replace_all(name, replacement)
+        .to_string()
+}
+
+/// 内容的sha256，十六进制小写
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    sha256(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("a/b:c?.md", ""), "abc.md");
+        assert_eq!(sanitize_file_name("a|b", "_"), "a_b");
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
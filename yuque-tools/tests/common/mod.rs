@@ -0,0 +1,241 @@
+/*
+ * Description: 离线测试用的语雀模拟服务，按录制的数据响应各接口
+ * Created: 2026-10-19 00:57:09
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Output, Stdio},
+};
+
+use serde_json::Value;
+use tempfile::TempDir;
+use tokio::process::{Child, Command};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// 覆盖语雀域名的环境变量，和 load_config 中的保持一致
+const YUQUE_HOST_ENV: &str = "YTOOL_YUQUE_HOST";
+
+/// 读取录制的接口数据
+pub fn fixture(name: &str) -> String {
+    let file = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    fs::read_to_string(&file).unwrap_or_else(|_| panic!("缺少测试数据：{}", file))
+}
+
+fn fixture_json(name: &str) -> Value {
+    serde_json::from_str(&fixture(name)).unwrap()
+}
+
+/// 知识库页面，目录数据和语雀一样编码在 window.appData 中
+fn toc_page(name: &str) -> String {
+    let encoded: String = fixture(name)
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() {
+                (byte as char).to_string()
+            } else {
+                format!("%{:02X}", byte)
+            }
+        })
+        .collect();
+    format!(
+        "<html><body><script>window.appData = JSON.parse(decodeURIComponent(\"{}\"));</script></body></html>",
+        encoded
+    )
+}
+
+pub struct MockYuque {
+    pub server: MockServer,
+}
+
+impl MockYuque {
+    /// 启动模拟服务并挂载所有接口
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/mobile_app/accounts/login"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("set-cookie", "_yuque_session=mock; path=/")
+                    .set_body_json(fixture_json("login.json")),
+            )
+            .mount(&server)
+            .await;
+
+        for (api, name) in [
+            ("/api/mine/book_stacks", "book_stacks.json"),
+            ("/api/mine/user_books", "user_books.json"),
+            ("/api/mine/raw_collab_books", "collab_books.json"),
+            ("/api/mine/group_quick_links", "group_quick_links.json"),
+            ("/api/groups/100/books", "group_books.json"),
+        ] {
+            Self::mount_json(&server, api, name).await;
+        }
+
+        Mock::given(method("GET"))
+            .and(path("/api/docs"))
+            .and(query_param("book_id", "11"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(fixture_json("rust_book_docs.json")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/docs"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": [] })),
+            )
+            .mount(&server)
+            .await;
+
+        for (book, name) in [
+            ("/mock-user/rust-book", "rust_book_toc.json"),
+            ("/mock-team/team-book", "team_book_toc.json"),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(book))
+                .respond_with(ResponseTemplate::new(200).set_body_string(toc_page(name)))
+                .mount(&server)
+                .await;
+        }
+
+        for (doc, name) in [
+            ("/mock-user/rust-book/ownership/markdown", "ownership.md"),
+            ("/mock-user/rust-book/lifetime/markdown", "lifetime.md"),
+            ("/mock-team/team-book/onboarding/markdown", "onboarding.md"),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(doc))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_string(fixture(&format!("markdown/{}", name))),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        Mock::given(method("GET"))
+            .and(path("/api/resources"))
+            .and(query_param("book_id", "201"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture_json("resources.json")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/r/resources/download/301"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(Self::resource_bytes()))
+            .mount(&server)
+            .await;
+
+        MockYuque { server }
+    }
+
+    /// 资源下载接口返回的内容
+    pub fn resource_bytes() -> Vec<u8> {
+        b"\x89PNG\r\n\x1a\nmock".to_vec()
+    }
+
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    async fn mount_json(server: &MockServer, api: &str, name: &str) {
+        Mock::given(method("GET"))
+            .and(path(api))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture_json(name)))
+            .mount(server)
+            .await;
+    }
+}
+
+/// 在临时目录中运行ytool，每个用例互不影响
+pub struct Workspace {
+    dir: TempDir,
+    host: String,
+}
+
+impl Workspace {
+    /// 创建工作目录并写入配置文件
+    /// # Arguments
+    /// * mock - 模拟服务
+    /// * config - yuque.config.json 的内容
+    pub fn new(mock: &MockYuque, config: Value) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("yuque.config.json"),
+            serde_json::to_string_pretty(&config).unwrap(),
+        )
+        .unwrap();
+        Workspace {
+            dir,
+            host: mock.uri(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn file(&self, relative: &str) -> PathBuf {
+        self.dir.path().join(relative)
+    }
+
+    pub fn read(&self, relative: &str) -> String {
+        fs::read_to_string(self.file(relative))
+            .unwrap_or_else(|_| panic!("未生成文件：{}", relative))
+    }
+
+    /// 执行ytool命令，语雀域名指向模拟服务
+    pub async fn run(&self, args: &[&str]) -> Output {
+        self.run_with_env(args, &[]).await
+    }
+
+    /// 在后台启动常驻的ytool命令，如 webhook，测试结束时退出
+    pub fn spawn(&self, args: &[&str]) -> Child {
+        Command::new(env!("CARGO_BIN_EXE_ytool"))
+            .args(args)
+            .current_dir(self.dir.path())
+            .env(YUQUE_HOST_ENV, &self.host)
+            .env("LANG", "zh_CN.UTF-8")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap()
+    }
+
+    /// 执行ytool命令，并设置额外的环境变量
+    pub async fn run_with_env(&self, args: &[&str], envs: &[(&str, &str)]) -> Output {
+        let output = Command::new(env!("CARGO_BIN_EXE_ytool"))
+            .args(args)
+            .current_dir(self.dir.path())
+            .env(YUQUE_HOST_ENV, &self.host)
+            // 提示信息的语言跟随环境变量，固定为中文，需要时由envs覆盖
+            .env("LANG", "zh_CN.UTF-8")
+            .env_remove("LC_ALL")
+            .env_remove("LC_MESSAGES")
+            .envs(envs.iter().copied())
+            .output()
+            .await
+            .unwrap();
+        if !output.status.success() {
+            println!(
+                "ytool {:?} 执行失败：\n{}\n{}",
+                args,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        output
+    }
+}
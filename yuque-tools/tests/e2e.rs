@@ -0,0 +1,557 @@
+/*
+ * Description: 基于模拟服务的端到端测试，不访问真实的语雀
+ * Created: 2026-10-19 00:57:09
+ * Author: vannvan
+ * Email : adoerww@gmail.com
+ * -----
+ * Copyright (c) https://github.com/vannvan
+ */
+
+mod common;
+
+use common::{fixture, MockYuque, Workspace};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_pull_personal_books() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+
+    let output = workspace.run(&["pull"]).await;
+    assert!(output.status.success());
+    // 非终端环境下逐行输出进度，最后输出汇总表
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[2/2] 完成"));
+    assert!(stdout.contains("文档下载汇总"));
+
+    assert_eq!(
+        workspace.read("docs/Rust笔记/基础/所有权.md"),
+        fixture("markdown/ownership.md")
+    );
+    assert_eq!(
+        workspace.read("docs/Rust笔记/基础/生命周期.md"),
+        fixture("markdown/lifetime.md")
+    );
+    // 草稿不导出，只记录在报告中
+    assert!(!workspace.file("docs/Rust笔记/草稿.md").exists());
+    let report = workspace.read("docs/导出报告.md");
+    assert!(report.contains("🙈 Hidden Rust笔记/草稿"));
+    assert!(report.contains("🌈 Success Rust笔记/基础/所有权"));
+
+    // 文档列表接口的更新时间和最后编辑者写入了缓存
+    let books_info = workspace.read(".meta/books_info.json");
+    assert!(books_info.contains("2023-09-01T08:00:00.000Z"));
+    assert!(books_info.contains("\"last_editor\":\"editor\""));
+}
+
+#[tokio::test]
+async fn test_pull_skips_existing_docs() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+    assert!(workspace.run(&["pull"]).await.status.success());
+
+    // 第二次使用缓存的登录状态，本地已存在的文档跳过
+    std::fs::write(workspace.file("docs/Rust笔记/基础/所有权.md"), "本地修改").unwrap();
+    assert!(workspace.run(&["pull"]).await.status.success());
+    assert_eq!(workspace.read("docs/Rust笔记/基础/所有权.md"), "本地修改");
+    assert!(workspace
+        .read("docs/导出报告.md")
+        .contains("🌈 Skip Rust笔记/基础/所有权"));
+}
+
+#[tokio::test]
+async fn test_pull_verbose_log() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+
+    let output = workspace
+        .run(&[
+            "pull",
+            "-v",
+            "--log-file",
+            "ytool.log",
+            "--dump-dir",
+            "dump",
+        ])
+        .await;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("GET-> "));
+    assert!(!stdout.contains("secret"));
+
+    // 日志文件每行一条JSON，文档的日志带上所在的知识库和路径
+    let log = workspace.read("ytool.log");
+    assert!(!log.contains("secret"));
+    assert!(!log.contains("_yuque_session=mock"));
+    let doc_log = log
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .find(|line| line["fields"]["message"] == "开始导出")
+        .unwrap();
+    assert_eq!(doc_log["span"]["book"], "rust-book");
+
+    assert!(workspace.file("dump/tree-doc.json").exists());
+    assert!(workspace.file("dump/flat-doc.json").exists());
+}
+
+#[tokio::test]
+async fn test_stats() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+    assert!(workspace.run(&["pull"]).await.status.success());
+
+    let output = workspace
+        .run(&["stats", "Rust笔记", "--format", "json"])
+        .await;
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats[0]["name"], "Rust笔记");
+    assert_eq!(stats[0]["exported_count"], 2);
+
+    let output = workspace.run(&["stats", "--format", "csv"]).await;
+    let csv = String::from_utf8_lossy(&output.stdout);
+    assert!(csv.starts_with("name,slug,doc_count"));
+    assert!(csv.contains("Rust笔记,rust-book,"));
+}
+
+#[tokio::test]
+async fn test_completions() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+
+    let output = workspace.run(&["completions", "bash"]).await;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("COMPLETE=\"bash\""));
+
+    // 补全时从缓存中读取知识库名称
+    assert!(workspace.run(&["pull"]).await.status.success());
+    let output = workspace
+        .run_with_env(&["--", "ytool", "bundle", ""], &[("COMPLETE", "fish")])
+        .await;
+    let candidates = String::from_utf8_lossy(&output.stdout);
+    assert!(candidates.contains("Rust笔记\trust-book"));
+}
+
+#[tokio::test]
+async fn test_pull_team_books() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "host": mock.uri(),
+            "toc_range": ["团队手册"]
+        }),
+    );
+
+    let output = workspace.run(&["pull"]).await;
+    assert!(output.status.success());
+    assert_eq!(
+        workspace.read("docs/团队手册/入职指南.md"),
+        fixture("markdown/onboarding.md")
+    );
+}
+
+#[tokio::test]
+async fn test_pull_obsidian_attachments() {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    let mock = MockYuque::start().await;
+    // 第三方图床，不应收到语雀的登录信息
+    let third_party = MockServer::start().await;
+    for server in [&mock.server, &third_party] {
+        Mock::given(method("GET"))
+            .and(path("/img/a.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(MockYuque::resource_bytes()))
+            .mount(server)
+            .await;
+    }
+    let third_party_url = third_party.uri().replace("127.0.0.1", "localhost");
+    Mock::given(method("GET"))
+        .and(path("/mock-user/rust-book/ownership/markdown"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            "![语雀]({}/img/a.png)\n![图床]({}/img/a.png)\n",
+            mock.uri(),
+            third_party_url
+        )))
+        .with_priority(1)
+        .mount(&mock.server)
+        .await;
+
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "target": "obsidian",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+    let output = workspace.run(&["pull"]).await;
+    assert!(output.status.success());
+    let content = workspace.read("docs/Rust笔记/基础/所有权.md");
+    assert_eq!(content.matches("![[a-").count(), 2);
+
+    let image_requests = |requests: Vec<wiremock::Request>| -> Vec<Vec<String>> {
+        requests
+            .into_iter()
+            .filter(|request| request.url.path() == "/img/a.png")
+            .map(|request| {
+                request
+                    .headers
+                    .keys()
+                    .map(|name| name.as_str().to_lowercase())
+                    .collect()
+            })
+            .collect()
+    };
+    // 语雀的图片带上登录信息，第三方的不带
+    let yuque = image_requests(mock.server.received_requests().await.unwrap());
+    assert_eq!(yuque.len(), 1);
+    assert!(yuque[0].contains(&"cookie".to_string()));
+    let others = image_requests(third_party.received_requests().await.unwrap());
+    assert_eq!(others.len(), 1);
+    assert!(!others[0].contains(&"cookie".to_string()));
+    assert!(!others[0].contains(&"referer".to_string()));
+}
+
+#[tokio::test]
+async fn test_pull_dry_run() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+
+    // 没有缓存时不登录，也不写入任何缓存
+    let output = workspace.run(&["pull", "--dry-run"]).await;
+    assert!(!output.status.success());
+    assert!(!workspace.file(".meta").exists());
+
+    // 根据已缓存的知识库信息生成计划，不修改输出目录和缓存
+    assert!(workspace.run(&["pull"]).await.status.success());
+    std::fs::remove_dir_all(workspace.file("docs")).unwrap();
+    let books_info = workspace.read(".meta/books_info.json");
+    let output = workspace.run(&["pull", "--dry-run"]).await;
+    assert!(output.status.success());
+    assert!(!workspace.file("docs").exists());
+    assert_eq!(workspace.read(".meta/books_info.json"), books_info);
+}
+
+#[tokio::test]
+async fn test_pull_archive() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+
+    let output = workspace
+        .run(&["pull", "--archive", "backup/docs.zip"])
+        .await;
+    assert!(output.status.success());
+    // 文档只写入归档，报告在归档旁边
+    assert!(!workspace.file("docs").exists());
+    assert!(workspace
+        .read("backup/导出报告.md")
+        .contains("🌈 Success Rust笔记/基础/所有权"));
+
+    let file = std::fs::File::open(workspace.file("backup/docs.zip")).unwrap();
+    let mut archive = zip::ZipArchive::new(file).unwrap();
+    let mut read_entry = |name: &str| {
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name(name).unwrap(), &mut content).unwrap();
+        content
+    };
+    assert_eq!(
+        read_entry("Rust笔记/基础/所有权.md"),
+        fixture("markdown/ownership.md")
+    );
+    let manifest: serde_json::Value = serde_json::from_str(&read_entry("manifest.json")).unwrap();
+    let entries = manifest["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(entries
+        .iter()
+        .all(|entry| entry["hash"].as_str().unwrap().len() == 64));
+}
+
+#[tokio::test]
+async fn test_pull_encrypted() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+    let identity = age::x25519::Identity::generate();
+    let recipient = identity.to_public().to_string();
+    std::fs::write(
+        workspace.file("key.txt"),
+        age::secrecy::ExposeSecret::expose_secret(&identity.to_string()),
+    )
+    .unwrap();
+
+    let output = workspace.run(&["pull", "--recipient", &recipient]).await;
+    assert!(output.status.success());
+    // 只有加密后的文档和报告
+    assert!(workspace.file("docs/Rust笔记/基础/所有权.md.age").exists());
+    assert!(!workspace.file("docs/Rust笔记/基础/所有权.md").exists());
+    assert!(!workspace.file("docs/导出报告.md").exists());
+
+    let output = workspace
+        .run(&["decrypt", "docs", "--identity", "key.txt", "--remove"])
+        .await;
+    assert!(output.status.success());
+    assert_eq!(
+        workspace.read("docs/Rust笔记/基础/所有权.md"),
+        fixture("markdown/ownership.md")
+    );
+    assert!(workspace
+        .read("docs/导出报告.md")
+        .contains("🌈 Success Rust笔记/基础/所有权"));
+    assert!(!workspace.file("docs/Rust笔记/基础/所有权.md.age").exists());
+
+    // 没有私钥时解密失败
+    let output = workspace
+        .run(&[
+            "pull",
+            "--recipient",
+            &recipient,
+            "--archive",
+            "out.tar.zst",
+        ])
+        .await;
+    assert!(output.status.success());
+    assert!(workspace.file("out.tar.zst.age").exists());
+    let output = workspace.run(&["decrypt", "out.tar.zst.age"]).await;
+    assert!(!output.status.success());
+    assert!(!workspace.file("out.tar.zst").exists());
+}
+
+#[tokio::test]
+async fn test_webhook_exports_single_doc() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+    assert!(workspace.run(&["pull"]).await.status.success());
+    std::fs::write(workspace.file("docs/Rust笔记/基础/所有权.md"), "本地修改").unwrap();
+    std::fs::write(workspace.file("docs/Rust笔记/基础/生命周期.md"), "本地修改").unwrap();
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let listen = format!("127.0.0.1:{}", port);
+    let _server = workspace.spawn(&["webhook", "--listen", &listen, "--debounce", "1s"]);
+
+    let payload = json!({
+        "data": {
+            "webhook_subject_type": "update",
+            "path": "mock-user/rust-book/ownership",
+            "title": "所有权"
+        }
+    });
+    let client = reqwest::Client::new();
+    let mut accepted = false;
+    for _ in 0..50 {
+        let resp = client
+            .post(format!("http://{}/webhook", listen))
+            .json(&payload)
+            .send()
+            .await;
+        if resp.is_ok_and(|resp| resp.status().as_u16() == 202) {
+            accepted = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(accepted);
+
+    // 只重新导出变更的文档，其余文档不动
+    let mut exported = false;
+    for _ in 0..100 {
+        if workspace.read("docs/Rust笔记/基础/所有权.md") == fixture("markdown/ownership.md")
+        {
+            exported = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(exported);
+    assert_eq!(workspace.read("docs/Rust笔记/基础/生命周期.md"), "本地修改");
+}
+
+#[tokio::test]
+async fn test_push_sends_token() {
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, ResponseTemplate,
+    };
+
+    let mock = MockYuque::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/repos/mock-user/rust-book/docs"))
+        .and(header("X-Auth-Token", "open-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": { "id": 7 } })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v2/repos/mock-user/rust-book/toc"))
+        .and(header("X-Auth-Token", "open-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            json!({ "data": [{ "uuid": "node-7", "doc_id": 7, "type": "DOC", "title": "笔记" }] }),
+        ))
+        .mount(&mock.server)
+        .await;
+
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret"
+        }),
+    );
+    std::fs::create_dir_all(workspace.file("notes")).unwrap();
+    std::fs::write(workspace.file("notes/笔记.md"), "# 笔记").unwrap();
+
+    // 没有Token时不推送
+    let output = workspace
+        .run(&["push", "notes", "--book", "mock-user/rust-book"])
+        .await;
+    assert!(!output.status.success());
+
+    std::fs::write(
+        workspace.file("yuque.config.json"),
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "token": "open-token"
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let output = workspace
+        .run(&["push", "notes", "--book", "mock-user/rust-book"])
+        .await;
+    assert!(output.status.success());
+    assert!(workspace.read(".meta/push_map.json").contains("node-7"));
+}
+
+#[tokio::test]
+async fn test_grd() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "host": mock.uri()
+        }),
+    );
+
+    let output = workspace.run(&["grd"]).await;
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read(workspace.file("resources/资源库/设计稿.png")).unwrap(),
+        MockYuque::resource_bytes()
+    );
+}
+
+#[tokio::test]
+async fn test_down() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(&mock, json!({}));
+
+    let output = workspace.run(&["down"]).await;
+    assert!(output.status.success());
+}
+
+#[tokio::test]
+async fn test_lang() {
+    let mock = MockYuque::start().await;
+    let workspace = Workspace::new(
+        &mock,
+        json!({
+            "username": "mock",
+            "password": "secret",
+            "toc_range": ["Rust笔记"]
+        }),
+    );
+
+    // 没有缓存时的错误提示跟随LANG
+    let output = workspace
+        .run_with_env(&["ls"], &[("LANG", "en_US.UTF-8")])
+        .await;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No cached books"));
+
+    // --lang 优先于LANG，帮助信息也会切换
+    let output = workspace.run(&["--lang", "en-US", "pull", "--help"]).await;
+    let help = String::from_utf8_lossy(&output.stdout);
+    assert!(help.contains("Fetch books"));
+    assert!(help.contains("Only print the export plan"));
+
+    let output = workspace.run(&["pull", "--lang=en-US"]).await;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Export finished"));
+
+    let output = workspace.run(&["ls"]).await;
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Rust笔记"));
+}